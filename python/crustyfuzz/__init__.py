@@ -5,6 +5,7 @@ import sys
 from .crustyfuzz import *  # noqa: F403
 
 sys.modules["crustyfuzz.fuzz"] = crustyfuzz.fuzz
+sys.modules["crustyfuzz.phonetic"] = crustyfuzz.phonetic
 sys.modules["crustyfuzz.process"] = crustyfuzz.process
 
 __doc__ = crustyfuzz.__doc__
@@ -20,20 +20,32 @@ from ._initialize import (
     ScoreAlignment,
     # scorers
     damerau_levenshtein,
+    gotoh,
     hamming,
     indel,
     jaro,
     jaro_winkler,
     lcs_seq,
+    lcs_str,
     levenshtein,
     osa,
     postfix,
     prefix,
+    ratcliff_obershelp,
+    smith_waterman,
+    jaccard,
+    sorensen_dice,
+    cosine,
+    overlap,
+    editex,
+    mra,
 )
 
+sys.modules["crustyfuzz.distance.gotoh"] = gotoh
 sys.modules["crustyfuzz.distance.hamming"] = hamming
 sys.modules["crustyfuzz.distance.lcs_seq"] = lcs_seq
 sys.modules["crustyfuzz.distance.indel"] = indel
+sys.modules["crustyfuzz.distance.lcs_str"] = lcs_str
 sys.modules["crustyfuzz.distance.levenshtein"] = levenshtein
 sys.modules["crustyfuzz.distance.damerau_levenshtein"] = damerau_levenshtein
 sys.modules["crustyfuzz.distance.jaro"] = jaro
@@ -41,3 +53,11 @@ sys.modules["crustyfuzz.distance.jaro_winkler"] = jaro_winkler
 sys.modules["crustyfuzz.distance.osa"] = osa
 sys.modules["crustyfuzz.distance.postfix"] = postfix
 sys.modules["crustyfuzz.distance.prefix"] = prefix
+sys.modules["crustyfuzz.distance.ratcliff_obershelp"] = ratcliff_obershelp
+sys.modules["crustyfuzz.distance.smith_waterman"] = smith_waterman
+sys.modules["crustyfuzz.distance.jaccard"] = jaccard
+sys.modules["crustyfuzz.distance.sorensen_dice"] = sorensen_dice
+sys.modules["crustyfuzz.distance.cosine"] = cosine
+sys.modules["crustyfuzz.distance.overlap"] = overlap
+sys.modules["crustyfuzz.distance.editex"] = editex
+sys.modules["crustyfuzz.distance.mra"] = mra
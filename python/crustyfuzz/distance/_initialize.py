@@ -6,16 +6,26 @@ __all__ = (
     "Opcodes",
     "ScoreAlignment",
     # scorers
+    "cosine",
     "damerau_levenshtein",
+    "editex",
+    "gotoh",
     "hamming",
     "indel",
+    "jaccard",
     "jaro",
     "jaro_winkler",
     "lcs_seq",
+    "lcs_str",
     "levenshtein",
+    "mra",
     "osa",
+    "overlap",
     "postfix",
     "prefix",
+    "ratcliff_obershelp",
+    "smith_waterman",
+    "sorensen_dice",
 )
 
 from ..crustyfuzz import distance
@@ -28,9 +38,11 @@ Opcode = distance.Opcode
 Opcodes = distance.Opcodes
 
 # scorers
+gotoh = distance.gotoh
 hamming = distance.hamming
 lcs_seq = distance.lcs_seq
 indel = distance.indel
+lcs_str = distance.lcs_str
 levenshtein = distance.levenshtein
 damerau_levenshtein = distance.damerau_levenshtein
 jaro = distance.jaro
@@ -38,3 +50,11 @@ jaro_winkler = distance.jaro_winkler
 osa = distance.osa
 postfix = distance.postfix
 prefix = distance.prefix
+ratcliff_obershelp = distance.ratcliff_obershelp
+smith_waterman = distance.smith_waterman
+jaccard = distance.jaccard
+sorensen_dice = distance.sorensen_dice
+cosine = distance.cosine
+overlap = distance.overlap
+editex = distance.editex
+mra = distance.mra
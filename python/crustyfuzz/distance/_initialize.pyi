@@ -1,4 +1,5 @@
 from collections.abc import Iterator
+from typing import Any
 
 _AnyOpList = (
     list[Editop | tuple[str, int, int]] | list[Opcode | tuple[str, int, int, int, int]]
@@ -19,6 +20,12 @@ class ScoreAlignment:
         dest_start: int,
         dest_end: int,
     ): ...
+    def to_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> ScoreAlignment: ...
+    def to_json(self) -> str: ...
+    @classmethod
+    def from_json(cls, json: str) -> ScoreAlignment: ...
     def __len__(self) -> int: ...
     def __eq__(self, other: object) -> bool: ...
     def __getitem__(self, i: int) -> int | float: ...
@@ -31,6 +38,12 @@ class MatchingBlock:
     size: int
 
     def __init__(self, a: int, b: int, size: int): ...
+    def to_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> MatchingBlock: ...
+    def to_json(self) -> str: ...
+    @classmethod
+    def from_json(cls, json: str) -> MatchingBlock: ...
     def __len__(self) -> int: ...
     def __eq__(self, other: object) -> bool: ...
     def __getitem__(self, i: int) -> int: ...
@@ -43,6 +56,12 @@ class Editop:
     dest_pos: int
 
     def __init__(self, tag: str, src_pos: int, dest_pos: int): ...
+    def to_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> Editop: ...
+    def to_json(self) -> str: ...
+    @classmethod
+    def from_json(cls, json: str) -> Editop: ...
     def __len__(self) -> int: ...
     def __eq__(self, other: object) -> bool: ...
     def __getitem__(self, i: int) -> int | str: ...
@@ -76,6 +95,12 @@ class Editops:
     def dest_len(self) -> int: ...
     @dest_len.setter
     def dest_len(self, value: int) -> None: ...
+    def to_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> Editops: ...
+    def to_json(self) -> str: ...
+    @classmethod
+    def from_json(cls, json: str) -> Editops: ...
     def __eq__(self, other: object) -> bool: ...
     def __len__(self) -> int: ...
     def __delitem__(self, key: int | slice) -> None: ...
@@ -93,6 +118,12 @@ class Opcode:
     def __init__(
         self, tag: str, src_start: int, src_end: int, dest_start: int, dest_end: int
     ): ...
+    def to_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> Opcode: ...
+    def to_json(self) -> str: ...
+    @classmethod
+    def from_json(cls, json: str) -> Opcode: ...
     def __len__(self) -> int: ...
     def __eq__(self, other: object) -> bool: ...
     def __getitem__(self, i: int) -> int | str: ...
@@ -125,6 +156,12 @@ class Opcodes:
     def dest_len(self) -> int: ...
     @dest_len.setter
     def dest_len(self, value: int) -> None: ...
+    def to_dict(self) -> dict[str, Any]: ...
+    @classmethod
+    def from_dict(cls, data: dict[str, Any]) -> Opcodes: ...
+    def to_json(self) -> str: ...
+    @classmethod
+    def from_json(cls, json: str) -> Opcodes: ...
     def __eq__(self, other: object) -> bool: ...
     def __len__(self) -> int: ...
     def __getitem__(self, key: int) -> Opcode: ...
@@ -0,0 +1,46 @@
+# SPDX-License-Identifier: MIT
+
+from __future__ import annotations
+
+from typing import Callable, Hashable, Sequence
+
+def distance(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    q: int = 2,
+    pad: str | None = None,
+    multiset: bool = False,
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: float | None = None,
+) -> float: ...
+def normalized_distance(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    q: int = 2,
+    pad: str | None = None,
+    multiset: bool = False,
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: float | None = 0,
+) -> float: ...
+def similarity(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    q: int = 2,
+    pad: str | None = None,
+    multiset: bool = False,
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: float | None = None,
+) -> float: ...
+def normalized_similarity(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    q: int = 2,
+    pad: str | None = None,
+    multiset: bool = False,
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: float | None = 0,
+) -> float: ...
@@ -13,11 +13,36 @@ from typing import Callable, Hashable, Sequence
 
 from rapidfuzz.distance import Editops, Opcodes
 
+class CostTable:
+    def __init__(
+        self,
+        substitutions: dict[tuple[Sequence[Hashable], Sequence[Hashable]], int]
+        | None = None,
+        *,
+        insertions: dict[Hashable, int] | None = None,
+        deletions: dict[Hashable, int] | None = None,
+        symmetric: bool = True,
+    ) -> None: ...
+    @staticmethod
+    def from_matrix(
+        alphabet: Sequence[Hashable],
+        matrix: Sequence[Sequence[int]],
+        *,
+        insertions: dict[Hashable, int] | None = None,
+        deletions: dict[Hashable, int] | None = None,
+    ) -> CostTable: ...
+    @staticmethod
+    def qwerty(cost: int = 1) -> CostTable: ...
+    @staticmethod
+    def ocr(cost: int = 1) -> CostTable: ...
+    def merge(self, other: CostTable) -> CostTable: ...
+
 def distance(
     s1: Sequence[Hashable],
     s2: Sequence[Hashable],
     *,
     weights: tuple[int, int, int] | None = (1, 1, 1),
+    costs: CostTable | None = None,
     processor: Callable[..., Sequence[Hashable]] | None = None,
     score_cutoff: int | None = None,
     score_hint: int | None = None,
@@ -27,6 +52,7 @@ def normalized_distance(
     s2: Sequence[Hashable],
     *,
     weights: tuple[int, int, int] | None = (1, 1, 1),
+    costs: CostTable | None = None,
     processor: Callable[..., Sequence[Hashable]] | None = None,
     score_cutoff: float | None = 0,
     score_hint: float | None = 0,
@@ -36,6 +62,7 @@ def similarity(
     s2: Sequence[Hashable],
     *,
     weights: tuple[int, int, int] | None = (1, 1, 1),
+    costs: CostTable | None = None,
     processor: Callable[..., Sequence[Hashable]] | None = None,
     score_cutoff: int | None = None,
     score_hint: int | None = None,
@@ -45,6 +72,7 @@ def normalized_similarity(
     s2: Sequence[Hashable],
     *,
     weights: tuple[int, int, int] | None = (1, 1, 1),
+    costs: CostTable | None = None,
     processor: Callable[..., Sequence[Hashable]] | None = None,
     score_cutoff: float | None = 0,
     score_hint: float | None = 0,
@@ -53,6 +81,8 @@ def editops(
     s1: Sequence[Hashable],
     s2: Sequence[Hashable],
     *,
+    weights: tuple[int, int, int] | None = (1, 1, 1),
+    costs: CostTable | None = None,
     processor: Callable[..., Sequence[Hashable]] | None = None,
     score_hint: int | None = None,
 ) -> Editops: ...
@@ -60,6 +90,8 @@ def opcodes(
     s1: Sequence[Hashable],
     s2: Sequence[Hashable],
     *,
+    weights: tuple[int, int, int] | None = (1, 1, 1),
+    costs: CostTable | None = None,
     processor: Callable[..., Sequence[Hashable]] | None = None,
     score_hint: int | None = None,
 ) -> Opcodes: ...
@@ -1,5 +1,13 @@
 from __future__ import annotations
 
+# Cosine
+from crustyfuzz.distance.cosine import distance as cosine_distance
+from crustyfuzz.distance.cosine import normalized_distance as cosine_normalized_distance
+from crustyfuzz.distance.cosine import (
+    normalized_similarity as cosine_normalized_similarity,
+)
+from crustyfuzz.distance.cosine import similarity as cosine_similarity
+
 # DamerauLevenshtein
 from crustyfuzz.distance.damerau_levenshtein import (
     distance as damerau_levenshtein_distance,
@@ -14,6 +22,24 @@ from crustyfuzz.distance.damerau_levenshtein import (
     similarity as damerau_levenshtein_similarity,
 )
 
+# Editex
+from crustyfuzz.distance.editex import distance as editex_distance
+from crustyfuzz.distance.editex import normalized_distance as editex_normalized_distance
+from crustyfuzz.distance.editex import (
+    normalized_similarity as editex_normalized_similarity,
+)
+from crustyfuzz.distance.editex import similarity as editex_similarity
+
+# Gotoh
+from crustyfuzz.distance.gotoh import distance as gotoh_distance
+from crustyfuzz.distance.gotoh import editops as gotoh_editops
+from crustyfuzz.distance.gotoh import normalized_distance as gotoh_normalized_distance
+from crustyfuzz.distance.gotoh import (
+    normalized_similarity as gotoh_normalized_similarity,
+)
+from crustyfuzz.distance.gotoh import opcodes as gotoh_opcodes
+from crustyfuzz.distance.gotoh import similarity as gotoh_similarity
+
 # Hamming
 from crustyfuzz.distance.hamming import distance as hamming_distance
 from crustyfuzz.distance.hamming import editops as hamming_editops
@@ -38,6 +64,16 @@ from crustyfuzz.distance.indel import (
 from crustyfuzz.distance.indel import opcodes as indel_opcodes
 from crustyfuzz.distance.indel import similarity as indel_similarity
 
+# Jaccard
+from crustyfuzz.distance.jaccard import distance as jaccard_distance
+from crustyfuzz.distance.jaccard import (
+    normalized_distance as jaccard_normalized_distance,
+)
+from crustyfuzz.distance.jaccard import (
+    normalized_similarity as jaccard_normalized_similarity,
+)
+from crustyfuzz.distance.jaccard import similarity as jaccard_similarity
+
 # Jaro
 from crustyfuzz.distance.jaro import distance as jaro_distance
 from crustyfuzz.distance.jaro import normalized_distance as jaro_normalized_distance
@@ -68,6 +104,17 @@ from crustyfuzz.distance.lcs_seq import (
 from crustyfuzz.distance.lcs_seq import opcodes as lcs_seq_opcodes
 from crustyfuzz.distance.lcs_seq import similarity as lcs_seq_similarity
 
+# LCSstr
+from crustyfuzz.distance.lcs_str import alignment as lcs_str_alignment
+from crustyfuzz.distance.lcs_str import distance as lcs_str_distance
+from crustyfuzz.distance.lcs_str import (
+    normalized_distance as lcs_str_normalized_distance,
+)
+from crustyfuzz.distance.lcs_str import (
+    normalized_similarity as lcs_str_normalized_similarity,
+)
+from crustyfuzz.distance.lcs_str import similarity as lcs_str_similarity
+
 # Levenshtein
 from crustyfuzz.distance.levenshtein import distance as levenshtein_distance
 from crustyfuzz.distance.levenshtein import editops as levenshtein_editops
@@ -80,6 +127,12 @@ from crustyfuzz.distance.levenshtein import (
 from crustyfuzz.distance.levenshtein import opcodes as levenshtein_opcodes
 from crustyfuzz.distance.levenshtein import similarity as levenshtein_similarity
 
+# MRA
+from crustyfuzz.distance.mra import distance as mra_distance
+from crustyfuzz.distance.mra import normalized_distance as mra_normalized_distance
+from crustyfuzz.distance.mra import normalized_similarity as mra_normalized_similarity
+from crustyfuzz.distance.mra import similarity as mra_similarity
+
 # OSA
 from crustyfuzz.distance.osa import distance as osa_distance
 from crustyfuzz.distance.osa import normalized_distance as osa_normalized_distance
@@ -88,6 +141,16 @@ from crustyfuzz.distance.osa import (
 )
 from crustyfuzz.distance.osa import similarity as osa_similarity
 
+# Overlap
+from crustyfuzz.distance.overlap import distance as overlap_distance
+from crustyfuzz.distance.overlap import (
+    normalized_distance as overlap_normalized_distance,
+)
+from crustyfuzz.distance.overlap import (
+    normalized_similarity as overlap_normalized_similarity,
+)
+from crustyfuzz.distance.overlap import similarity as overlap_similarity
+
 # Postfix
 from crustyfuzz.distance.postfix import distance as postfix_distance
 from crustyfuzz.distance.postfix import (
@@ -108,11 +171,70 @@ from crustyfuzz.distance.prefix import (
 )
 from crustyfuzz.distance.prefix import similarity as prefix_similarity
 
+# RatcliffObershelp
+from crustyfuzz.distance.ratcliff_obershelp import (
+    distance as ratcliff_obershelp_distance,
+)
+from crustyfuzz.distance.ratcliff_obershelp import (
+    matching_blocks as ratcliff_obershelp_matching_blocks,
+)
+from crustyfuzz.distance.ratcliff_obershelp import (
+    normalized_distance as ratcliff_obershelp_normalized_distance,
+)
+from crustyfuzz.distance.ratcliff_obershelp import (
+    normalized_similarity as ratcliff_obershelp_normalized_similarity,
+)
+from crustyfuzz.distance.ratcliff_obershelp import opcodes as ratcliff_obershelp_opcodes
+from crustyfuzz.distance.ratcliff_obershelp import (
+    similarity as ratcliff_obershelp_similarity,
+)
+
+# SmithWaterman
+from crustyfuzz.distance.smith_waterman import (
+    alignment as smith_waterman_alignment,
+)
+from crustyfuzz.distance.smith_waterman import distance as smith_waterman_distance
+from crustyfuzz.distance.smith_waterman import editops as smith_waterman_editops
+from crustyfuzz.distance.smith_waterman import (
+    normalized_distance as smith_waterman_normalized_distance,
+)
+from crustyfuzz.distance.smith_waterman import (
+    normalized_similarity as smith_waterman_normalized_similarity,
+)
+from crustyfuzz.distance.smith_waterman import opcodes as smith_waterman_opcodes
+from crustyfuzz.distance.smith_waterman import (
+    similarity as smith_waterman_similarity,
+)
+
+# SorensenDice
+from crustyfuzz.distance.sorensen_dice import distance as sorensen_dice_distance
+from crustyfuzz.distance.sorensen_dice import (
+    normalized_distance as sorensen_dice_normalized_distance,
+)
+from crustyfuzz.distance.sorensen_dice import (
+    normalized_similarity as sorensen_dice_normalized_similarity,
+)
+from crustyfuzz.distance.sorensen_dice import similarity as sorensen_dice_similarity
+
 __all__ = (
+    "cosine_distance",
+    "cosine_normalized_distance",
+    "cosine_normalized_similarity",
+    "cosine_similarity",
     "damerau_levenshtein_distance",
     "damerau_levenshtein_normalized_distance",
     "damerau_levenshtein_normalized_similarity",
     "damerau_levenshtein_similarity",
+    "editex_distance",
+    "editex_normalized_distance",
+    "editex_normalized_similarity",
+    "editex_similarity",
+    "gotoh_distance",
+    "gotoh_editops",
+    "gotoh_normalized_distance",
+    "gotoh_normalized_similarity",
+    "gotoh_opcodes",
+    "gotoh_similarity",
     "hamming_distance",
     "hamming_editops",
     "hamming_normalized_distance",
@@ -125,6 +247,10 @@ __all__ = (
     "indel_normalized_similarity",
     "indel_opcodes",
     "indel_similarity",
+    "jaccard_distance",
+    "jaccard_normalized_distance",
+    "jaccard_normalized_similarity",
+    "jaccard_similarity",
     "jaro_distance",
     "jaro_normalized_distance",
     "jaro_normalized_similarity",
@@ -139,16 +265,29 @@ __all__ = (
     "lcs_seq_normalized_similarity",
     "lcs_seq_opcodes",
     "lcs_seq_similarity",
+    "lcs_str_alignment",
+    "lcs_str_distance",
+    "lcs_str_normalized_distance",
+    "lcs_str_normalized_similarity",
+    "lcs_str_similarity",
     "levenshtein_distance",
     "levenshtein_editops",
     "levenshtein_normalized_distance",
     "levenshtein_normalized_similarity",
     "levenshtein_opcodes",
     "levenshtein_similarity",
+    "mra_distance",
+    "mra_normalized_distance",
+    "mra_normalized_similarity",
+    "mra_similarity",
     "osa_distance",
     "osa_normalized_distance",
     "osa_normalized_similarity",
     "osa_similarity",
+    "overlap_distance",
+    "overlap_normalized_distance",
+    "overlap_normalized_similarity",
+    "overlap_similarity",
     "postfix_distance",
     "postfix_normalized_distance",
     "postfix_normalized_similarity",
@@ -157,4 +296,21 @@ __all__ = (
     "prefix_normalized_distance",
     "prefix_normalized_similarity",
     "prefix_similarity",
+    "ratcliff_obershelp_distance",
+    "ratcliff_obershelp_matching_blocks",
+    "ratcliff_obershelp_normalized_distance",
+    "ratcliff_obershelp_normalized_similarity",
+    "ratcliff_obershelp_opcodes",
+    "ratcliff_obershelp_similarity",
+    "smith_waterman_alignment",
+    "smith_waterman_distance",
+    "smith_waterman_editops",
+    "smith_waterman_normalized_distance",
+    "smith_waterman_normalized_similarity",
+    "smith_waterman_opcodes",
+    "smith_waterman_similarity",
+    "sorensen_dice_distance",
+    "sorensen_dice_normalized_distance",
+    "sorensen_dice_normalized_similarity",
+    "sorensen_dice_similarity",
 )
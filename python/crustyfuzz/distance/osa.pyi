@@ -9,6 +9,7 @@ def distance(
     s1: Sequence[Hashable],
     s2: Sequence[Hashable],
     *,
+    weights: tuple[int, int, int, int] | None = (1, 1, 1, 1),
     processor: Callable[..., Sequence[Hashable]] | None = None,
     score_cutoff: int | None = None,
 ) -> int: ...
@@ -16,6 +17,7 @@ def normalized_distance(
     s1: Sequence[Hashable],
     s2: Sequence[Hashable],
     *,
+    weights: tuple[int, int, int, int] | None = (1, 1, 1, 1),
     processor: Callable[..., Sequence[Hashable]] | None = None,
     score_cutoff: float | None = 0,
 ) -> float: ...
@@ -23,6 +25,7 @@ def similarity(
     s1: Sequence[Hashable],
     s2: Sequence[Hashable],
     *,
+    weights: tuple[int, int, int, int] | None = (1, 1, 1, 1),
     processor: Callable[..., Sequence[Hashable]] | None = None,
     score_cutoff: int | None = None,
 ) -> int: ...
@@ -30,6 +33,7 @@ def normalized_similarity(
     s1: Sequence[Hashable],
     s2: Sequence[Hashable],
     *,
+    weights: tuple[int, int, int, int] | None = (1, 1, 1, 1),
     processor: Callable[..., Sequence[Hashable]] | None = None,
     score_cutoff: float | None = 0,
 ) -> float: ...
@@ -0,0 +1,60 @@
+# SPDX-License-Identifier: MIT
+
+from __future__ import annotations
+
+from typing import Any, Callable, Hashable, Sequence
+
+from crustyfuzz.distance import MatchingBlock, Opcodes
+
+def distance(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    isjunk: Callable[[Any], bool] | None = None,
+    autojunk: bool = True,
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: int | None = None,
+) -> int: ...
+def normalized_distance(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    isjunk: Callable[[Any], bool] | None = None,
+    autojunk: bool = True,
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: float | None = 0,
+) -> float: ...
+def similarity(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    isjunk: Callable[[Any], bool] | None = None,
+    autojunk: bool = True,
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: int | None = None,
+) -> int: ...
+def normalized_similarity(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    isjunk: Callable[[Any], bool] | None = None,
+    autojunk: bool = True,
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: float | None = 0,
+) -> float: ...
+def matching_blocks(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    isjunk: Callable[[Any], bool] | None = None,
+    autojunk: bool = True,
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+) -> list[MatchingBlock]: ...
+def opcodes(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    isjunk: Callable[[Any], bool] | None = None,
+    autojunk: bool = True,
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+) -> Opcodes: ...
@@ -0,0 +1,62 @@
+# SPDX-License-Identifier: MIT
+
+from __future__ import annotations
+
+from typing import Callable, Hashable, Sequence
+
+from crustyfuzz.distance import Editops, Opcodes, ScoreAlignment
+
+def distance(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    weights: tuple[int, int, int, int] | None = (1, 1, 1, 1),
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: int | None = None,
+) -> int: ...
+def normalized_distance(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    weights: tuple[int, int, int, int] | None = (1, 1, 1, 1),
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: float | None = 0,
+) -> float: ...
+def similarity(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    weights: tuple[int, int, int, int] | None = (1, 1, 1, 1),
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: int | None = None,
+) -> int: ...
+def normalized_similarity(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    weights: tuple[int, int, int, int] | None = (1, 1, 1, 1),
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: float | None = 0,
+) -> float: ...
+def alignment(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    weights: tuple[int, int, int, int] | None = (1, 1, 1, 1),
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: int | None = None,
+) -> ScoreAlignment | None: ...
+def editops(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    weights: tuple[int, int, int, int] | None = (1, 1, 1, 1),
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+) -> Editops: ...
+def opcodes(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    weights: tuple[int, int, int, int] | None = (1, 1, 1, 1),
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+) -> Opcodes: ...
@@ -72,3 +72,12 @@ def QRatio(
     processor: Callable[..., Sequence[Hashable]] | None = None,
     score_cutoff: float | None = 0,
 ) -> float: ...
+def monge_elkan(
+    s1: Sequence[Hashable],
+    s2: Sequence[Hashable],
+    *,
+    inner: Callable[..., float] | None = None,
+    symmetric: bool = True,
+    processor: Callable[..., Sequence[Hashable]] | None = None,
+    score_cutoff: float | None = 0,
+) -> float: ...
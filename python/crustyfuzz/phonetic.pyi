@@ -0,0 +1,19 @@
+from collections.abc import Callable
+from typing import Any, Literal
+
+Encoder = Literal["soundex", "metaphone", "double_metaphone", "nysiis"]
+
+def soundex(s: str) -> str: ...
+def metaphone(s: str) -> str: ...
+def double_metaphone(s: str) -> tuple[str, str]: ...
+def nysiis(s: str) -> str: ...
+def score(
+    s1: str | None,
+    s2: str | None,
+    *,
+    encoder: Encoder = "metaphone",
+    metric: Callable[..., Any] | None = None,
+    processor: Callable[..., str] | None = None,
+    score_cutoff: Any | None = None,
+    **kwargs: Any,
+) -> Any: ...
@@ -2,6 +2,10 @@
 # Copyright (C) 2022 Max Bachmann
 
 import re
+from collections.abc import Iterator
+from contextlib import contextmanager
+
+from .crustyfuzz import _exact_comparison
 
 _alnum_regex = re.compile(r"(?ui)\W")
 
@@ -28,3 +32,44 @@ def default_process(sentence: str) -> str:
     """
     string_out = _alnum_regex.sub(" ", sentence)
     return string_out.strip().lower()
+
+
+def set_exact_comparison(enabled: bool) -> None:
+    """
+    Select how elements of sequences that are not strings are compared.
+
+    By default elements are compared by their hash, which is fast but
+    can make distinct elements compare equal when their hashes collide.
+    When enabled, the elements of both sequences are mapped to ids using
+    Python equality, so they compare equal exactly when ``a == b``.
+
+    The mode is stored in a ``contextvars.ContextVar``, so it only applies
+    to the current thread or asyncio task.
+
+    Parameters
+    ----------
+    enabled : bool
+        whether to compare elements using Python equality
+    """
+    _exact_comparison.set(enabled)
+
+
+def get_exact_comparison() -> bool:
+    """
+    Returns whether elements are compared using Python equality,
+    see ``set_exact_comparison``.
+    """
+    return _exact_comparison.get()
+
+
+@contextmanager
+def exact_comparison() -> Iterator[None]:
+    """
+    Context manager that compares elements using Python equality,
+    see ``set_exact_comparison``.
+    """
+    token = _exact_comparison.set(True)
+    try:
+        yield
+    finally:
+        _exact_comparison.reset(token)
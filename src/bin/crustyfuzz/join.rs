@@ -0,0 +1,563 @@
+use clap::{Args, ValueEnum};
+use crustyfuzz::fuzz;
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use rayon::prelude::*;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum JoinError {
+    #[error("{0}")]
+    Csv(#[from] csv::Error),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("delimiter '{0}' is not an ASCII character")]
+    Delimiter(char),
+    #[error("column '{column}' not found in {path}")]
+    MissingColumn { column: String, path: String },
+    #[error("failed to set up thread pool: {0}")]
+    ThreadPool(String),
+}
+
+type ScorerFn = fn(&[u32], &[u32], f64) -> f64;
+
+/// Scorers from `crustyfuzz.fuzz` that can be used to compare the join columns
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Scorer {
+    #[value(name = "ratio")]
+    Ratio,
+    #[value(name = "partial_ratio")]
+    PartialRatio,
+    #[value(name = "token_sort_ratio")]
+    TokenSortRatio,
+    #[value(name = "token_set_ratio")]
+    TokenSetRatio,
+    #[value(name = "token_ratio")]
+    TokenRatio,
+    #[value(name = "partial_token_sort_ratio")]
+    PartialTokenSortRatio,
+    #[value(name = "partial_token_set_ratio")]
+    PartialTokenSetRatio,
+    #[value(name = "partial_token_ratio")]
+    PartialTokenRatio,
+    #[value(name = "WRatio")]
+    WRatio,
+    #[value(name = "QRatio")]
+    QRatio,
+}
+
+impl Scorer {
+    fn func(self) -> ScorerFn {
+        match self {
+            Scorer::Ratio => fuzz::ratio,
+            Scorer::PartialRatio => fuzz::partial_ratio,
+            Scorer::TokenSortRatio => {
+                |s1, s2, score_cutoff| fuzz::token_sort_ratio(s1, s2, Some(score_cutoff))
+            }
+            Scorer::TokenSetRatio => fuzz::token_set_ratio,
+            Scorer::TokenRatio => fuzz::token_ratio,
+            Scorer::PartialTokenSortRatio => fuzz::partial_token_sort_ratio,
+            Scorer::PartialTokenSetRatio => fuzz::partial_token_set_ratio,
+            Scorer::PartialTokenRatio => fuzz::partial_token_ratio,
+            Scorer::WRatio => fuzz::weighted_ratio,
+            Scorer::QRatio => fuzz::quick_ratio,
+        }
+    }
+}
+
+/// Preprocessing applied to the join columns before they are compared
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Processor {
+    /// Compare the columns as they are
+    None,
+    /// Same as `crustyfuzz.utils.default_process`
+    Default,
+}
+
+impl Processor {
+    fn apply(self, s: &str) -> Vec<u32> {
+        match self {
+            Processor::None => s.chars().map(|c| c as u32).collect(),
+            Processor::Default => default_process(s).chars().map(|c| c as u32).collect(),
+        }
+    }
+}
+
+/// Replaces every non alphanumeric character with a space, trims whitespace
+/// and converts the string to lower case
+fn default_process(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect::<String>()
+        .trim()
+        .to_lowercase()
+}
+
+/// How rows of the left file are paired with rows of the right file
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum JoinMode {
+    /// Only the best match above the threshold for every left row
+    Best,
+    /// Every match above the threshold for every left row
+    All,
+    /// Every row of either file is used at most once, best scores first
+    OneToOne,
+}
+
+#[derive(Args)]
+pub struct JoinArgs {
+    /// CSV file with the rows that should be matched
+    left: PathBuf,
+    /// CSV file with the rows to search for matches
+    right: PathBuf,
+    /// Column of the left file used for matching
+    #[arg(long)]
+    left_col: String,
+    /// Column of the right file used for matching
+    #[arg(long)]
+    right_col: String,
+    /// Scorer used to compare the two columns
+    #[arg(long, value_enum, default_value_t = Scorer::WRatio)]
+    scorer: Scorer,
+    /// Preprocessing applied to both columns before comparing them
+    #[arg(long, value_enum, default_value_t = Processor::None)]
+    processor: Processor,
+    /// Minimum score between 0 and 100 for two rows to be joined
+    #[arg(long, default_value_t = 0.0, value_parser = parse_threshold)]
+    threshold: f64,
+    /// Which matches are written for every row
+    #[arg(long, value_enum, default_value_t = JoinMode::Best)]
+    mode: JoinMode,
+    /// Name of the score column appended to the output
+    #[arg(long, default_value = "score")]
+    score_col: String,
+    /// Field delimiter of the input and output files
+    #[arg(long, default_value_t = ',')]
+    delimiter: char,
+    /// Output file. The joined CSV is written to stdout by default
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+    /// Number of worker threads. Defaults to the number of CPUs
+    #[arg(long)]
+    threads: Option<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Match {
+    pub left: usize,
+    pub right: usize,
+    pub score: f64,
+}
+
+struct Table {
+    headers: StringRecord,
+    records: Vec<StringRecord>,
+    keys: Vec<Vec<u32>>,
+}
+
+fn parse_threshold(s: &str) -> Result<f64, String> {
+    let threshold: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    if (0.0..=100.0).contains(&threshold) {
+        Ok(threshold)
+    } else {
+        Err(format!("{threshold} is not in 0..=100"))
+    }
+}
+
+/// The csv crate splits fields on a single byte, so the delimiter has to be
+/// ASCII to match the UTF-8 encoded files.
+fn delimiter_byte(delimiter: char) -> Result<u8, JoinError> {
+    u8::try_from(delimiter)
+        .ok()
+        .filter(u8::is_ascii)
+        .ok_or(JoinError::Delimiter(delimiter))
+}
+
+fn read_table(
+    path: &Path,
+    column: &str,
+    delimiter: u8,
+    processor: Processor,
+) -> Result<Table, JoinError> {
+    let mut reader = ReaderBuilder::new().delimiter(delimiter).from_path(path)?;
+    let headers = reader.headers()?.clone();
+    let index =
+        headers
+            .iter()
+            .position(|h| h == column)
+            .ok_or_else(|| JoinError::MissingColumn {
+                column: column.to_string(),
+                path: path.display().to_string(),
+            })?;
+
+    let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+    let keys = records
+        .iter()
+        .map(|r| processor.apply(r.get(index).unwrap_or("")))
+        .collect();
+
+    Ok(Table {
+        headers,
+        records,
+        keys,
+    })
+}
+
+pub fn run(args: JoinArgs) -> Result<(), JoinError> {
+    if let Some(threads) = args.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .map_err(|e| JoinError::ThreadPool(e.to_string()))?;
+    }
+
+    let delimiter = delimiter_byte(args.delimiter)?;
+    let left = read_table(&args.left, &args.left_col, delimiter, args.processor)?;
+    let right = read_table(&args.right, &args.right_col, delimiter, args.processor)?;
+
+    let matches = join(
+        &left.keys,
+        &right.keys,
+        args.scorer.func(),
+        args.threshold,
+        args.mode,
+    );
+
+    let output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(std::fs::File::create(path)?),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let mut writer = WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(output);
+
+    writer.write_record(&joined_headers(
+        &left.headers,
+        &right.headers,
+        &args.score_col,
+    ))?;
+
+    for m in matches {
+        let mut record = left.records[m.left].clone();
+        record.extend(right.records[m.right].iter());
+        record.push_field(&m.score.to_string());
+        writer.write_record(&record)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Right-hand columns whose name is already used by the left file get a
+/// `_right` suffix, so every column of the output has a unique name.
+fn joined_headers(left: &StringRecord, right: &StringRecord, score_col: &str) -> StringRecord {
+    let mut headers = left.clone();
+    for header in right {
+        if left.iter().any(|h| h == header) {
+            headers.push_field(&format!("{header}_right"));
+        } else {
+            headers.push_field(header);
+        }
+    }
+    headers.push_field(score_col);
+    headers
+}
+
+/**
+Matches every key in `left` against the keys in `right`. The matches are
+sorted by the index of the left row and for each left row by descending score.
+When multiple choices have the same score they are sorted by their index,
+in line with `process.extract`.
+
+Empty keys are never matched.
+*/
+pub fn join(
+    left: &[Vec<u32>],
+    right: &[Vec<u32>],
+    scorer: ScorerFn,
+    score_cutoff: f64,
+    mode: JoinMode,
+) -> Vec<Match> {
+    match mode {
+        JoinMode::Best => left
+            .par_iter()
+            .enumerate()
+            .filter_map(|(i, query)| {
+                extract_one(query, right, scorer, score_cutoff).map(|(j, score)| Match {
+                    left: i,
+                    right: j,
+                    score,
+                })
+            })
+            .collect(),
+        JoinMode::All => left
+            .par_iter()
+            .enumerate()
+            .flat_map_iter(|(i, query)| {
+                extract(query, right, scorer, score_cutoff)
+                    .into_iter()
+                    .map(move |(j, score)| Match {
+                        left: i,
+                        right: j,
+                        score,
+                    })
+            })
+            .collect(),
+        JoinMode::OneToOne => {
+            let mut candidates = join(left, right, scorer, score_cutoff, JoinMode::All);
+            candidates.par_sort_by(|a, b| {
+                b.score
+                    .total_cmp(&a.score)
+                    .then(a.left.cmp(&b.left))
+                    .then(a.right.cmp(&b.right))
+            });
+
+            let mut left_used = vec![false; left.len()];
+            let mut right_used = vec![false; right.len()];
+            let mut matches: Vec<Match> = candidates
+                .into_iter()
+                .filter(|m| {
+                    if left_used[m.left] || right_used[m.right] {
+                        return false;
+                    }
+                    left_used[m.left] = true;
+                    right_used[m.right] = true;
+                    true
+                })
+                .collect();
+
+            matches.sort_by_key(|m| m.left);
+            matches
+        }
+    }
+}
+
+fn extract_one(
+    query: &[u32],
+    choices: &[Vec<u32>],
+    scorer: ScorerFn,
+    score_cutoff: f64,
+) -> Option<(usize, f64)> {
+    if query.is_empty() {
+        return None;
+    }
+
+    let mut result: Option<(usize, f64)> = None;
+    let mut current_score_cutoff = score_cutoff;
+    for (j, choice) in choices.iter().enumerate() {
+        if choice.is_empty() {
+            continue;
+        }
+
+        let score = scorer(query, choice, current_score_cutoff);
+        if score >= current_score_cutoff && result.is_none_or(|(_, best)| score > best) {
+            current_score_cutoff = score;
+            result = Some((j, score));
+        }
+
+        if score == 100.0 {
+            break;
+        }
+    }
+
+    result
+}
+
+fn extract(
+    query: &[u32],
+    choices: &[Vec<u32>],
+    scorer: ScorerFn,
+    score_cutoff: f64,
+) -> Vec<(usize, f64)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut results: Vec<(usize, f64)> = choices
+        .iter()
+        .enumerate()
+        .filter(|(_, choice)| !choice.is_empty())
+        .map(|(j, choice)| (j, scorer(query, choice, score_cutoff)))
+        .filter(|&(_, score)| score >= score_cutoff)
+        .collect();
+
+    // stable sort, so equal scores stay ordered by index
+    results.sort_by(|a, b| b.1.total_cmp(&a.1));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn str_to_vec(s: &str) -> Vec<u32> {
+        s.chars().map(|c| c as u32).collect()
+    }
+
+    fn keys(strings: &[&str]) -> Vec<Vec<u32>> {
+        strings.iter().map(|s| str_to_vec(s)).collect()
+    }
+
+    #[test]
+    fn test_delimiter_byte() {
+        assert_eq!(delimiter_byte(';').unwrap(), b';');
+        assert_eq!(delimiter_byte('\t').unwrap(), b'\t');
+        assert!(matches!(
+            delimiter_byte('§'),
+            Err(JoinError::Delimiter('§'))
+        ));
+        assert!(matches!(
+            delimiter_byte('→'),
+            Err(JoinError::Delimiter('→'))
+        ));
+    }
+
+    #[test]
+    fn test_parse_threshold() {
+        assert_eq!(parse_threshold("0"), Ok(0.0));
+        assert_eq!(parse_threshold("85.5"), Ok(85.5));
+        assert_eq!(parse_threshold("100"), Ok(100.0));
+        assert!(parse_threshold("-1").is_err());
+        assert!(parse_threshold("100.1").is_err());
+        assert!(parse_threshold("NaN").is_err());
+        assert!(parse_threshold("high").is_err());
+    }
+
+    #[test]
+    fn test_joined_headers() {
+        let left = StringRecord::from(vec!["id", "name", "city"]);
+        let right = StringRecord::from(vec!["id", "vendor", "city"]);
+        assert_eq!(
+            joined_headers(&left, &right, "score"),
+            StringRecord::from(vec![
+                "id",
+                "name",
+                "city",
+                "id_right",
+                "vendor",
+                "city_right",
+                "score"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_default_process() {
+        assert_eq!(default_process("  New York Mets! "), "new york mets");
+        assert_eq!(default_process("snake_case-Key"), "snake_case key");
+        assert_eq!(
+            Processor::Default.apply("Mets, New-York"),
+            str_to_vec("mets  new york")
+        );
+    }
+
+    #[test]
+    fn test_join_processor() {
+        let left = vec![Processor::Default.apply("New York Mets")];
+        let right = vec![Processor::Default.apply("mets new york")];
+        let matches = join(
+            &left,
+            &right,
+            Scorer::TokenSortRatio.func(),
+            85.0,
+            JoinMode::Best,
+        );
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].score, 100.0);
+
+        let left = vec![Processor::None.apply("New York Mets")];
+        let right = vec![Processor::None.apply("mets new york")];
+        let matches = join(
+            &left,
+            &right,
+            Scorer::TokenSortRatio.func(),
+            85.0,
+            JoinMode::Best,
+        );
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_join_best() {
+        let left = keys(&["new york mets", "atlanta braves", "boston"]);
+        let right = keys(&["braves atlanta", "mets new york", "new york yankees"]);
+        let matches = join(
+            &left,
+            &right,
+            Scorer::TokenSortRatio.func(),
+            85.0,
+            JoinMode::Best,
+        );
+        assert_eq!(
+            matches,
+            vec![
+                Match {
+                    left: 0,
+                    right: 1,
+                    score: 100.0
+                },
+                Match {
+                    left: 1,
+                    right: 0,
+                    score: 100.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_join_all() {
+        let left = keys(&["abcd"]);
+        let right = keys(&["abce", "abcd", "xyz", "abce"]);
+        let matches = join(&left, &right, Scorer::Ratio.func(), 50.0, JoinMode::All);
+        let pairs: Vec<(usize, f64)> = matches.iter().map(|m| (m.right, m.score)).collect();
+        assert_eq!(pairs, vec![(1, 100.0), (0, 75.0), (3, 75.0)]);
+    }
+
+    #[test]
+    fn test_join_one_to_one() {
+        let left = keys(&["abcd", "abcf"]);
+        let right = keys(&["abcd", "abcx"]);
+        let best = join(&left, &right, Scorer::Ratio.func(), 0.0, JoinMode::Best);
+        assert_eq!(best.iter().map(|m| m.right).collect::<Vec<_>>(), vec![0, 0]);
+
+        let one_to_one = join(&left, &right, Scorer::Ratio.func(), 0.0, JoinMode::OneToOne);
+        assert_eq!(
+            one_to_one,
+            vec![
+                Match {
+                    left: 0,
+                    right: 0,
+                    score: 100.0
+                },
+                Match {
+                    left: 1,
+                    right: 1,
+                    score: 75.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_join_skips_empty_keys() {
+        let left = keys(&["", "abc"]);
+        let right = keys(&["", "abc"]);
+        let matches = join(&left, &right, Scorer::WRatio.func(), 0.0, JoinMode::All);
+        assert_eq!(
+            matches,
+            vec![Match {
+                left: 1,
+                right: 1,
+                score: 100.0
+            }]
+        );
+    }
+}
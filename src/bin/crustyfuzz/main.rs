@@ -0,0 +1,34 @@
+mod join;
+
+use clap::{Parser, Subcommand};
+use std::process::ExitCode;
+
+/// A rusty string matching command line tool
+#[derive(Parser)]
+#[command(name = "crustyfuzz", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Fuzzy join two CSV files on a pair of columns
+    Join(join::JoinArgs),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Join(args) => join::run(args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("crustyfuzz: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,27 +1,194 @@
 pub mod error;
 pub mod models;
+pub mod pattern_match;
+#[cfg(test)]
+pub mod test_utils;
 pub mod utils;
 
+#[cfg(feature = "python")]
 use crate::common::error::ConversionError;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::sync::GILOnceCell;
+#[cfg(feature = "python")]
+use pyo3::types::PyDict;
+#[cfg(all(feature = "python", not(feature = "abi3")))]
+use pyo3::types::PyStringData;
+#[cfg(feature = "python")]
 use pyo3::types::{PyBytes, PySequence, PyString};
+#[cfg(feature = "python")]
+use std::borrow::Cow;
+#[cfg(feature = "python")]
 use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "python")]
 use std::hash::{Hash, Hasher};
 
+#[cfg(feature = "python")]
 pub type ConversionResult<T> = Result<Option<T>, ConversionError>;
+#[cfg(feature = "python")]
 pub type ConvertedSequence = Vec<u32>;
 
+#[cfg(feature = "python")]
 const NEGATIVE_ONE_MARKER: u32 = u32::MAX;
 
+/// Whether elements of non-string sequences are compared using Python equality
+/// instead of their hash. This is a `contextvars.ContextVar`, so the mode only
+/// applies to the thread or asyncio task that set it.
+#[cfg(feature = "python")]
+static EXACT_COMPARISON: GILOnceCell<PyObject> = GILOnceCell::new();
+
+#[cfg(feature = "python")]
+pub fn exact_comparison_var(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    EXACT_COMPARISON
+        .get_or_try_init(py, || {
+            let kwargs = PyDict::new_bound(py);
+            kwargs.set_item("default", false)?;
+            let var = PyModule::import_bound(py, "contextvars")?
+                .getattr("ContextVar")?
+                .call(("crustyfuzz.exact_comparison",), Some(&kwargs))?;
+            Ok::<_, PyErr>(var.unbind())
+        })
+        .map(|var| var.bind(py))
+}
+
+#[cfg(feature = "python")]
+pub fn exact_comparison(py: Python<'_>) -> PyResult<bool> {
+    exact_comparison_var(py)?.call_method0("get")?.extract()
+}
+
+/// Element of a sequence passed to the generic kernels. Strings keep the
+/// element width of their storage, so elements are compared as `u32`.
+pub trait SeqElem: Copy + Ord {
+    fn value(self) -> u32;
+}
+
+impl SeqElem for u8 {
+    #[inline]
+    fn value(self) -> u32 {
+        self as u32
+    }
+}
+
+impl SeqElem for u16 {
+    #[inline]
+    fn value(self) -> u32 {
+        self as u32
+    }
+}
+
+impl SeqElem for u32 {
+    #[inline]
+    fn value(self) -> u32 {
+        self
+    }
+}
+
+/// Converted sequence, which borrows the storage of `str` and `bytes`
+/// objects where possible instead of copying it.
+#[cfg(feature = "python")]
+pub enum SeqView<'a> {
+    Ucs1(&'a [u8]),
+    // only strings stored with two bytes per character are read as `u16`,
+    // which requires access to their storage
+    #[cfg_attr(feature = "abi3", allow(dead_code))]
+    Ucs2(&'a [u16]),
+    Ucs4(Cow<'a, [u32]>),
+}
+
+#[cfg(feature = "python")]
+impl SeqView<'_> {
+    #[cfg_attr(not(feature = "process"), allow(dead_code))]
+    pub fn len(&self) -> usize {
+        match self {
+            SeqView::Ucs1(s) => s.len(),
+            SeqView::Ucs2(s) => s.len(),
+            SeqView::Ucs4(s) => s.len(),
+        }
+    }
+
+    #[cfg_attr(not(feature = "process"), allow(dead_code))]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn into_vec(self) -> ConvertedSequence {
+        match self {
+            SeqView::Ucs1(s) => s.iter().map(|&c| c as u32).collect(),
+            SeqView::Ucs2(s) => s.iter().map(|&c| c as u32).collect(),
+            SeqView::Ucs4(s) => s.into_owned(),
+        }
+    }
+}
+
+/// Evaluates `$body` with `$s` bound to the slice of the `SeqView` `$view`,
+/// which instantiates generic code for each element width.
+#[cfg(feature = "python")]
+macro_rules! with_view {
+    ($view:expr, |$s:ident| $body:expr) => {
+        match $view {
+            $crate::common::SeqView::Ucs1(view) => {
+                let $s: &[u8] = view;
+                $body
+            }
+            $crate::common::SeqView::Ucs2(view) => {
+                let $s: &[u16] = view;
+                $body
+            }
+            $crate::common::SeqView::Ucs4(view) => {
+                let $s: &[u32] = view;
+                $body
+            }
+        }
+    };
+}
+
+/// `with_view` for two views at once.
+#[cfg(feature = "python")]
+macro_rules! with_views {
+    ($view1:expr, $view2:expr, |$s1:ident, $s2:ident| $body:expr) => {
+        $crate::common::with_view!($view1, |$s1| $crate::common::with_view!($view2, |$s2| {
+            $body
+        }))
+    };
+}
+
+#[cfg(feature = "python")]
+pub(crate) use {with_view, with_views};
+
+/// Borrows the canonical PEP 393 storage of the string directly, which
+/// avoids encoding it to UTF-8 and decoding it again.
+#[cfg(all(feature = "python", not(feature = "abi3")))]
+fn string_view<'a>(s: &'a Bound<'_, PyString>) -> Result<SeqView<'a>, ConversionError> {
+    // SAFETY: strings are immutable and the view borrows `s`, which keeps the
+    // storage alive
+    let data = unsafe { s.data() }.map_err(|e| ConversionError::StringExtraction(e.to_string()))?;
+    Ok(match data {
+        PyStringData::Ucs1(data) => SeqView::Ucs1(data),
+        PyStringData::Ucs2(data) => SeqView::Ucs2(data),
+        PyStringData::Ucs4(data) => SeqView::Ucs4(Cow::Borrowed(data)),
+    })
+}
+
+/// The storage is not part of the stable ABI, so the string is converted
+/// through UTF-8 instead.
+#[cfg(all(feature = "python", feature = "abi3"))]
+fn string_view<'a>(s: &'a Bound<'_, PyString>) -> Result<SeqView<'a>, ConversionError> {
+    // if we supported python 3.10 only, we could use `to_str` here, see [1]
+    // [1]: https://docs.rs/pyo3/latest/pyo3/types/trait.PyStringMethods.html#required-methods
+    Ok(SeqView::Ucs4(Cow::Owned(
+        s.to_cow()
+            .map_err(|e| ConversionError::StringExtraction(e.to_string()))?
+            .chars()
+            .map(|c| c as u32)
+            .collect(),
+    )))
+}
+
+#[cfg(feature = "python")]
 pub fn conv_sequence(s: &Bound<'_, PyAny>) -> ConversionResult<ConvertedSequence> {
     if let Ok(s) = s.downcast::<PyString>() {
-        return Ok(Some(
-            s.to_cow()
-                .map_err(|e| ConversionError::StringExtraction(e.to_string()))?
-                .chars()
-                .map(|c| c as u32)
-                .collect(),
-        ));
+        return Ok(Some(string_view(s)?.into_vec()));
     }
 
     if let Ok(bytes) = s.downcast::<PyBytes>() {
@@ -67,7 +234,7 @@ pub fn conv_sequence(s: &Bound<'_, PyAny>) -> ConversionResult<ConvertedSequence
             if n == -1 {
                 result.push(NEGATIVE_ONE_MARKER);
                 continue;
-            } else if n > 0 {
+            } else if n > 0 && n < NEGATIVE_ONE_MARKER as i64 {
                 // so that string and ordinal input is interpreted equally
                 result.push(n as u32);
                 continue;
@@ -85,30 +252,15 @@ pub fn conv_sequence(s: &Bound<'_, PyAny>) -> ConversionResult<ConvertedSequence
     Ok(Some(result))
 }
 
+#[cfg(feature = "python")]
 pub fn conv_sequences(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
 ) -> Result<(Option<ConvertedSequence>, Option<ConvertedSequence>), ConversionError> {
     if let (Ok(s1_str), Ok(s2_str)) = (s1.downcast::<PyString>(), s2.downcast::<PyString>()) {
-        // if we supported python 3.10 only, we could use `to_str` here, see [1]
-        // [1]: https://docs.rs/pyo3/latest/pyo3/types/trait.PyStringMethods.html#required-methods
         return Ok((
-            Some(
-                s1_str
-                    .to_cow()
-                    .map_err(|e| ConversionError::StringExtraction(e.to_string()))?
-                    .chars()
-                    .map(|c| c as u32)
-                    .collect(),
-            ),
-            Some(
-                s2_str
-                    .to_cow()
-                    .map_err(|e| ConversionError::StringExtraction(e.to_string()))?
-                    .chars()
-                    .map(|c| c as u32)
-                    .collect(),
-            ),
+            Some(string_view(s1_str)?.into_vec()),
+            Some(string_view(s2_str)?.into_vec()),
         ));
     }
 
@@ -119,28 +271,114 @@ pub fn conv_sequences(
         ));
     }
 
+    if !s1.is_none() && !s2.is_none() && exact_comparison(s1.py())? {
+        let (s1, s2) = intern_sequences(s1, s2)?;
+        return Ok((Some(s1), Some(s2)));
+    }
+
     Ok((conv_sequence(s1)?, conv_sequence(s2)?))
 }
 
-pub fn common_prefix(s1: &[u32], s2: &[u32]) -> usize {
-    s1.iter().zip(s2.iter()).take_while(|(a, b)| a == b).count()
+/// Borrowed variant of `conv_sequence`.
+#[cfg(feature = "python")]
+pub fn conv_sequence_view<'a>(s: &'a Bound<'_, PyAny>) -> ConversionResult<SeqView<'a>> {
+    if let Ok(s) = s.downcast::<PyString>() {
+        return Ok(Some(string_view(s)?));
+    }
+
+    if let Ok(bytes) = s.downcast::<PyBytes>() {
+        return Ok(Some(SeqView::Ucs1(bytes.as_bytes())));
+    }
+
+    Ok(conv_sequence(s)?.map(|s| SeqView::Ucs4(Cow::Owned(s))))
+}
+
+/// Same as `conv_sequences`, but `str` and `bytes` objects are borrowed
+/// instead of being copied into a `Vec<u32>`.
+#[cfg(feature = "python")]
+pub fn conv_sequence_views<'a>(
+    s1: &'a Bound<'_, PyAny>,
+    s2: &'a Bound<'_, PyAny>,
+) -> Result<(Option<SeqView<'a>>, Option<SeqView<'a>>), ConversionError> {
+    let both_str = s1.is_instance_of::<PyString>() && s2.is_instance_of::<PyString>();
+    let both_bytes = s1.is_instance_of::<PyBytes>() && s2.is_instance_of::<PyBytes>();
+    if !both_str && !both_bytes && !s1.is_none() && !s2.is_none() && exact_comparison(s1.py())? {
+        let (s1, s2) = intern_sequences(s1, s2)?;
+        return Ok((
+            Some(SeqView::Ucs4(Cow::Owned(s1))),
+            Some(SeqView::Ucs4(Cow::Owned(s2))),
+        ));
+    }
+
+    Ok((conv_sequence_view(s1)?, conv_sequence_view(s2)?))
 }
 
-pub fn common_suffix(s1: &[u32], s2: &[u32]) -> usize {
+/// Maps the elements of both sequences to dense ids, so that two elements
+/// share an id exactly when they compare equal in Python.
+#[cfg(feature = "python")]
+fn intern_sequences(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+) -> Result<(ConvertedSequence, ConvertedSequence), ConversionError> {
+    let ids = PyDict::new_bound(s1.py());
+    Ok((intern_sequence(s1, &ids)?, intern_sequence(s2, &ids)?))
+}
+
+#[cfg(feature = "python")]
+fn intern_sequence(
+    s: &Bound<'_, PyAny>,
+    ids: &Bound<'_, PyDict>,
+) -> Result<ConvertedSequence, ConversionError> {
+    let mut result = Vec::new();
+    for elem in s
+        .iter()
+        .map_err(|e| ConversionError::SequenceDowncast(e.to_string()))?
+    {
+        let elem = elem.map_err(|e| ConversionError::SequenceItem(e.to_string()))?;
+        let id = match ids
+            .get_item(&elem)
+            .map_err(|e| ConversionError::Hashing(e.to_string()))?
+        {
+            Some(id) => id.extract::<u32>()?,
+            None => {
+                let id = ids.len() as u32;
+                ids.set_item(&elem, id)
+                    .map_err(|e| ConversionError::Hashing(e.to_string()))?;
+                id
+            }
+        };
+        result.push(id);
+    }
+    Ok(result)
+}
+
+/// Whether both sequences contain the same elements.
+pub fn seq_eq<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2]) -> bool {
+    s1.len() == s2.len() && common_prefix(s1, s2) == s1.len()
+}
+
+pub fn common_prefix<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2]) -> usize {
+    s1.iter()
+        .zip(s2.iter())
+        .take_while(|(a, b)| a.value() == b.value())
+        .count()
+}
+
+pub fn common_suffix<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2]) -> usize {
     s1.iter()
         .rev()
         .zip(s2.iter().rev())
-        .take_while(|(a, b)| a == b)
+        .take_while(|(a, b)| a.value() == b.value())
         .count()
 }
 
-pub fn common_affix(s1: &[u32], s2: &[u32]) -> (usize, usize) {
+pub fn common_affix<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2]) -> (usize, usize) {
     let prefix_len = common_prefix(s1, s2);
     let suffix_len = common_suffix(&s1[prefix_len..], &s2[prefix_len..]);
     (prefix_len, suffix_len)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "python"))]
 mod tests {
     use super::*;
     use pyo3::types::{PyBytes, PyList};
@@ -156,6 +394,58 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_conv_sequence_string_kinds() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            // one string for each of the 1, 2 and 4 byte storage kinds
+            for text in ["aé", "a€", "a😀"] {
+                let input = PyString::new_bound(py, text);
+                let result = conv_sequence(&input).unwrap();
+                let expected = text.chars().map(|c| c as u32).collect::<Vec<_>>();
+                assert_eq!(result, Some(expected));
+            }
+        });
+    }
+
+    #[test]
+    fn test_conv_sequence_views() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let bytes = PyBytes::new_bound(py, b"ab").into_any();
+            for text in ["a\u{e9}", "a\u{20ac}", "a\u{1f600}"] {
+                let s = PyString::new_bound(py, text).into_any();
+                let (v1, v2) = conv_sequence_views(&s, &bytes).unwrap();
+                let expected = text.chars().map(|c| c as u32).collect::<Vec<_>>();
+                assert_eq!(v1.unwrap().into_vec(), expected);
+                assert!(matches!(v2, Some(SeqView::Ucs1(b"ab"))));
+            }
+        });
+    }
+
+    #[cfg(not(feature = "abi3"))]
+    #[test]
+    fn test_string_view_borrows_storage() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let s = PyString::new_bound(py, "a\u{e9}");
+            assert!(matches!(
+                string_view(&s).unwrap(),
+                SeqView::Ucs1([0x61, 0xe9])
+            ));
+            let s = PyString::new_bound(py, "a\u{20ac}");
+            assert!(matches!(
+                string_view(&s).unwrap(),
+                SeqView::Ucs2([0x61, 0x20ac])
+            ));
+            let s = PyString::new_bound(py, "a\u{1f600}");
+            assert!(matches!(
+                string_view(&s).unwrap(),
+                SeqView::Ucs4(Cow::Borrowed([0x61, 0x1f600]))
+            ));
+        });
+    }
+
     #[test]
     fn test_conv_sequence_bytes() {
         pyo3::prepare_freethreaded_python();
@@ -237,6 +527,40 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_conv_sequence_large_int() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let list = PyList::new_bound(py, [(1i64 << 32) + 5, 5i64]);
+            let result = conv_sequence(&list).unwrap().unwrap();
+            assert_ne!(result[0], result[1]);
+        });
+    }
+
+    #[test]
+    fn test_intern_sequences() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let s1 = PyList::new_bound(py, [1i64 << 40, 7i64, 1i64 << 40]);
+            let s2 = PyList::new_bound(py, [7i64, (1i64 << 40) + 1]);
+            let (r1, r2) = intern_sequences(&s1, &s2).unwrap();
+            assert_eq!(r1, vec![0, 1, 0]);
+            assert_eq!(r2, vec![1, 2]);
+        });
+    }
+
+    #[test]
+    fn test_intern_sequences_string() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let s1 = PyString::new_bound(py, "abc");
+            let s2 = PyList::new_bound(py, ["c", "a"]);
+            let (r1, r2) = intern_sequences(&s1, &s2).unwrap();
+            assert_eq!(r1, vec![0, 1, 2]);
+            assert_eq!(r2, vec![2, 0]);
+        });
+    }
+
     #[test]
     fn test_conv_sequence_array() {
         pyo3::prepare_freethreaded_python();
@@ -1,3 +1,4 @@
+#[cfg(feature = "python")]
 use pyo3::PyErr;
 use thiserror::Error;
 
@@ -5,8 +6,13 @@ use thiserror::Error;
 pub enum CrustyError {
     #[error("Hamming distance requires equal length strings")]
     LengthMismatch,
+    #[error("Substitutions require a non-empty sequence on both sides")]
+    EmptySubstitution,
+    #[error("Invalid edit script: {0}")]
+    InvalidEditScript(String),
 }
 
+#[cfg(feature = "python")]
 #[derive(Error, Debug)]
 pub enum ConversionError {
     #[error("Failed to extract string: {0}")]
@@ -23,6 +29,7 @@ pub enum ConversionError {
     ArrayConversion(String),
 }
 
+#[cfg(feature = "python")]
 impl From<PyErr> for ConversionError {
     fn from(err: PyErr) -> Self {
         ConversionError::StringExtraction(err.to_string())
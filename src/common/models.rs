@@ -1,35 +1,52 @@
+#[cfg(feature = "fuzz")]
+use crate::common::SeqElem;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 
 /// A token represents a contiguous sequence of non-whitespace characters
+#[cfg(feature = "fuzz")]
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
-pub struct Token<'a> {
-    pub chars: &'a [u32],
+pub struct Token<'a, T = u32> {
+    pub chars: &'a [T],
+}
+
+#[cfg(feature = "fuzz")]
+impl<T: SeqElem> Token<'_, T> {
+    /// Copies the token into a `Vec<u32>`, so tokens of sequences with
+    /// different element widths can be compared.
+    pub fn to_vec(&self) -> Vec<u32> {
+        self.chars.iter().map(|c| c.value()).collect()
+    }
 }
 
 /// An iterator that yields tokens from a character sequence
-pub struct TokenIterator<'a> {
-    chars: &'a [u32],
+#[cfg(feature = "fuzz")]
+pub struct TokenIterator<'a, T> {
+    chars: &'a [T],
     pos: usize,
 }
 
-impl<'a> TokenIterator<'a> {
-    pub fn new(chars: &'a [u32]) -> Self {
+#[cfg(feature = "fuzz")]
+impl<'a, T: SeqElem> TokenIterator<'a, T> {
+    pub fn new(chars: &'a [T]) -> Self {
         TokenIterator { chars, pos: 0 }
     }
 }
 
-impl<'a> Iterator for TokenIterator<'a> {
-    type Item = Token<'a>;
+/// Elements of non-string sequences can be hashes outside of the valid
+/// character range, which are never whitespace.
+#[cfg(feature = "fuzz")]
+fn is_whitespace(c: u32) -> bool {
+    char::from_u32(c).is_some_and(char::is_whitespace)
+}
+
+#[cfg(feature = "fuzz")]
+impl<'a, T: SeqElem> Iterator for TokenIterator<'a, T> {
+    type Item = Token<'a, T>;
 
     fn next(&mut self) -> Option<Self::Item> {
         // Skip whitespace
-        while self.pos < self.chars.len() {
-            if !char::from_u32(self.chars[self.pos])
-                .expect("invalid char")
-                .is_whitespace()
-            {
-                break;
-            }
+        while self.pos < self.chars.len() && is_whitespace(self.chars[self.pos].value()) {
             self.pos += 1;
         }
 
@@ -40,13 +57,7 @@ impl<'a> Iterator for TokenIterator<'a> {
         let start = self.pos;
 
         // Find end of token
-        while self.pos < self.chars.len() {
-            if char::from_u32(self.chars[self.pos])
-                .expect("invalid char")
-                .is_whitespace()
-            {
-                break;
-            }
+        while self.pos < self.chars.len() && !is_whitespace(self.chars[self.pos].value()) {
             self.pos += 1;
         }
 
@@ -57,12 +68,14 @@ impl<'a> Iterator for TokenIterator<'a> {
 }
 
 /// A sequence of tokens that can be joined back into a string
-pub struct TokenSequence<'a> {
-    tokens: Vec<Token<'a>>,
+#[cfg(feature = "fuzz")]
+pub struct TokenSequence<'a, T> {
+    tokens: Vec<Token<'a, T>>,
 }
 
-impl<'a> TokenSequence<'a> {
-    pub fn new(tokens: Vec<Token<'a>>) -> Self {
+#[cfg(feature = "fuzz")]
+impl<'a, T: SeqElem> TokenSequence<'a, T> {
+    pub fn new(tokens: Vec<Token<'a, T>>) -> Self {
         TokenSequence { tokens }
     }
 
@@ -86,12 +99,13 @@ impl<'a> TokenSequence<'a> {
             if i > 0 {
                 result.push(' ' as u32);
             }
-            result.extend_from_slice(token.chars);
+            result.extend(token.chars.iter().map(|c| c.value()));
         }
         result
     }
 }
 
+#[cfg(feature = "python")]
 #[derive(FromPyObject)]
 pub enum IndexResult {
     #[pyo3(transparent, annotation = "int")]
@@ -102,6 +116,7 @@ pub enum IndexResult {
     String(String),
 }
 
+#[cfg(feature = "python")]
 impl IntoPy<PyObject> for IndexResult {
     fn into_py(self, py: Python<'_>) -> PyObject {
         match self {
@@ -112,6 +127,7 @@ impl IntoPy<PyObject> for IndexResult {
     }
 }
 
+#[cfg(feature = "python")]
 #[derive(Clone, FromPyObject)]
 pub enum StrOrInt {
     #[pyo3(transparent, annotation = "str")]
@@ -120,6 +136,7 @@ pub enum StrOrInt {
     Int(usize),
 }
 
+#[cfg(feature = "python")]
 impl IntoPy<PyObject> for StrOrInt {
     fn into_py(self, py: Python<'_>) -> PyObject {
         match self {
@@ -0,0 +1,270 @@
+use crate::common::SeqElem;
+use std::collections::HashMap;
+
+/// Bit masks of the positions of each character in a sequence, split into
+/// 64 bit words for the bit-parallel algorithms. ASCII characters are looked
+/// up in a direct-indexed table, all other characters in a hashmap.
+pub struct PatternMatchVector {
+    words: usize,
+    /// `words` masks for each ASCII character
+    ascii: Vec<u64>,
+    /// offset of the masks of each non-ASCII character in `extended`
+    offsets: HashMap<u32, usize>,
+    /// the first block is all zeros and is used for missing characters
+    extended: Vec<u64>,
+}
+
+impl PatternMatchVector {
+    pub fn new<T: SeqElem>(s: &[T]) -> Self {
+        Self::from_positions(
+            s.len().div_ceil(64),
+            s.iter().map(|c| c.value()).enumerate(),
+        )
+    }
+
+    /// Builds a single pattern match vector for several sequences, where
+    /// sequence `k` occupies the bits `k * lane_bits..(k + 1) * lane_bits`.
+    /// Each sequence has to fit into its lane and `words` has to be large
+    /// enough for all lanes.
+    pub fn from_lanes<T: SeqElem>(lanes: &[&[T]], lane_bits: usize, words: usize) -> Self {
+        let positions = lanes.iter().enumerate().flat_map(|(lane, s)| {
+            s.iter()
+                .enumerate()
+                .map(move |(i, ch)| (lane * lane_bits + i, ch.value()))
+        });
+        Self::from_positions(words, positions)
+    }
+
+    fn from_positions(words: usize, positions: impl Iterator<Item = (usize, u32)>) -> Self {
+        let mut ascii = vec![0; 128 * words];
+        let mut offsets = HashMap::new();
+        let mut extended = vec![0; words];
+
+        for (i, ch) in positions {
+            let offset = if ch < 128 {
+                ch as usize * words
+            } else {
+                *offsets.entry(ch).or_insert_with(|| {
+                    extended.resize(extended.len() + words, 0);
+                    extended.len() - words
+                })
+            };
+            let masks = if ch < 128 { &mut ascii } else { &mut extended };
+            masks[offset + i / 64] |= 1 << (i % 64);
+        }
+
+        PatternMatchVector {
+            words,
+            ascii,
+            offsets,
+            extended,
+        }
+    }
+
+    pub fn words(&self) -> usize {
+        self.words
+    }
+
+    /// The masks of all words for `ch`.
+    #[inline]
+    pub fn get(&self, ch: u32) -> &[u64] {
+        if ch < 128 {
+            let offset = ch as usize * self.words;
+            &self.ascii[offset..offset + self.words]
+        } else {
+            let offset = self.offsets.get(&ch).copied().unwrap_or(0);
+            &self.extended[offset..offset + self.words]
+        }
+    }
+}
+
+/// The bit vectors of the bit-parallel algorithms after each character of s2,
+/// which are used to backtrack the edit operations.
+pub struct BitMatrix {
+    words: usize,
+    bits: Vec<u64>,
+}
+
+impl BitMatrix {
+    pub fn new(rows: usize, words: usize) -> Self {
+        BitMatrix {
+            words,
+            bits: Vec::with_capacity(rows * words),
+        }
+    }
+
+    pub fn push_row(&mut self, row: &[u64]) {
+        self.bits.extend_from_slice(row);
+    }
+
+    #[inline]
+    pub fn test_bit(&self, row: usize, col: usize) -> bool {
+        self.bits[row * self.words + col / 64] & (1 << (col % 64)) != 0
+    }
+}
+
+/// Upper limit for the number of words `CheckpointedBitMatrix` keeps at once.
+const MAX_BLOCK_WORDS: usize = 1 << 20;
+
+/// Bit matrix whose rows are recomputed on demand instead of being stored.
+///
+/// Row `i` is the state after calling `step(i, state)` on the state of row
+/// `i - 1`. Rows have to be read in non-increasing order, which is the order
+/// a traceback visits them in. Like Hirschberg's algorithm, the remaining rows
+/// are split in half until a block fits into `MAX_BLOCK_WORDS`, keeping only
+/// the state at each split point. This keeps memory linear in the row width
+/// at the cost of computing every row O(log n) times.
+pub struct CheckpointedBitMatrix<F> {
+    words: usize,
+    step: F,
+    max_block_words: usize,
+    checkpoints: Vec<(usize, Vec<u64>)>,
+    block_start: usize,
+    block: BitMatrix,
+}
+
+impl<F: FnMut(usize, &mut [u64])> CheckpointedBitMatrix<F> {
+    pub fn new(rows: usize, initial: Vec<u64>, step: F) -> Self {
+        let words = initial.len();
+        CheckpointedBitMatrix {
+            words,
+            step,
+            max_block_words: MAX_BLOCK_WORDS,
+            checkpoints: vec![(0, initial)],
+            block_start: rows,
+            block: BitMatrix::new(0, words),
+        }
+    }
+
+    pub fn test_bit(&mut self, row: usize, col: usize) -> bool {
+        if row < self.block_start {
+            self.compute_block(row);
+        }
+        self.block.test_bit(row - self.block_start, col)
+    }
+
+    fn compute_block(&mut self, row: usize) {
+        // rows are read in non-increasing order, so later checkpoints are done
+        while self
+            .checkpoints
+            .last()
+            .is_some_and(|&(start, _)| start > row)
+        {
+            self.checkpoints.pop();
+        }
+
+        let max_rows = usize::max(1, self.max_block_words / self.words.max(1));
+        let mut end = self.block_start;
+        loop {
+            let (start, mut state) = match self.checkpoints.last() {
+                Some((start, state)) => (*start, state.clone()),
+                None => unreachable!("the first row is always checkpointed"),
+            };
+
+            if end - start <= max_rows {
+                let mut block = BitMatrix::new(end - start, self.words);
+                for r in start..end {
+                    (self.step)(r, &mut state);
+                    block.push_row(&state);
+                }
+                self.block = block;
+                self.block_start = start;
+                return;
+            }
+
+            let mid = start + (end - start) / 2;
+            for r in start..mid {
+                (self.step)(r, &mut state);
+            }
+            if row >= mid {
+                self.checkpoints.push((mid, state));
+            } else {
+                end = mid;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_match_vector() {
+        let s = (0..130).map(|i| i % 2).collect::<Vec<u32>>();
+        let block = PatternMatchVector::new(&s);
+        assert_eq!(block.words(), 3);
+        assert_eq!(
+            block.get(0),
+            &[0x5555555555555555, 0x5555555555555555, 0b01]
+        );
+        assert_eq!(
+            block.get(1),
+            &[0xAAAAAAAAAAAAAAAA, 0xAAAAAAAAAAAAAAAA, 0b10]
+        );
+        assert_eq!(block.get(2), &[0, 0, 0]);
+    }
+
+    #[test]
+    fn test_pattern_match_vector_extended() {
+        let s = [0x1F600_u32, 97, 0x1F600, 0x20AC];
+        let block = PatternMatchVector::new(&s);
+        assert_eq!(block.words(), 1);
+        assert_eq!(block.get(0x1F600), &[0b0101]);
+        assert_eq!(block.get(97), &[0b0010]);
+        assert_eq!(block.get(0x20AC), &[0b1000]);
+        assert_eq!(block.get(0x20AD), &[0]);
+        assert_eq!(block.get(98), &[0]);
+    }
+
+    #[test]
+    fn test_pattern_match_vector_lanes() {
+        let lanes: [&[u32]; 3] = [&[1, 2], &[2], &[0x20AC, 1]];
+        let block = PatternMatchVector::from_lanes(&lanes, 32, 2);
+        assert_eq!(block.words(), 2);
+        assert_eq!(block.get(1), &[0b01, 0b10]);
+        assert_eq!(block.get(2), &[0b10 | 1 << 32, 0]);
+        assert_eq!(block.get(0x20AC), &[0, 0b01]);
+    }
+
+    #[test]
+    fn test_bit_matrix() {
+        let mut matrix = BitMatrix::new(2, 2);
+        matrix.push_row(&[0b10, 0]);
+        matrix.push_row(&[0, 1]);
+        assert!(matrix.test_bit(0, 1));
+        assert!(!matrix.test_bit(0, 0));
+        assert!(matrix.test_bit(1, 64));
+        assert!(!matrix.test_bit(1, 65));
+    }
+
+    #[test]
+    fn test_checkpointed_bit_matrix() {
+        let step = |row: usize, state: &mut [u64]| {
+            state[0] = state[0].rotate_left(3) ^ row as u64;
+            state[1] = state[1].wrapping_mul(31).wrapping_add(state[0]);
+        };
+        let rows = 1000;
+
+        let mut state = vec![0x1234, 0x5678];
+        let mut expected = BitMatrix::new(rows, 2);
+        for row in 0..rows {
+            step(row, &mut state);
+            expected.push_row(&state);
+        }
+
+        let mut matrix = CheckpointedBitMatrix::new(rows, vec![0x1234, 0x5678], step);
+        matrix.max_block_words = 10;
+        let mut row = rows - 1;
+        loop {
+            for col in [0, 5, 63, 64, 100, 127] {
+                assert_eq!(matrix.test_bit(row, col), expected.test_bit(row, col));
+            }
+            if row < 2 {
+                break;
+            }
+            row -= 1 + row % 2;
+        }
+        assert!(matrix.checkpoints.len() <= 1 + rows.ilog2() as usize);
+    }
+}
@@ -0,0 +1,24 @@
+//! Helpers shared by the unit tests.
+
+/// Converts a string into the `u32` sequence the metrics operate on.
+pub fn to_seq(s: &str) -> Vec<u32> {
+    s.chars().map(|c| c as u32).collect()
+}
+
+/// Reproducible sequence of `len` elements in `offset..offset + alphabet`.
+pub fn pseudo_random_seq_in(seed: u64, len: usize, alphabet: u32, offset: u32) -> Vec<u32> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % alphabet as u64) as u32 + offset
+        })
+        .collect()
+}
+
+/// Reproducible sequence over a four element alphabet, so matches are frequent.
+pub fn pseudo_random_seq(seed: u64, len: usize) -> Vec<u32> {
+    pseudo_random_seq_in(seed, len, 4, 0)
+}
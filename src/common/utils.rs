@@ -1,7 +1,5 @@
+#[allow(dead_code)]
 pub fn is_none<T>(s: Option<&[T]>) -> bool {
     // TODO: check for nan
-    match s {
-        Some(_) => false,
-        None => true,
-    }
+    s.is_none()
 }
@@ -1,21 +1,66 @@
+pub mod batch;
+#[cfg(feature = "qgram")]
+pub mod cosine;
+#[cfg(feature = "damerau_levenshtein")]
 pub mod damerau_levenshtein;
+#[cfg(feature = "phonetic")]
+pub mod editex;
+pub mod gotoh;
 pub mod hamming;
 pub mod indel;
+#[cfg(feature = "qgram")]
+pub mod jaccard;
+#[cfg(feature = "jaro")]
 pub mod jaro;
+#[cfg(feature = "jaro")]
 pub mod jaro_winkler;
 pub mod lcs_seq;
+pub mod lcs_str;
+#[cfg(feature = "levenshtein")]
 pub mod levenshtein;
 pub mod models;
+#[cfg(feature = "phonetic")]
+pub mod mra;
+#[cfg(feature = "osa")]
 pub mod osa;
+#[cfg(feature = "qgram")]
+pub mod overlap;
 pub mod postfix;
 pub mod prefix;
+#[cfg(feature = "qgram")]
+pub mod qgram;
+pub mod ratcliff_obershelp;
+pub mod smith_waterman;
+#[cfg(feature = "qgram")]
+pub mod sorensen_dice;
 
-use crate::common::conv_sequences;
+pub use crate::common::pattern_match::PatternMatchVector;
+
+#[cfg(feature = "python")]
+use crate::common::{conv_sequence_views, conv_sequences, SeqView};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use std::collections::HashMap;
+#[cfg(feature = "python")]
+use std::sync::Arc;
+#[cfg(feature = "python")]
 use std::sync::Mutex;
+#[cfg(feature = "python")]
 use std::sync::OnceLock;
 
+/// Largest distance that has to be computed exactly, so that every distance
+/// above it is rejected by `accepts`. `estimate` should be close to the last
+/// accepted distance and `accepts` has to be monotone.
+fn distance_cutoff(maximum: usize, estimate: f64, accepts: impl Fn(usize) -> bool) -> usize {
+    let mut cutoff = (estimate.max(0.0) as usize).min(maximum);
+    while cutoff < maximum && accepts(cutoff + 1) {
+        cutoff += 1;
+    }
+    cutoff
+}
+
+#[cfg(feature = "python")]
 #[inline]
 fn prep_inputs(
     s1: &Bound<'_, PyAny>,
@@ -43,6 +88,27 @@ fn prep_inputs(
     Ok((s1, s2))
 }
 
+/// Same as the conversion in `prep_inputs`, but borrows `str` and `bytes`
+/// objects for the kernels that are generic over the element width.
+#[cfg(feature = "python")]
+pub(crate) fn conv_views<'a>(
+    s1: &'a Bound<'_, PyAny>,
+    s2: &'a Bound<'_, PyAny>,
+) -> PyResult<(SeqView<'a>, SeqView<'a>)> {
+    match conv_sequence_views(s1, s2) {
+        Ok((Some(s1), Some(s2))) => Ok((s1, s2)),
+        Ok((_, _)) => Err(pyo3::exceptions::PyValueError::new_err(
+            "Cannot handle None",
+        )),
+        Err(e) => Err(pyo3::exceptions::PyTypeError::new_err(format!(
+            "Failed to convert sequences to u32. {}",
+            e
+        ))),
+    }
+}
+
+#[cfg(feature = "python")]
+#[allow(dead_code)]
 pub enum ScorerFlag {
     ResultF64 = 1 << 5,
     ResultI64 = 1 << 6,
@@ -50,6 +116,7 @@ pub enum ScorerFlag {
     Symmetric = 1 << 11,
 }
 
+#[cfg(feature = "python")]
 impl std::ops::BitOr for ScorerFlag {
     type Output = u64;
     fn bitor(self, rhs: Self) -> Self::Output {
@@ -57,18 +124,25 @@ impl std::ops::BitOr for ScorerFlag {
     }
 }
 
+#[cfg(feature = "python")]
+#[allow(dead_code)]
 pub struct ScorerFlags {
     pub optimal_score: u64,
     pub worst_score: u64,
     pub flags: u64,
 }
 
-type ScorerFn = Box<dyn Fn(&HashMap<String, PyObject>) -> ScorerFlags + Send + Sync>;
+#[cfg(feature = "python")]
+type ScorerFn = Arc<dyn Fn(&HashMap<String, PyObject>) -> ScorerFlags + Send + Sync>;
+#[cfg(feature = "python")]
 type ScorerMap = HashMap<String, ScorerFn>;
+#[cfg(feature = "python")]
 type GlobalScorerMutex = Mutex<ScorerMap>;
 
+#[cfg(feature = "python")]
 pub static SCORER_METADATA: OnceLock<GlobalScorerMutex> = OnceLock::new();
 
+#[cfg(feature = "python")]
 pub fn get_scorer_flags(
     scorer: &Bound<'_, PyAny>,
     scorer_kwargs: &HashMap<String, PyObject>,
@@ -77,14 +151,13 @@ pub fn get_scorer_flags(
     let name: String = scorer.getattr("__name__").ok()?.extract().ok()?;
     let fqn = format!("{}.{}", module, name);
 
-    SCORER_METADATA
-        .get()?
-        .lock()
-        .unwrap()
-        .get(&fqn)
-        .map(|f| f(scorer_kwargs))
+    // the lock is released before calling into the metadata, so scorers
+    // wrapping another scorer can look up its flags as well
+    let get_flags = SCORER_METADATA.get()?.lock().unwrap().get(&fqn)?.clone();
+    Some(get_flags(scorer_kwargs))
 }
 
+#[cfg(feature = "python")]
 fn get_scorer_flags_distance() -> ScorerFlags {
     ScorerFlags {
         optimal_score: 0,
@@ -93,6 +166,7 @@ fn get_scorer_flags_distance() -> ScorerFlags {
     }
 }
 
+#[cfg(feature = "python")]
 fn get_scorer_flags_similarity() -> ScorerFlags {
     ScorerFlags {
         optimal_score: 2u64.pow(63) - 1,
@@ -101,6 +175,7 @@ fn get_scorer_flags_similarity() -> ScorerFlags {
     }
 }
 
+#[cfg(feature = "python")]
 fn get_scorer_flags_normalized_distance() -> ScorerFlags {
     ScorerFlags {
         optimal_score: 0,
@@ -109,6 +184,7 @@ fn get_scorer_flags_normalized_distance() -> ScorerFlags {
     }
 }
 
+#[cfg(feature = "python")]
 fn get_scorer_flags_normalized_similarity() -> ScorerFlags {
     ScorerFlags {
         optimal_score: 1,
@@ -117,6 +193,36 @@ fn get_scorer_flags_normalized_similarity() -> ScorerFlags {
     }
 }
 
+/// Clears the symmetric flag for scorers that depend on the argument order.
+#[cfg(feature = "python")]
+fn asymmetric(mut flags: ScorerFlags) -> ScorerFlags {
+    flags.flags &= !(ScorerFlag::Symmetric as u64);
+    flags
+}
+
+/// Clears the symmetric flag of damerau_levenshtein and osa when `weights`
+/// uses different costs for insertions and deletions.
+#[cfg(all(
+    feature = "python",
+    any(feature = "damerau_levenshtein", feature = "osa")
+))]
+fn transposition_flags(
+    flags: ScorerFlags,
+    scorer_kwargs: &HashMap<String, PyObject>,
+) -> ScorerFlags {
+    Python::with_gil(|py| {
+        let weights = scorer_kwargs.get("weights").and_then(|w| {
+            w.extract::<Option<(usize, usize, usize, usize)>>(py)
+                .expect("Failed to extract weights")
+        });
+        match weights {
+            Some((insert, delete, _, _)) if insert != delete => asymmetric(flags),
+            _ => flags,
+        }
+    })
+}
+
+#[cfg(all(feature = "python", feature = "fuzz"))]
 fn get_scorer_flags_fuzz() -> ScorerFlags {
     ScorerFlags {
         optimal_score: 100,
@@ -125,14 +231,33 @@ fn get_scorer_flags_fuzz() -> ScorerFlags {
     }
 }
 
+/// Whether levenshtein is symmetric for the `weights` and `costs` in
+/// `scorer_kwargs`.
+#[cfg(all(feature = "python", feature = "levenshtein"))]
+fn levenshtein_symmetric(py: Python<'_>, scorer_kwargs: &HashMap<String, PyObject>) -> bool {
+    let weights = scorer_kwargs
+        .get("weights")
+        .and_then(|w| {
+            w.extract::<Option<levenshtein::Weights>>(py)
+                .expect("Failed to extract weights")
+        })
+        .unwrap_or(levenshtein::Weights(1, 1, 1));
+    let costs = scorer_kwargs.get("costs").map(|c| {
+        c.extract::<Option<PyRef<'_, levenshtein::CostTable>>>(py)
+            .expect("Failed to extract costs")
+    });
+
+    match costs.flatten() {
+        Some(costs) => costs.is_symmetric(&weights),
+        None => weights.0 == weights.1,
+    }
+}
+
+#[cfg(all(feature = "python", feature = "levenshtein"))]
 fn get_scorer_flags_levenshtein_distance(scorer_kwargs: &HashMap<String, PyObject>) -> ScorerFlags {
     Python::with_gil(|py| {
         let mut flags = ScorerFlag::ResultSizeT as u64;
-        let weights = scorer_kwargs.get("weights").map(|w| {
-            w.extract::<(usize, usize, usize)>(py)
-                .expect("Failed to extract weights")
-        });
-        if weights.is_none() || weights.unwrap().0 == weights.unwrap().1 {
+        if levenshtein_symmetric(py, scorer_kwargs) {
             flags |= ScorerFlag::Symmetric as u64;
         }
         ScorerFlags {
@@ -143,16 +268,13 @@ fn get_scorer_flags_levenshtein_distance(scorer_kwargs: &HashMap<String, PyObjec
     })
 }
 
+#[cfg(all(feature = "python", feature = "levenshtein"))]
 fn get_scorer_flags_levenshtein_similarity(
     scorer_kwargs: &HashMap<String, PyObject>,
 ) -> ScorerFlags {
     Python::with_gil(|py| {
         let mut flags = ScorerFlag::ResultSizeT as u64;
-        let weights = scorer_kwargs.get("weights").map(|w| {
-            w.extract::<(usize, usize, usize)>(py)
-                .expect("Failed to extract weights")
-        });
-        if weights.is_none() || weights.unwrap().0 == weights.unwrap().1 {
+        if levenshtein_symmetric(py, scorer_kwargs) {
             flags |= ScorerFlag::Symmetric as u64;
         }
         ScorerFlags {
@@ -163,16 +285,13 @@ fn get_scorer_flags_levenshtein_similarity(
     })
 }
 
+#[cfg(all(feature = "python", feature = "levenshtein"))]
 fn get_scorer_flags_levenshtein_normalized_distance(
     scorer_kwargs: &HashMap<String, PyObject>,
 ) -> ScorerFlags {
     Python::with_gil(|py| {
         let mut flags = ScorerFlag::ResultF64 as u64;
-        let weights = scorer_kwargs.get("weights").map(|w| {
-            w.extract::<(usize, usize, usize)>(py)
-                .expect("Failed to extract weights")
-        });
-        if weights.is_none() || weights.unwrap().0 == weights.unwrap().1 {
+        if levenshtein_symmetric(py, scorer_kwargs) {
             flags |= ScorerFlag::Symmetric as u64;
         }
         ScorerFlags {
@@ -183,16 +302,13 @@ fn get_scorer_flags_levenshtein_normalized_distance(
     })
 }
 
+#[cfg(all(feature = "python", feature = "levenshtein"))]
 fn get_scorer_flags_levenshtein_normalized_similarity(
     scorer_kwargs: &HashMap<String, PyObject>,
 ) -> ScorerFlags {
     Python::with_gil(|py| {
         let mut flags = ScorerFlag::ResultF64 as u64;
-        let weights = scorer_kwargs.get("weights").map(|w| {
-            w.extract::<(usize, usize, usize)>(py)
-                .expect("Failed to extract weights")
-        });
-        if weights.is_none() || weights.unwrap().0 == weights.unwrap().1 {
+        if levenshtein_symmetric(py, scorer_kwargs) {
             flags |= ScorerFlag::Symmetric as u64;
         }
         ScorerFlags {
@@ -203,228 +319,404 @@ fn get_scorer_flags_levenshtein_normalized_similarity(
     })
 }
 
+#[cfg(feature = "python")]
 pub fn setup_scorer_metadata() {
     SCORER_METADATA.get_or_init(|| Mutex::new(HashMap::new()));
 
     // hamming
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "hamming.distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_distance()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "hamming.similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_similarity()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_similarity()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "hamming.normalized_distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "hamming.normalized_similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
     );
 
     // lcs seq
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "lcs_seq.distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_distance()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "lcs_seq.similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_similarity()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_similarity()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "lcs_seq.normalized_distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "lcs_seq.normalized_similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
     );
 
     // indel
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "indel.distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_distance()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "indel.similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_similarity()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_similarity()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "indel.normalized_distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "indel.normalized_similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
     );
 
-    // levenshtein
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "levenshtein.distance".to_string(),
-        Box::new(get_scorer_flags_levenshtein_distance),
-    );
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "levenshtein.similarity".to_string(),
-        Box::new(get_scorer_flags_levenshtein_similarity),
-    );
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "levenshtein.normalized_distance".to_string(),
-        Box::new(get_scorer_flags_levenshtein_normalized_distance),
-    );
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "levenshtein.normalized_similarity".to_string(),
-        Box::new(get_scorer_flags_levenshtein_normalized_similarity),
-    );
+    #[cfg(feature = "levenshtein")]
+    {
+        // levenshtein
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "levenshtein.distance".to_string(),
+            Arc::new(get_scorer_flags_levenshtein_distance),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "levenshtein.similarity".to_string(),
+            Arc::new(get_scorer_flags_levenshtein_similarity),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "levenshtein.normalized_distance".to_string(),
+            Arc::new(get_scorer_flags_levenshtein_normalized_distance),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "levenshtein.normalized_similarity".to_string(),
+            Arc::new(get_scorer_flags_levenshtein_normalized_similarity),
+        );
+    }
 
-    // damerau-levenshtein
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "damerau_levenshtein.distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_distance()),
-    );
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "damerau_levenshtein.similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_similarity()),
-    );
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "damerau_levenshtein.normalized_distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
-    );
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "damerau_levenshtein.normalized_similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
-    );
+    #[cfg(feature = "damerau_levenshtein")]
+    {
+        // damerau-levenshtein
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "damerau_levenshtein.distance".to_string(),
+            Arc::new(|scorer_kwargs| {
+                transposition_flags(get_scorer_flags_distance(), scorer_kwargs)
+            }),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "damerau_levenshtein.similarity".to_string(),
+            Arc::new(|scorer_kwargs| {
+                transposition_flags(get_scorer_flags_similarity(), scorer_kwargs)
+            }),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "damerau_levenshtein.normalized_distance".to_string(),
+            Arc::new(|scorer_kwargs| {
+                transposition_flags(get_scorer_flags_normalized_distance(), scorer_kwargs)
+            }),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "damerau_levenshtein.normalized_similarity".to_string(),
+            Arc::new(|scorer_kwargs| {
+                transposition_flags(get_scorer_flags_normalized_similarity(), scorer_kwargs)
+            }),
+        );
+    }
 
-    // jaro
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "jaro.distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
-    );
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "jaro.similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
-    );
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "jaro.normalized_distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
-    );
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "jaro.normalized_similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
-    );
+    #[cfg(feature = "jaro")]
+    {
+        // jaro
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "jaro.distance".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "jaro.similarity".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "jaro.normalized_distance".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "jaro.normalized_similarity".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        );
+    }
 
-    // jaro-winkler
+    #[cfg(feature = "jaro")]
+    {
+        // jaro-winkler
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "jaro_winkler.distance".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "jaro_winkler.similarity".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "jaro_winkler.normalized_distance".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "jaro_winkler.normalized_similarity".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        );
+    }
+
+    #[cfg(feature = "phonetic")]
+    {
+        // phonetic edit distances
+        for module in ["editex", "mra"] {
+            SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+                format!("{module}.distance"),
+                Arc::new(|_scorer_kwargs| get_scorer_flags_distance()),
+            );
+            SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+                format!("{module}.similarity"),
+                Arc::new(|_scorer_kwargs| get_scorer_flags_similarity()),
+            );
+            SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+                format!("{module}.normalized_distance"),
+                Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+            );
+            SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+                format!("{module}.normalized_similarity"),
+                Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+            );
+        }
+    }
+
+    #[cfg(feature = "osa")]
+    {
+        // OSA
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "osa.distance".to_string(),
+            Arc::new(|scorer_kwargs| {
+                transposition_flags(get_scorer_flags_distance(), scorer_kwargs)
+            }),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "osa.similarity".to_string(),
+            Arc::new(|scorer_kwargs| {
+                transposition_flags(get_scorer_flags_similarity(), scorer_kwargs)
+            }),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "osa.normalized_distance".to_string(),
+            Arc::new(|scorer_kwargs| {
+                transposition_flags(get_scorer_flags_normalized_distance(), scorer_kwargs)
+            }),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "osa.normalized_similarity".to_string(),
+            Arc::new(|scorer_kwargs| {
+                transposition_flags(get_scorer_flags_normalized_similarity(), scorer_kwargs)
+            }),
+        );
+    }
+
+    #[cfg(feature = "qgram")]
+    {
+        // q-gram coefficients
+        for module in ["jaccard", "sorensen_dice", "cosine", "overlap"] {
+            SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+                format!("{module}.distance"),
+                Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+            );
+            SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+                format!("{module}.similarity"),
+                Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+            );
+            SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+                format!("{module}.normalized_distance"),
+                Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+            );
+            SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+                format!("{module}.normalized_similarity"),
+                Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+            );
+        }
+    }
+
+    // gotoh
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "jaro_winkler.distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        "gotoh.distance".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "jaro_winkler.similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        "gotoh.similarity".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_similarity()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "jaro_winkler.normalized_distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        "gotoh.normalized_distance".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "jaro_winkler.normalized_similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        "gotoh.normalized_similarity".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
     );
 
-    // OSA
+    // lcs_str
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "osa.distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_distance()),
+        "lcs_str.distance".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "osa.similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_similarity()),
+        "lcs_str.similarity".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_similarity()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "osa.normalized_distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        "lcs_str.normalized_distance".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "osa.normalized_similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        "lcs_str.normalized_similarity".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
     );
 
     // postfix
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "postfix.distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_distance()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "postfix.similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_similarity()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_similarity()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "postfix.normalized_distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "postfix.normalized_similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
     );
 
     // prefix
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "prefix.distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_distance()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "prefix.similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_similarity()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_similarity()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "prefix.normalized_distance".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
         "prefix.normalized_similarity".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
     );
 
-    // fuzz
+    // ratcliff_obershelp, the junk heuristics only look at s2
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "fuzz.ratio".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        "ratcliff_obershelp.distance".to_string(),
+        Arc::new(|_scorer_kwargs| asymmetric(get_scorer_flags_distance())),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "fuzz.partial_ratio".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        "ratcliff_obershelp.similarity".to_string(),
+        Arc::new(|_scorer_kwargs| asymmetric(get_scorer_flags_similarity())),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "fuzz.token_sort_ratio".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        "ratcliff_obershelp.normalized_distance".to_string(),
+        Arc::new(|_scorer_kwargs| asymmetric(get_scorer_flags_normalized_distance())),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "fuzz.token_set_ratio".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
-    );
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "fuzz.token_ratio".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
-    );
-    SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "fuzz.partial_token_sort_ratio".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        "ratcliff_obershelp.normalized_similarity".to_string(),
+        Arc::new(|_scorer_kwargs| asymmetric(get_scorer_flags_normalized_similarity())),
     );
+
+    // smith_waterman
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "fuzz.partial_token_set_ratio".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        "smith_waterman.distance".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "fuzz.partial_token_ratio".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        "smith_waterman.similarity".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_similarity()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "fuzz.WRatio".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        "smith_waterman.normalized_distance".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_distance()),
     );
     SCORER_METADATA.get().unwrap().lock().unwrap().insert(
-        "fuzz.QRatio".to_string(),
-        Box::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        "smith_waterman.normalized_similarity".to_string(),
+        Arc::new(|_scorer_kwargs| get_scorer_flags_normalized_similarity()),
     );
+
+    #[cfg(feature = "phonetic")]
+    {
+        // phonetic
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "phonetic.score".to_string(),
+            Arc::new(crate::phonetic::get_scorer_flags_score),
+        );
+    }
+
+    #[cfg(feature = "fuzz")]
+    {
+        // fuzz
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "fuzz.ratio".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "fuzz.partial_ratio".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "fuzz.token_sort_ratio".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "fuzz.token_set_ratio".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "fuzz.token_ratio".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "fuzz.partial_token_sort_ratio".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "fuzz.partial_token_set_ratio".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "fuzz.partial_token_ratio".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "fuzz.WRatio".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "fuzz.QRatio".to_string(),
+            Arc::new(|_scorer_kwargs| get_scorer_flags_fuzz()),
+        );
+        SCORER_METADATA.get().unwrap().lock().unwrap().insert(
+            "fuzz.monge_elkan".to_string(),
+            Arc::new(|scorer_kwargs| {
+                let symmetric = Python::with_gil(|py| {
+                    scorer_kwargs
+                        .get("symmetric")
+                        .is_none_or(|s| s.extract::<bool>(py).expect("Failed to extract symmetric"))
+                });
+                match symmetric {
+                    true => get_scorer_flags_fuzz(),
+                    false => asymmetric(get_scorer_flags_fuzz()),
+                }
+            }),
+        );
+    }
 }
@@ -0,0 +1,541 @@
+//! Batched scoring of one query against many short choices.
+//!
+//! The choices are packed into lanes of 8, 16, 32 or 64 bits, depending on
+//! their length, and the bit-parallel kernels run on all lanes at once. The
+//! lanes are processed with SSE2 or AVX2 on x86_64 and with SWAR arithmetic
+//! on plain `u64` words elsewhere.
+
+use crate::common::pattern_match::PatternMatchVector;
+use crate::common::SeqElem;
+
+/// Maximum length of a choice that can be scored in a batch.
+pub const MAX_LEN: usize = 64;
+
+/// Vector of lanes, which supports lane-wise addition and subtraction for
+/// lanes of `BITS` bits.
+trait Lanes: Copy {
+    /// Number of `u64` words per vector.
+    const WORDS: usize;
+
+    unsafe fn load(src: &[u64]) -> Self;
+    unsafe fn store(self, dst: &mut [u64]);
+    unsafe fn splat(value: u64) -> Self;
+    unsafe fn and(self, other: Self) -> Self;
+    unsafe fn or(self, other: Self) -> Self;
+    #[cfg_attr(not(feature = "levenshtein"), allow(dead_code))]
+    unsafe fn xor(self, other: Self) -> Self;
+    unsafe fn add<const BITS: u32>(self, other: Self) -> Self;
+    unsafe fn sub<const BITS: u32>(self, other: Self) -> Self;
+}
+
+/// Mask with the highest bit of every lane set.
+const fn high_bits(bits: u32) -> u64 {
+    let mut mask = 0;
+    let mut bit = bits - 1;
+    while bit < 64 {
+        mask |= 1 << bit;
+        bit += bits;
+    }
+    mask
+}
+
+/// Lanes inside of a single `u64`, which keeps carries from crossing lane
+/// boundaries by handling the highest bit of every lane separately.
+#[derive(Clone, Copy)]
+#[cfg_attr(all(target_arch = "x86_64", not(test)), allow(dead_code))]
+struct Swar(u64);
+
+#[cfg_attr(all(target_arch = "x86_64", not(test)), allow(dead_code))]
+impl Lanes for Swar {
+    const WORDS: usize = 1;
+
+    unsafe fn load(src: &[u64]) -> Self {
+        Swar(src[0])
+    }
+
+    unsafe fn store(self, dst: &mut [u64]) {
+        dst[0] = self.0;
+    }
+
+    unsafe fn splat(value: u64) -> Self {
+        Swar(value)
+    }
+
+    unsafe fn and(self, other: Self) -> Self {
+        Swar(self.0 & other.0)
+    }
+
+    unsafe fn or(self, other: Self) -> Self {
+        Swar(self.0 | other.0)
+    }
+
+    unsafe fn xor(self, other: Self) -> Self {
+        Swar(self.0 ^ other.0)
+    }
+
+    unsafe fn add<const BITS: u32>(self, other: Self) -> Self {
+        let high = high_bits(BITS);
+        let sum = (self.0 & !high).wrapping_add(other.0 & !high);
+        Swar(sum ^ ((self.0 ^ other.0) & high))
+    }
+
+    unsafe fn sub<const BITS: u32>(self, other: Self) -> Self {
+        let high = high_bits(BITS);
+        let diff = (self.0 | high).wrapping_sub(other.0 & !high);
+        Swar(diff ^ ((self.0 ^ !other.0) & high))
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use super::Lanes;
+    use crate::common::SeqElem;
+    use std::arch::x86_64::*;
+
+    #[derive(Clone, Copy)]
+    pub struct Sse2(__m128i);
+
+    impl Lanes for Sse2 {
+        const WORDS: usize = 2;
+
+        #[inline(always)]
+        unsafe fn load(src: &[u64]) -> Self {
+            Sse2(_mm_loadu_si128(src[..2].as_ptr() as *const __m128i))
+        }
+
+        #[inline(always)]
+        unsafe fn store(self, dst: &mut [u64]) {
+            _mm_storeu_si128(dst[..2].as_mut_ptr() as *mut __m128i, self.0)
+        }
+
+        #[inline(always)]
+        unsafe fn splat(value: u64) -> Self {
+            Sse2(_mm_set1_epi64x(value as i64))
+        }
+
+        #[inline(always)]
+        unsafe fn and(self, other: Self) -> Self {
+            Sse2(_mm_and_si128(self.0, other.0))
+        }
+
+        #[inline(always)]
+        unsafe fn or(self, other: Self) -> Self {
+            Sse2(_mm_or_si128(self.0, other.0))
+        }
+
+        #[inline(always)]
+        unsafe fn xor(self, other: Self) -> Self {
+            Sse2(_mm_xor_si128(self.0, other.0))
+        }
+
+        #[inline(always)]
+        unsafe fn add<const BITS: u32>(self, other: Self) -> Self {
+            Sse2(match BITS {
+                8 => _mm_add_epi8(self.0, other.0),
+                16 => _mm_add_epi16(self.0, other.0),
+                32 => _mm_add_epi32(self.0, other.0),
+                _ => _mm_add_epi64(self.0, other.0),
+            })
+        }
+
+        #[inline(always)]
+        unsafe fn sub<const BITS: u32>(self, other: Self) -> Self {
+            Sse2(match BITS {
+                8 => _mm_sub_epi8(self.0, other.0),
+                16 => _mm_sub_epi16(self.0, other.0),
+                32 => _mm_sub_epi32(self.0, other.0),
+                _ => _mm_sub_epi64(self.0, other.0),
+            })
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct Avx2(__m256i);
+
+    impl Lanes for Avx2 {
+        const WORDS: usize = 4;
+
+        #[inline(always)]
+        unsafe fn load(src: &[u64]) -> Self {
+            Avx2(_mm256_loadu_si256(src[..4].as_ptr() as *const __m256i))
+        }
+
+        #[inline(always)]
+        unsafe fn store(self, dst: &mut [u64]) {
+            _mm256_storeu_si256(dst[..4].as_mut_ptr() as *mut __m256i, self.0)
+        }
+
+        #[inline(always)]
+        unsafe fn splat(value: u64) -> Self {
+            Avx2(_mm256_set1_epi64x(value as i64))
+        }
+
+        #[inline(always)]
+        unsafe fn and(self, other: Self) -> Self {
+            Avx2(_mm256_and_si256(self.0, other.0))
+        }
+
+        #[inline(always)]
+        unsafe fn or(self, other: Self) -> Self {
+            Avx2(_mm256_or_si256(self.0, other.0))
+        }
+
+        #[inline(always)]
+        unsafe fn xor(self, other: Self) -> Self {
+            Avx2(_mm256_xor_si256(self.0, other.0))
+        }
+
+        #[inline(always)]
+        unsafe fn add<const BITS: u32>(self, other: Self) -> Self {
+            Avx2(match BITS {
+                8 => _mm256_add_epi8(self.0, other.0),
+                16 => _mm256_add_epi16(self.0, other.0),
+                32 => _mm256_add_epi32(self.0, other.0),
+                _ => _mm256_add_epi64(self.0, other.0),
+            })
+        }
+
+        #[inline(always)]
+        unsafe fn sub<const BITS: u32>(self, other: Self) -> Self {
+            Avx2(match BITS {
+                8 => _mm256_sub_epi8(self.0, other.0),
+                16 => _mm256_sub_epi16(self.0, other.0),
+                32 => _mm256_sub_epi32(self.0, other.0),
+                _ => _mm256_sub_epi64(self.0, other.0),
+            })
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn lcs_avx2<Q: SeqElem, const BITS: u32>(
+        block: &super::PatternMatchVector,
+        query: &[Q],
+    ) -> Vec<u64> {
+        super::lcs_lanes::<Avx2, Q, BITS>(block, query)
+    }
+
+    #[cfg(feature = "levenshtein")]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn levenshtein_avx2<Q: SeqElem, const BITS: u32>(
+        block: &super::PatternMatchVector,
+        query: &[Q],
+    ) -> (Vec<u64>, Vec<u64>) {
+        super::levenshtein_lanes::<Avx2, Q, BITS>(block, query)
+    }
+}
+
+/// Bit-parallel LCS of Hyyrö on every lane of `block`. Returns the final
+/// state, in which the zero bits of each lane mark the matched characters.
+#[inline(always)]
+unsafe fn lcs_lanes<V: Lanes, Q: SeqElem, const BITS: u32>(
+    block: &PatternMatchVector,
+    query: &[Q],
+) -> Vec<u64> {
+    let mut result = vec![!0_u64; block.words()];
+    for offset in (0..block.words()).step_by(V::WORDS) {
+        let mut state = V::splat(!0);
+        for &ch in query {
+            let matches = V::load(&block.get(ch.value())[offset..]);
+            let update = state.and(matches);
+            state = state.add::<BITS>(update).or(state.sub::<BITS>(update));
+        }
+        state.store(&mut result[offset..]);
+    }
+    result
+}
+
+/// Bit-parallel Levenshtein distance of Hyyrö on every lane of `block`.
+/// Returns VP and VN of the last column.
+#[cfg(feature = "levenshtein")]
+#[inline(always)]
+unsafe fn levenshtein_lanes<V: Lanes, Q: SeqElem, const BITS: u32>(
+    block: &PatternMatchVector,
+    query: &[Q],
+) -> (Vec<u64>, Vec<u64>) {
+    let ones = V::splat(!0);
+    let lowest = V::splat(high_bits(BITS) >> (BITS - 1));
+    let mut result_vp = vec![0_u64; block.words()];
+    let mut result_vn = vec![0_u64; block.words()];
+
+    for offset in (0..block.words()).step_by(V::WORDS) {
+        let mut vp = ones;
+        let mut vn = V::splat(0);
+        for &ch in query {
+            // Step 1: Computing D0
+            let x = V::load(&block.get(ch.value())[offset..]);
+            let d0 = x.and(vp).add::<BITS>(vp).xor(vp).or(x).or(vn);
+
+            // Step 2: Computing HP and HN
+            let hp = vn.or(d0.or(vp).xor(ones));
+            let hn = d0.and(vp);
+
+            // Step 3: Computing VP and VN
+            let hp = hp.add::<BITS>(hp).or(lowest);
+            let hn = hn.add::<BITS>(hn);
+            vp = hn.or(d0.or(hp).xor(ones));
+            vn = hp.and(d0);
+        }
+        vp.store(&mut result_vp[offset..]);
+        vn.store(&mut result_vn[offset..]);
+    }
+
+    (result_vp, result_vn)
+}
+
+fn lcs_dispatch<Q: SeqElem, const BITS: u32>(block: &PatternMatchVector, query: &[Q]) -> Vec<u64> {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        if is_x86_feature_detected!("avx2") {
+            x86::lcs_avx2::<Q, BITS>(block, query)
+        } else {
+            lcs_lanes::<x86::Sse2, Q, BITS>(block, query)
+        }
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    unsafe {
+        lcs_lanes::<Swar, Q, BITS>(block, query)
+    }
+}
+
+#[cfg(feature = "levenshtein")]
+fn levenshtein_dispatch<Q: SeqElem, const BITS: u32>(
+    block: &PatternMatchVector,
+    query: &[Q],
+) -> (Vec<u64>, Vec<u64>) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        if is_x86_feature_detected!("avx2") {
+            x86::levenshtein_avx2::<Q, BITS>(block, query)
+        } else {
+            levenshtein_lanes::<x86::Sse2, Q, BITS>(block, query)
+        }
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    unsafe {
+        levenshtein_lanes::<Swar, Q, BITS>(block, query)
+    }
+}
+
+/// Counts the bits of `lane` that lie within the first `len` bits.
+fn lane_count(words: &[u64], lane: usize, lane_bits: usize, len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    let start = lane * lane_bits;
+    let mask = u64::MAX >> (64 - len);
+    ((words[start / 64] >> (start % 64)) & mask).count_ones() as usize
+}
+
+/// Groups the choices by the smallest lane width they fit into and calls
+/// `score` for every group with the choices, their lane width and the
+/// pattern match vector of the group.
+fn for_each_lane_group<C: SeqElem>(
+    choices: &[&[C]],
+    mut score: impl FnMut(&[usize], u32, &PatternMatchVector),
+) {
+    assert!(
+        choices.iter().all(|choice| choice.len() <= MAX_LEN),
+        "choices of a batch can have at most {} elements",
+        MAX_LEN
+    );
+
+    for (min_len, lane_bits) in [(0, 8), (9, 16), (17, 32), (33, 64)] {
+        let group = (0..choices.len())
+            .filter(|&i| (min_len..=lane_bits as usize).contains(&choices[i].len()))
+            .collect::<Vec<_>>();
+        if group.is_empty() {
+            continue;
+        }
+
+        // pad to whole AVX2 vectors, so every kernel can run on all words
+        let words = (group.len() * lane_bits as usize).div_ceil(256) * 4;
+        let lanes = group.iter().map(|&i| choices[i]).collect::<Vec<_>>();
+        let block = PatternMatchVector::from_lanes(&lanes, lane_bits as usize, words);
+        score(&group, lane_bits, &block);
+    }
+}
+
+/// Length of the longest common subsequence of `query` and every choice.
+/// Choices can have at most `MAX_LEN` elements.
+pub fn lcs_seq_similarity<Q: SeqElem, C: SeqElem>(query: &[Q], choices: &[&[C]]) -> Vec<usize> {
+    let mut result = vec![0; choices.len()];
+    for_each_lane_group(choices, |group, lane_bits, block| {
+        let state = match lane_bits {
+            8 => lcs_dispatch::<Q, 8>(block, query),
+            16 => lcs_dispatch::<Q, 16>(block, query),
+            32 => lcs_dispatch::<Q, 32>(block, query),
+            _ => lcs_dispatch::<Q, 64>(block, query),
+        };
+        let inverted = state.iter().map(|word| !word).collect::<Vec<_>>();
+        for (lane, &i) in group.iter().enumerate() {
+            result[i] = lane_count(&inverted, lane, lane_bits as usize, choices[i].len());
+        }
+    });
+    result
+}
+
+/// Indel distance between `query` and every choice. Choices can have at most
+/// `MAX_LEN` elements.
+pub fn indel_distance<Q: SeqElem, C: SeqElem>(query: &[Q], choices: &[&[C]]) -> Vec<usize> {
+    lcs_seq_similarity(query, choices)
+        .into_iter()
+        .zip(choices)
+        .map(|(sim, choice)| query.len() + choice.len() - 2 * sim)
+        .collect()
+}
+
+/// Uniform Levenshtein distance between `query` and every choice. Choices can
+/// have at most `MAX_LEN` elements.
+#[cfg(feature = "levenshtein")]
+pub fn levenshtein_distance<Q: SeqElem, C: SeqElem>(query: &[Q], choices: &[&[C]]) -> Vec<usize> {
+    let mut result = vec![0; choices.len()];
+    for_each_lane_group(choices, |group, lane_bits, block| {
+        let (vp, vn) = match lane_bits {
+            8 => levenshtein_dispatch::<Q, 8>(block, query),
+            16 => levenshtein_dispatch::<Q, 16>(block, query),
+            32 => levenshtein_dispatch::<Q, 32>(block, query),
+            _ => levenshtein_dispatch::<Q, 64>(block, query),
+        };
+        // the last column starts at D[0][n] = n and changes by VP - VN per row
+        for (lane, &i) in group.iter().enumerate() {
+            let len = choices[i].len();
+            result[i] = query.len() + lane_count(&vp, lane, lane_bits as usize, len)
+                - lane_count(&vn, lane, lane_bits as usize, len);
+        }
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::pseudo_random_seq_in;
+    use crate::distance::{indel, lcs_seq};
+
+    fn batch_seq(seed: u64, len: usize) -> Vec<u32> {
+        let offset = if seed.is_multiple_of(3) { 200 } else { 0 };
+        pseudo_random_seq_in(seed, len, 4, offset)
+    }
+
+    fn choices() -> Vec<Vec<u32>> {
+        (0..150)
+            .map(|i| batch_seq(i, (i as usize * 7) % (MAX_LEN + 1)))
+            .collect()
+    }
+
+    #[test]
+    fn test_swar() {
+        unsafe {
+            let x = Swar(0x01FF_0080_7F00_FF01);
+            let y = Swar(0x0101_0080_0101_0101);
+            assert_eq!(x.add::<8>(y).0, 0x0200_0000_8001_0002);
+            assert_eq!(x.sub::<8>(y).0, 0x00FE_0000_7EFF_FE00);
+            assert_eq!(x.add::<16>(y).0, 0x0300_0100_8001_0002);
+            assert_eq!(x.add::<64>(y).0, x.0.wrapping_add(y.0));
+        }
+    }
+
+    #[test]
+    fn test_lcs_lanes_swar() {
+        let choices = choices();
+        let query = batch_seq(1000, 40);
+        for choice in &choices {
+            let block = PatternMatchVector::from_lanes(&[choice], 64, 1);
+            let state = unsafe { lcs_lanes::<Swar, _, 64>(&block, &query) };
+            assert_eq!(
+                lane_count(&[!state[0]], 0, 64, choice.len()),
+                lcs_seq::similarity(choice, &query, None)
+            );
+        }
+    }
+
+    #[test]
+    fn test_lcs_seq_similarity() {
+        let choices = choices();
+        let refs = choices.iter().map(|c| c.as_slice()).collect::<Vec<_>>();
+        for query_len in [0, 1, 10, 64, 100] {
+            let query = batch_seq(1000 + query_len as u64, query_len);
+            let expected = refs
+                .iter()
+                .map(|choice| lcs_seq::similarity(choice, &query, None))
+                .collect::<Vec<_>>();
+            assert_eq!(lcs_seq_similarity(&query, &refs), expected);
+
+            let expected = refs
+                .iter()
+                .map(|choice| indel::distance(&query, choice, None))
+                .collect::<Vec<_>>();
+            assert_eq!(indel_distance(&query, &refs), expected);
+        }
+    }
+
+    #[test]
+    fn test_mixed_element_widths() {
+        let choices = choices();
+        let refs = choices.iter().map(|c| c.as_slice()).collect::<Vec<_>>();
+        // the same characters stored with one and two bytes each
+        let choices_ucs2 = choices
+            .iter()
+            .map(|c| c.iter().map(|&ch| ch as u16).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let refs_ucs2 = choices_ucs2
+            .iter()
+            .map(|c| c.as_slice())
+            .collect::<Vec<_>>();
+        for query_len in [1, 10, 64] {
+            let query = batch_seq(3000 + query_len as u64, query_len);
+            let query_ucs1 = query.iter().map(|&ch| ch as u8).collect::<Vec<_>>();
+            assert_eq!(
+                lcs_seq_similarity(&query_ucs1, &refs_ucs2),
+                lcs_seq_similarity(&query, &refs)
+            );
+            #[cfg(feature = "levenshtein")]
+            assert_eq!(
+                levenshtein_distance(&query_ucs1, &refs_ucs2),
+                levenshtein_distance(&query, &refs)
+            );
+        }
+    }
+
+    #[cfg(feature = "levenshtein")]
+    #[test]
+    fn test_levenshtein_distance() {
+        use crate::distance::levenshtein;
+
+        let choices = choices();
+        let refs = choices.iter().map(|c| c.as_slice()).collect::<Vec<_>>();
+        for query_len in [0, 1, 10, 64, 100] {
+            let query = batch_seq(2000 + query_len as u64, query_len);
+            let expected = refs
+                .iter()
+                .map(|choice| levenshtein::distance(&query, choice, None, None))
+                .collect::<Vec<_>>();
+            assert_eq!(levenshtein_distance(&query, &refs), expected);
+
+            for lane_bits in [8, 16, 32, 64] {
+                let short = refs
+                    .iter()
+                    .filter(|c| c.len() <= lane_bits)
+                    .copied()
+                    .collect::<Vec<_>>();
+                let words = (short.len() * lane_bits).div_ceil(64);
+                let block = PatternMatchVector::from_lanes(&short, lane_bits, words);
+                let (vp, vn) = unsafe {
+                    match lane_bits {
+                        8 => levenshtein_lanes::<Swar, _, 8>(&block, &query),
+                        16 => levenshtein_lanes::<Swar, _, 16>(&block, &query),
+                        32 => levenshtein_lanes::<Swar, _, 32>(&block, &query),
+                        _ => levenshtein_lanes::<Swar, _, 64>(&block, &query),
+                    }
+                };
+                for (lane, choice) in short.iter().enumerate() {
+                    let dist = query.len() + lane_count(&vp, lane, lane_bits, choice.len())
+                        - lane_count(&vn, lane, lane_bits, choice.len());
+                    assert_eq!(dist, levenshtein::distance(&query, choice, None, None));
+                }
+            }
+        }
+    }
+}
@@ -1,11 +1,89 @@
+use crate::common::common_affix;
+#[cfg(feature = "python")]
 use crate::distance::prep_inputs;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 use std::cmp::min;
 use std::collections::HashMap;
 
-fn damerau_levenshtein_distance_zhao(s1: &[u32], s2: &[u32]) -> usize {
+/// Costs in the form `(insert, delete, substitute, transpose)`. A
+/// transposition swaps two characters, which may be separated by deletions in
+/// s1 and insertions in s2.
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weights(pub usize, pub usize, pub usize, pub usize);
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights(1, 1, 1, 1)
+    }
+}
+
+/// Transpositions are never required to turn s1 into s2, so this is the same
+/// as the maximum of the weighted Levenshtein distance.
+fn damerau_levenshtein_maximum(s1: &[u32], s2: &[u32], weights: &Weights) -> usize {
+    let len1 = s1.len();
+    let len2 = s2.len();
+    let (insert, delete, substitute) = (weights.0, weights.1, weights.2);
+
+    let max_dist = len1 * delete + len2 * insert;
+
+    if len1 >= len2 {
+        max_dist.min(len2 * substitute + (len1 - len2) * delete)
+    } else {
+        max_dist.min(len1 * substitute + (len2 - len1) * insert)
+    }
+}
+
+/// Maps a sequence element to the last row of s1 it occurred in.
+trait LastRowId {
+    fn get(&self, ch: u32) -> isize;
+    fn insert(&mut self, ch: u32, row: isize);
+}
+
+impl LastRowId for HashMap<u32, isize> {
+    fn get(&self, ch: u32) -> isize {
+        *HashMap::get(self, &ch).unwrap_or(&-1)
+    }
+
+    fn insert(&mut self, ch: u32, row: isize) {
+        HashMap::insert(self, ch, row);
+    }
+}
+
+/// Direct lookup table for sequences that only contain elements below 256.
+struct ExtendedAsciiRowId([isize; 256]);
+
+impl LastRowId for ExtendedAsciiRowId {
+    fn get(&self, ch: u32) -> isize {
+        self.0[ch as usize]
+    }
+
+    fn insert(&mut self, ch: u32, row: isize) {
+        self.0[ch as usize] = row;
+    }
+}
+
+fn damerau_levenshtein_distance_zhao(s1: &[u32], s2: &[u32], score_cutoff: usize) -> usize {
+    if s1.iter().chain(s2).all(|&ch| ch < 256) {
+        zhao_banded(s1, s2, score_cutoff, ExtendedAsciiRowId([-1; 256]))
+    } else {
+        zhao_banded(s1, s2, score_cutoff, HashMap::new())
+    }
+}
+
+/// Zhao's algorithm restricted to the cells within `score_cutoff` of the
+/// main diagonal. Cells outside the band have a distance above the cutoff,
+/// so they are treated as unreachable. Returns `score_cutoff + 1` once a
+/// whole row exceeds the cutoff.
+fn zhao_banded(
+    s1: &[u32],
+    s2: &[u32],
+    score_cutoff: usize,
+    mut last_row_id: impl LastRowId,
+) -> usize {
     let max_value = usize::max(s1.len(), s2.len()) + 1;
-    let mut last_row_id = HashMap::new();
+    let band = min(score_cutoff, max_value);
     let size = s2.len() + 2;
 
     let mut fr = vec![max_value; size];
@@ -15,12 +93,17 @@ fn damerau_levenshtein_distance_zhao(s1: &[u32], s2: &[u32]) -> usize {
 
     for i in 1..=s1.len() {
         std::mem::swap(&mut r, &mut r1);
+        let j_start = usize::max(1, i.saturating_sub(band));
+        let j_end = min(s2.len(), i + band);
+
         let mut last_col_id: isize = -1;
-        let mut last_i2l1 = r[0];
-        r[0] = i;
+        let mut last_i2l1 = r[j_start - 1];
+        // the cell left of the band still holds a value of row i - 2
+        r[j_start - 1] = if j_start == 1 { i } else { max_value };
         let mut t = max_value;
+        let mut row_min = r[j_start - 1];
 
-        for j in 1..=s2.len() {
+        for j in j_start..=j_end {
             let diag = r1[j - 1] + if s1[i - 1] != s2[j - 1] { 1 } else { 0 };
             let left = r[j - 1] + 1;
             let up = r1[j] + 1;
@@ -31,10 +114,11 @@ fn damerau_levenshtein_distance_zhao(s1: &[u32], s2: &[u32]) -> usize {
                 fr[j] = if j >= 2 { r1[j - 2] } else { r1[size - 1] }; // save H_k-1,j-2
                 t = last_i2l1; // save H_i-2,l-1
             } else {
-                let k = *last_row_id.get(&s2[j - 1]).unwrap_or(&(-1_isize));
+                let k = last_row_id.get(s2[j - 1]);
                 let l = last_col_id;
 
-                if (j as isize - l) == 1 {
+                // fr[j] is only up to date if the match in row k was inside the band
+                if (j as isize - l) == 1 && j as isize - k <= band as isize {
                     let transpose = fr[j] + (i as isize - k) as usize;
                     temp = min(temp, transpose);
                 } else if (i as isize - k) == 1 {
@@ -45,6 +129,18 @@ fn damerau_levenshtein_distance_zhao(s1: &[u32], s2: &[u32]) -> usize {
 
             last_i2l1 = r[j];
             r[j] = temp;
+            row_min = min(row_min, temp);
+        }
+
+        // the cell right of the band is read by the next row
+        if j_end < s2.len() {
+            r[j_end + 1] = max_value;
+        }
+
+        // the row minimum never decreases, since a transposition costs at
+        // least as much as deleting the skipped rows
+        if row_min > score_cutoff {
+            return score_cutoff + 1;
         }
 
         last_row_id.insert(s1[i - 1], i as isize);
@@ -53,25 +149,86 @@ fn damerau_levenshtein_distance_zhao(s1: &[u32], s2: &[u32]) -> usize {
     r[s2.len()]
 }
 
+/// Weighted variant of the recurrence of Lowrance and Wagner. Since a
+/// transposition can reach back to any earlier row, the whole matrix is kept.
+fn damerau_levenshtein_weighted(s1: &[u32], s2: &[u32], weights: &Weights) -> usize {
+    let (insert, delete, substitute, transpose) = (weights.0, weights.1, weights.2, weights.3);
+    let (len1, len2) = (s1.len(), s2.len());
+    let unreachable = len1 * delete + len2 * insert + 1;
+
+    // row and column 0 are sentinels, so `d[i + 1][j + 1]` is the distance
+    // between the first i characters of s1 and the first j characters of s2
+    let width = len2 + 2;
+    let mut d = vec![unreachable; (len1 + 2) * width];
+    for i in 0..=len1 {
+        d[(i + 1) * width + 1] = i * delete;
+    }
+    for j in 0..=len2 {
+        d[width + j + 1] = j * insert;
+    }
+
+    let mut last_row_id = HashMap::new();
+    for i in 1..=len1 {
+        let mut last_col_id = 0;
+        for j in 1..=len2 {
+            let k = *last_row_id.get(&s2[j - 1]).unwrap_or(&0);
+            let l = last_col_id;
+            let cost = if s1[i - 1] == s2[j - 1] {
+                last_col_id = j;
+                0
+            } else {
+                substitute
+            };
+
+            let transposition =
+                d[k * width + l] + (i - k - 1) * delete + transpose + (j - l - 1) * insert;
+            d[(i + 1) * width + j + 1] = min(
+                min(d[i * width + j] + cost, d[(i + 1) * width + j] + insert),
+                min(d[i * width + j + 1] + delete, transposition),
+            );
+        }
+        last_row_id.insert(s1[i - 1], i);
+    }
+
+    d[(len1 + 1) * width + len2 + 1]
+}
+
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "distance",
-    signature = (s1, s2, *, processor=None, score_cutoff=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
 )]
 pub fn py_distance(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<usize>,
 ) -> PyResult<usize> {
     let (s1, s2) = prep_inputs(s1, s2, processor)?;
-    let res = distance(&s1, &s2, score_cutoff);
+    let res = distance(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
 
     Ok(res)
 }
 
-fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
-    let dist = damerau_levenshtein_distance_zhao(s1, s2);
+pub fn distance(s1: &[u32], s2: &[u32], weights: Weights, score_cutoff: Option<usize>) -> usize {
+    let cutoff = score_cutoff.unwrap_or(usize::MAX);
+
+    let dist = if weights == Weights::default() {
+        // the length difference is a lower bound for the distance
+        if s1.len().abs_diff(s2.len()) > cutoff {
+            return cutoff + 1;
+        }
+
+        let (prefix_len, suffix_len) = common_affix(s1, s2);
+        let s1 = &s1[prefix_len..s1.len() - suffix_len];
+        let s2 = &s2[prefix_len..s2.len() - suffix_len];
+
+        damerau_levenshtein_distance_zhao(s1, s2, cutoff)
+    } else {
+        damerau_levenshtein_weighted(s1, s2, &weights)
+    };
 
     match score_cutoff {
         Some(cutoff) if dist <= cutoff => dist,
@@ -80,26 +237,28 @@ fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "similarity",
-    signature = (s1, s2, *, processor=None, score_cutoff=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
 )]
 pub fn py_similarity(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<usize>,
 ) -> PyResult<usize> {
     let (s1, s2) = prep_inputs(s1, s2, processor)?;
-    let sim = similarity(&s1, &s2, score_cutoff);
+    let sim = similarity(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
 
     Ok(sim)
 }
 
-pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
-    let maximum = usize::max(s1.len(), s2.len());
-    let dist = distance(s1, s2, score_cutoff);
+pub fn similarity(s1: &[u32], s2: &[u32], weights: Weights, score_cutoff: Option<usize>) -> usize {
+    let maximum = damerau_levenshtein_maximum(s1, s2, &weights);
+    let dist = distance(s1, s2, weights, None);
     let sim = maximum - dist;
 
     match score_cutoff {
@@ -109,14 +268,16 @@ pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_distance",
-    signature = (s1, s2, *, processor=None, score_cutoff=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
 )]
 pub fn py_normalized_distance(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<f64>,
 ) -> PyResult<f64> {
@@ -125,17 +286,22 @@ pub fn py_normalized_distance(
     }
 
     let (s1, s2) = prep_inputs(s1, s2, processor)?;
-    let norm_dist = normalized_distance(&s1, &s2, score_cutoff);
+    let norm_dist = normalized_distance(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
 
     Ok(norm_dist)
 }
 
-pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
-    let maximum = usize::max(s1.len(), s2.len()) as f64;
+pub fn normalized_distance(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let maximum = damerau_levenshtein_maximum(s1, s2, &weights) as f64;
     let norm_dist = if maximum == 0.0 {
         0.0
     } else {
-        let dist = distance(s1, s2, None) as f64;
+        let dist = distance(s1, s2, weights, None) as f64;
         dist / maximum
     };
 
@@ -146,14 +312,16 @@ pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) ->
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_similarity",
-    signature = (s1, s2, *, processor=None, score_cutoff=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
 )]
 pub fn py_normalized_similarity(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<f64>,
 ) -> PyResult<f64> {
@@ -162,13 +330,18 @@ pub fn py_normalized_similarity(
     }
 
     let (s1, s2) = prep_inputs(s1, s2, processor)?;
-    let norm_sim = normalized_similarity(&s1, &s2, score_cutoff);
+    let norm_sim = normalized_similarity(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
 
     Ok(norm_sim)
 }
 
-pub fn normalized_similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
-    let norm_dist = normalized_distance(s1, s2, None);
+pub fn normalized_similarity(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let norm_dist = normalized_distance(s1, s2, weights, None);
     let norm_sim = 1.0 - norm_dist;
 
     match score_cutoff {
@@ -181,12 +354,13 @@ pub fn normalized_similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>)
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::test_utils::pseudo_random_seq_in;
 
     #[test]
     fn test_damerau_levenshtein_distance_zhao_simple() {
         let s1 = "test".chars().map(|c| c as u32).collect::<Vec<_>>();
         let s2 = "text".chars().map(|c| c as u32).collect::<Vec<_>>();
-        let dist = damerau_levenshtein_distance_zhao(&s1, &s2);
+        let dist = damerau_levenshtein_distance_zhao(&s1, &s2, usize::MAX);
         assert_eq!(dist, 1);
     }
 
@@ -194,7 +368,7 @@ mod tests {
     fn test_damerau_levenshtein_distance_zhao() {
         let s1 = "kitten".chars().map(|c| c as u32).collect::<Vec<_>>();
         let s2 = "sitting".chars().map(|c| c as u32).collect::<Vec<_>>();
-        let dist = damerau_levenshtein_distance_zhao(&s1, &s2);
+        let dist = damerau_levenshtein_distance_zhao(&s1, &s2, usize::MAX);
         assert_eq!(dist, 3);
     }
 
@@ -202,7 +376,7 @@ mod tests {
     fn test_damerau_levenshtein_distance_zhao_empty() {
         let s1 = "".chars().map(|c| c as u32).collect::<Vec<_>>();
         let s2 = "".chars().map(|c| c as u32).collect::<Vec<_>>();
-        let dist = damerau_levenshtein_distance_zhao(&s1, &s2);
+        let dist = damerau_levenshtein_distance_zhao(&s1, &s2, usize::MAX);
         assert_eq!(dist, 0);
     }
 
@@ -210,7 +384,7 @@ mod tests {
     fn test_damerau_levenshtein_distance_zhao_empty_1() {
         let s1 = "".chars().map(|c| c as u32).collect::<Vec<_>>();
         let s2 = "sitting".chars().map(|c| c as u32).collect::<Vec<_>>();
-        let dist = damerau_levenshtein_distance_zhao(&s1, &s2);
+        let dist = damerau_levenshtein_distance_zhao(&s1, &s2, usize::MAX);
         assert_eq!(dist, 7);
     }
 
@@ -218,7 +392,106 @@ mod tests {
     fn test_damerau_levenshtein_distance_zhao_empty_2() {
         let s1 = "kitten".chars().map(|c| c as u32).collect::<Vec<_>>();
         let s2 = "".chars().map(|c| c as u32).collect::<Vec<_>>();
-        let dist = damerau_levenshtein_distance_zhao(&s1, &s2);
+        let dist = damerau_levenshtein_distance_zhao(&s1, &s2, usize::MAX);
         assert_eq!(dist, 6);
     }
+
+    fn distance_dp(s1: &[u32], s2: &[u32]) -> usize {
+        let (len1, len2) = (s1.len(), s2.len());
+        let max_dist = len1 + len2;
+        let mut last_row = HashMap::new();
+        let mut d = vec![vec![0; len2 + 2]; len1 + 2];
+        d[0][0] = max_dist;
+        for i in 0..=len1 {
+            d[i + 1][0] = max_dist;
+            d[i + 1][1] = i;
+        }
+        for j in 0..=len2 {
+            d[0][j + 1] = max_dist;
+            d[1][j + 1] = j;
+        }
+        for i in 1..=len1 {
+            let mut last_col = 0;
+            for j in 1..=len2 {
+                let k = *last_row.get(&s2[j - 1]).unwrap_or(&0);
+                let l = last_col;
+                let cost = if s1[i - 1] == s2[j - 1] {
+                    last_col = j;
+                    0
+                } else {
+                    1
+                };
+                d[i + 1][j + 1] = min(
+                    min(d[i][j] + cost, d[i + 1][j] + 1),
+                    min(d[i][j + 1] + 1, d[k][l] + (i - k - 1) + 1 + (j - l - 1)),
+                );
+            }
+            last_row.insert(s1[i - 1], i);
+        }
+        d[len1 + 1][len2 + 1]
+    }
+
+    #[test]
+    fn test_distance_score_cutoff() {
+        let lens = [0, 1, 2, 7, 30, 64, 100];
+        for alphabet in [3, 10] {
+            for (seed, &len1) in lens.iter().enumerate() {
+                for &len2 in &lens {
+                    let mut s1 = pseudo_random_seq_in(seed as u64, len1, alphabet, 250);
+                    let s2 = pseudo_random_seq_in(seed as u64 + 100, len2, alphabet, 250);
+                    if len1 == len2 && seed % 2 == 0 {
+                        // mostly equal sequences with a few transpositions
+                        s1 = s2.clone();
+                        for i in (1..len1).step_by(9) {
+                            s1.swap(i - 1, i);
+                        }
+                    }
+                    let expected = distance_dp(&s1, &s2);
+                    assert_eq!(distance(&s1, &s2, Weights::default(), None), expected);
+                    for cutoff in 0..expected + 2 {
+                        assert_eq!(
+                            distance(&s1, &s2, Weights::default(), Some(cutoff)),
+                            min(expected, cutoff + 1),
+                            "len1={len1} len2={len2} cutoff={cutoff}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_distance() {
+        let seq = |s: &str| s.chars().map(|c| c as u32).collect::<Vec<_>>();
+        let dist = |a: &str, b: &str, w: Weights| distance(&seq(a), &seq(b), w, None);
+        assert_eq!(dist("ab", "ba", Weights(1, 1, 2, 1)), 1);
+        assert_eq!(dist("ab", "ba", Weights(1, 1, 2, 3)), 2);
+        assert_eq!(dist("ca", "abc", Weights(1, 1, 1, 1)), 2);
+        assert_eq!(dist("ca", "abc", Weights(2, 2, 2, 1)), 3);
+        assert_eq!(dist("abc", "", Weights(1, 3, 1, 1)), 9);
+        assert_eq!(dist("", "abc", Weights(1, 3, 1, 1)), 3);
+
+        let s1 = seq("abcdef");
+        let s2 = seq("bacdfe");
+        let w = Weights(2, 2, 3, 1);
+        assert_eq!(distance(&s1, &s2, w, None), 2);
+        assert_eq!(distance(&s1, &s2, w, Some(1)), 2);
+        assert_eq!(damerau_levenshtein_maximum(&s1, &s2, &w), 18);
+        assert_eq!(similarity(&s1, &s2, w, None), 16);
+        assert_eq!(normalized_distance(&s1, &s2, w, None), 2.0 / 18.0);
+    }
+
+    #[test]
+    fn test_weighted_matches_zhao() {
+        for alphabet in [3, 10] {
+            for (seed, len) in [0, 1, 7, 30, 70].into_iter().enumerate() {
+                let s1 = pseudo_random_seq_in(seed as u64, len, alphabet, 250);
+                let s2 = pseudo_random_seq_in(seed as u64 + 100, len + seed, alphabet, 250);
+                assert_eq!(
+                    damerau_levenshtein_weighted(&s1, &s2, &Weights::default()),
+                    distance_dp(&s1, &s2)
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,388 @@
+#[cfg(feature = "python")]
+use crate::distance::prep_inputs;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+/// Letters that sound alike. A letter can be part of several groups.
+const LETTER_GROUPS: [&[u8]; 10] = [
+    b"aeiouy", b"bp", b"ckq", b"dt", b"lr", b"mn", b"gj", b"fpv", b"sxz", b"csz",
+];
+
+/// Cost of two letters without anything in common.
+const MISMATCH_COST: usize = 2;
+
+fn fold_case(c: u32) -> u32 {
+    match char::from_u32(c) {
+        Some(ch) if ch.is_ascii_uppercase() => ch.to_ascii_lowercase() as u32,
+        _ => c,
+    }
+}
+
+/// Bitmask of the letter groups `c` is part of.
+fn letter_groups(c: u32) -> u16 {
+    LETTER_GROUPS
+        .iter()
+        .enumerate()
+        .filter(|(_, group)| c < 128 && group.contains(&(c as u8)))
+        .fold(0, |mask, (i, _)| mask | 1 << i)
+}
+
+fn replace_cost(a: u32, b: u32) -> usize {
+    if a == b {
+        0
+    } else if letter_groups(a) & letter_groups(b) != 0 {
+        1
+    } else {
+        MISMATCH_COST
+    }
+}
+
+/// Costs of deleting every element of `s`. Letters following an `h` or `w`
+/// are cheaper to delete, since those are often silent.
+fn delete_costs(s: &[u32]) -> Vec<usize> {
+    s.iter()
+        .enumerate()
+        .map(|(i, &c)| match i.checked_sub(1).map(|prev| s[prev]) {
+            Some(prev) if prev != c && (prev == 'h' as u32 || prev == 'w' as u32) => 1,
+            Some(prev) => replace_cost(prev, c),
+            None => MISMATCH_COST,
+        })
+        .collect()
+}
+
+fn editex_maximum(s1: &[u32], s2: &[u32]) -> usize {
+    usize::max(s1.len(), s2.len()) * MISMATCH_COST
+}
+
+/// Editex distance with memory linear in the length of s2. Returns
+/// `score_cutoff + 1` once a whole row exceeds the cutoff.
+fn editex_distance(s1: &[u32], s2: &[u32], score_cutoff: usize) -> usize {
+    let s1: Vec<u32> = s1.iter().map(|&c| fold_case(c)).collect();
+    let s2: Vec<u32> = s2.iter().map(|&c| fold_case(c)).collect();
+    let deletes = delete_costs(&s1);
+    let inserts = delete_costs(&s2);
+
+    let mut row = Vec::with_capacity(s2.len() + 1);
+    row.push(0);
+    for (j, cost) in inserts.iter().enumerate() {
+        row.push(row[j] + cost);
+    }
+
+    for (i, &ch1) in s1.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] += deletes[i];
+        let mut row_min = row[0];
+
+        for (j, &ch2) in s2.iter().enumerate() {
+            let cost = [
+                row[j + 1] + deletes[i],
+                row[j] + inserts[j],
+                diagonal + replace_cost(ch1, ch2),
+            ]
+            .into_iter()
+            .min()
+            .unwrap();
+            diagonal = row[j + 1];
+            row[j + 1] = cost;
+            row_min = row_min.min(cost);
+        }
+
+        if row_min > score_cutoff {
+            return score_cutoff + 1;
+        }
+    }
+
+    row[s2.len()]
+}
+
+/**
+Calculates the Editex distance between two strings.
+
+Editex is a Levenshtein distance that takes the sound of letters into
+account. Replacing a letter with one of the same letter group (e.g. "d"
+and "t" or two vowels) costs 1, any other edit costs 2. Deleting a letter
+after an "h" or "w" costs 1 as well, since those are often silent. ASCII
+letters are compared case insensitively.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Maximum distance between s1 and s2, that is
+    considered as a result. If the distance is bigger than score_cutoff,
+    score_cutoff + 1 is returned instead. Default is None, which deactivates
+    this behaviour.
+
+Returns
+-------
+distance : int
+    distance between s1 and s2
+
+Examples
+--------
+\>>> from crustyfuzz.distance import editex
+\>>> editex.distance("Smith", "Smyth")
+1
+\>>> editex.distance("Smith", "Smart")
+5
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "distance",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let dist = distance(&s1, &s2, score_cutoff);
+
+    Ok(dist)
+}
+
+pub fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
+    let maximum = editex_maximum(s1, s2);
+    let cutoff = score_cutoff.unwrap_or(maximum).min(maximum);
+    let dist = editex_distance(s1, s2, cutoff);
+
+    match score_cutoff {
+        Some(cutoff) if dist <= cutoff => dist,
+        Some(cutoff) => cutoff + 1,
+        None => dist,
+    }
+}
+
+/**
+Calculates the Editex similarity in the range [max, 0].
+
+This is calculated as ``max - distance``, where max is twice the length
+of the longer string.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Minimum similarity between s1 and s2, that is considered as a result.
+    If the similarity is smaller than score_cutoff, 0 is returned instead.
+    Default is None, which deactivates this behaviour.
+
+Returns
+-------
+similarity : int
+    similarity between s1 and s2
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "similarity",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let sim = similarity(&s1, &s2, score_cutoff);
+
+    Ok(sim)
+}
+
+pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
+    let maximum = editex_maximum(s1, s2);
+    let dist_cutoff = score_cutoff.map(|cutoff| maximum.saturating_sub(cutoff));
+    let dist = distance(s1, s2, dist_cutoff);
+    let sim = maximum.saturating_sub(dist);
+
+    match score_cutoff {
+        Some(cutoff) if sim >= cutoff => sim,
+        Some(_) => 0,
+        None => sim,
+    }
+}
+
+/**
+Calculates a normalized Editex distance in the range [1, 0].
+
+This is calculated as ``distance / max``, where max is twice the length
+of the longer string.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_dist > score_cutoff 1.0 is returned instead. Default is 1.0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_dist : float
+    normalized distance between s1 and s2 as a float between 0 and 1.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_distance",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(1.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let norm_dist = normalized_distance(&s1, &s2, score_cutoff);
+
+    Ok(norm_dist)
+}
+
+pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
+    let maximum = editex_maximum(s1, s2);
+    let norm_dist = if maximum == 0 {
+        0.0
+    } else {
+        distance(s1, s2, None) as f64 / maximum as f64
+    };
+
+    match score_cutoff {
+        Some(cutoff) if norm_dist <= cutoff => norm_dist,
+        Some(_) => 1.0,
+        None => norm_dist,
+    }
+}
+
+/**
+Calculates a normalized Editex similarity in the range [0, 1].
+
+This is calculated as ``1 - normalized_distance``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_sim < score_cutoff 0 is returned instead. Default is 0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_sim : float
+    normalized similarity between s1 and s2 as a float between 0 and 1.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_similarity",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(0.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let norm_sim = normalized_similarity(&s1, &s2, score_cutoff);
+
+    Ok(norm_sim)
+}
+
+pub fn normalized_similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
+    let norm_sim = 1.0 - normalized_distance(s1, s2, None);
+
+    match score_cutoff {
+        Some(cutoff) if norm_sim >= cutoff => norm_sim,
+        Some(_) => 0.0,
+        None => norm_sim,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::to_seq;
+
+    #[test]
+    fn test_distance() {
+        let cases = [
+            ("", "", 0),
+            ("cat", "hat", 2),
+            ("Niall", "Neil", 2),
+            ("aluminum", "Catalan", 12),
+            ("ATCG", "TAGC", 6),
+            ("Smith", "Smyth", 1),
+            ("", "abc", 6),
+        ];
+        for (s1, s2, expected) in cases {
+            assert_eq!(
+                distance(&to_seq(s1), &to_seq(s2), None),
+                expected,
+                "{s1} {s2}"
+            );
+            assert_eq!(
+                distance(&to_seq(s2), &to_seq(s1), None),
+                expected,
+                "{s2} {s1}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_silent_letters() {
+        // deleting a letter after an "h" or "w" is cheaper
+        assert_eq!(delete_costs(&to_seq("bahr")), vec![2, 2, 2, 1]);
+        assert_eq!(distance(&to_seq("bahr"), &to_seq("bah"), None), 1);
+        assert_eq!(distance(&to_seq("bart"), &to_seq("bar"), None), 2);
+    }
+
+    #[test]
+    fn test_score_cutoff() {
+        let s1 = to_seq("aluminum");
+        let s2 = to_seq("Catalan");
+        assert_eq!(distance(&s1, &s2, Some(12)), 12);
+        assert_eq!(distance(&s1, &s2, Some(5)), 6);
+        assert_eq!(similarity(&s1, &s2, Some(4)), 4);
+        assert_eq!(similarity(&s1, &s2, Some(5)), 0);
+        assert_eq!(normalized_distance(&s1, &s2, None), 0.75);
+        assert_eq!(normalized_similarity(&s1, &s2, Some(0.3)), 0.0);
+    }
+}
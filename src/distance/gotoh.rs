@@ -0,0 +1,686 @@
+#[cfg(feature = "python")]
+use crate::distance::models::Opcodes;
+use crate::distance::models::{Editop, Editops};
+#[cfg(feature = "python")]
+use crate::distance::prep_inputs;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+/// Costs in the form `(gap_open, gap_extend, replace)`. A gap of length `k`
+/// costs `gap_open + (k - 1) * gap_extend`, so `(1, 1, 1)` is the uniform
+/// Levenshtein distance. Since a gap can always be split into several gaps,
+/// `gap_extend` is limited to `gap_open`.
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weights(pub usize, pub usize, pub usize);
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights(1, 1, 1)
+    }
+}
+
+const UNREACHABLE: usize = usize::MAX / 4;
+
+// sources of a cell in the traceback matrix
+const FROM_DIAGONAL: u8 = 0;
+const FROM_INSERT: u8 = 1;
+const FROM_DELETE: u8 = 2;
+const SOURCE_MASK: u8 = 3;
+const INSERT_EXTENDED: u8 = 1 << 2;
+const DELETE_EXTENDED: u8 = 1 << 3;
+
+fn gap_extend(weights: &Weights) -> usize {
+    weights.1.min(weights.0)
+}
+
+fn gap_cost(len: usize, weights: &Weights) -> usize {
+    match len {
+        0 => 0,
+        _ => weights.0 + (len - 1) * gap_extend(weights),
+    }
+}
+
+fn gotoh_maximum(s1: &[u32], s2: &[u32], weights: &Weights) -> usize {
+    let len1 = s1.len();
+    let len2 = s2.len();
+
+    let max_dist = gap_cost(len1, weights) + gap_cost(len2, weights);
+    max_dist.min(len1.min(len2) * weights.2 + gap_cost(len1.abs_diff(len2), weights))
+}
+
+/// Gotoh's recurrences for a single cell. Returns the cheapest cost of the
+/// cell and its source.
+#[inline]
+fn best_cell(diagonal: usize, insert: usize, delete: usize) -> (usize, u8) {
+    let mut best = (diagonal, FROM_DIAGONAL);
+    if delete < best.0 {
+        best = (delete, FROM_DELETE);
+    }
+    if insert < best.0 {
+        best = (insert, FROM_INSERT);
+    }
+    best
+}
+
+/// Global alignment cost with memory linear in the length of s2. Returns
+/// `score_cutoff + 1` once a whole row exceeds the cutoff.
+fn gotoh_distance(s1: &[u32], s2: &[u32], weights: &Weights, score_cutoff: usize) -> usize {
+    let (gap_open, gap_extend, replace) = (weights.0, gap_extend(weights), weights.2);
+    let mut costs: Vec<usize> = (0..=s2.len()).map(|j| gap_cost(j, weights)).collect();
+    let mut deletes = vec![UNREACHABLE; s2.len() + 1];
+
+    for (i, &ch1) in s1.iter().enumerate() {
+        let mut diagonal = costs[0];
+        costs[0] = gap_cost(i + 1, weights);
+        let mut insert = UNREACHABLE;
+        let mut row_min = costs[0];
+
+        for (j, &ch2) in s2.iter().enumerate() {
+            insert = usize::min(costs[j] + gap_open, insert + gap_extend);
+            deletes[j + 1] = usize::min(costs[j + 1] + gap_open, deletes[j + 1] + gap_extend);
+
+            let substitution = if ch1 == ch2 { 0 } else { replace };
+            let (cost, _) = best_cell(diagonal + substitution, insert, deletes[j + 1]);
+            diagonal = costs[j + 1];
+            costs[j + 1] = cost;
+            row_min = row_min.min(cost);
+        }
+
+        if row_min > score_cutoff {
+            return score_cutoff + 1;
+        }
+    }
+
+    costs[s2.len()]
+}
+
+/// Sources of every cell of the cost matrix, which are used to backtrack the
+/// edit operations.
+fn traceback_matrix(s1: &[u32], s2: &[u32], weights: &Weights) -> Vec<u8> {
+    let (gap_open, gap_extend, replace) = (weights.0, gap_extend(weights), weights.2);
+    let cols = s2.len() + 1;
+    let mut sources = vec![FROM_DIAGONAL; (s1.len() + 1) * cols];
+    let mut costs: Vec<usize> = (0..=s2.len()).map(|j| gap_cost(j, weights)).collect();
+    let mut deletes = vec![UNREACHABLE; cols];
+
+    for (j, source) in sources[..cols].iter_mut().enumerate().skip(1) {
+        *source = FROM_INSERT | if j > 1 { INSERT_EXTENDED } else { 0 };
+    }
+
+    for (i, &ch1) in s1.iter().enumerate() {
+        let row = (i + 1) * cols;
+        sources[row] = FROM_DELETE | if i > 0 { DELETE_EXTENDED } else { 0 };
+
+        let mut diagonal = costs[0];
+        costs[0] = gap_cost(i + 1, weights);
+        let mut insert = UNREACHABLE;
+
+        for (j, &ch2) in s2.iter().enumerate() {
+            let cell = row + j + 1;
+
+            let insert_open = costs[j] + gap_open;
+            if insert + gap_extend < insert_open {
+                sources[cell] |= INSERT_EXTENDED;
+                insert += gap_extend;
+            } else {
+                insert = insert_open;
+            }
+
+            let delete_open = costs[j + 1] + gap_open;
+            if deletes[j + 1] + gap_extend < delete_open {
+                sources[cell] |= DELETE_EXTENDED;
+                deletes[j + 1] += gap_extend;
+            } else {
+                deletes[j + 1] = delete_open;
+            }
+
+            let substitution = if ch1 == ch2 { 0 } else { replace };
+            let (cost, source) = best_cell(diagonal + substitution, insert, deletes[j + 1]);
+            sources[cell] |= source;
+            diagonal = costs[j + 1];
+            costs[j + 1] = cost;
+        }
+    }
+
+    sources
+}
+
+/**
+Calculates the global alignment distance of s1 and s2 with affine gap
+costs, which is computed with the algorithm of Gotoh. Opening a gap can
+cost more than extending it, so a run of missing characters is cheaper
+than the same number of scattered deletions.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int] or None, optional
+    The costs in the form (gap_open, gap_extend, replace). A gap of length
+    k costs gap_open + (k - 1) * gap_extend, where gap_extend is at most
+    gap_open. Default is (1, 1, 1), which is the same as the uniform
+    Levenshtein distance.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Maximum distance between s1 and s2, that is
+    considered as a result. If the distance is bigger than score_cutoff,
+    score_cutoff + 1 is returned instead. Default is None, which deactivates
+    this behaviour.
+
+Returns
+-------
+distance : int
+    distance between s1 and s2
+
+Examples
+--------
+\>>> from crustyfuzz.distance import gotoh
+\>>> gotoh.distance("total amount due", "total due")
+7
+
+A dropped word is cheaper than scattered deletions with a low gap_extend:
+
+\>>> gotoh.distance("total amount due", "total due", weights=(3, 1, 2))
+9
+\>>> gotoh.distance("total amount due", "ttl amnt due", weights=(3, 1, 2))
+10
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "distance",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), processor=None, score_cutoff=None)
+)]
+pub fn py_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let dist = distance(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
+
+    Ok(dist)
+}
+
+pub fn distance(s1: &[u32], s2: &[u32], weights: Weights, score_cutoff: Option<usize>) -> usize {
+    let maximum = gotoh_maximum(s1, s2, &weights);
+    let cutoff = score_cutoff.unwrap_or(maximum).min(maximum);
+    let dist = gotoh_distance(s1, s2, &weights, cutoff);
+
+    match score_cutoff {
+        Some(cutoff) if dist <= cutoff => dist,
+        Some(cutoff) => cutoff + 1,
+        None => dist,
+    }
+}
+
+/**
+Calculates the global alignment similarity with affine gap costs in the
+range [max, 0].
+
+This is calculated as ``max - distance``, where max is the distance of
+two sequences of the same lengths without any common element.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int] or None, optional
+    The costs in the form (gap_open, gap_extend, replace).
+    Default is (1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Minimum similarity between s1 and s2, that is considered as a result.
+    If the similarity is smaller than score_cutoff, 0 is returned instead.
+    Default is None, which deactivates this behaviour.
+
+Returns
+-------
+similarity : int
+    similarity between s1 and s2
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "similarity",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), processor=None, score_cutoff=None)
+)]
+pub fn py_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let sim = similarity(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
+
+    Ok(sim)
+}
+
+pub fn similarity(s1: &[u32], s2: &[u32], weights: Weights, score_cutoff: Option<usize>) -> usize {
+    let maximum = gotoh_maximum(s1, s2, &weights);
+    let dist_cutoff = score_cutoff.map(|cutoff| maximum.saturating_sub(cutoff));
+    let dist = distance(s1, s2, weights, dist_cutoff);
+    let sim = maximum.saturating_sub(dist);
+
+    match score_cutoff {
+        Some(cutoff) if sim >= cutoff => sim,
+        Some(_) => 0,
+        None => sim,
+    }
+}
+
+/**
+Calculates a normalized global alignment distance with affine gap costs
+in the range [1, 0].
+
+This is calculated as ``distance / max``, where max is the distance of
+two sequences of the same lengths without any common element.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int] or None, optional
+    The costs in the form (gap_open, gap_extend, replace).
+    Default is (1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_dist > score_cutoff 1.0 is returned instead. Default is 1.0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_dist : float
+    normalized distance between s1 and s2 as a float between 0 and 1.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_distance",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(1.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let norm_dist = normalized_distance(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
+
+    Ok(norm_dist)
+}
+
+pub fn normalized_distance(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let maximum = gotoh_maximum(s1, s2, &weights);
+    let norm_dist = if maximum == 0 {
+        0.0
+    } else {
+        distance(s1, s2, weights, None) as f64 / maximum as f64
+    };
+
+    match score_cutoff {
+        Some(cutoff) if norm_dist <= cutoff => norm_dist,
+        Some(_) => 1.0,
+        None => norm_dist,
+    }
+}
+
+/**
+Calculates a normalized global alignment similarity with affine gap costs
+in the range [0, 1].
+
+This is calculated as ``1 - normalized_distance``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int] or None, optional
+    The costs in the form (gap_open, gap_extend, replace).
+    Default is (1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_sim < score_cutoff 0 is returned instead. Default is 0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_sim : float
+    normalized similarity between s1 and s2 as a float between 0 and 1.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_similarity",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(0.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let norm_sim = normalized_similarity(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
+
+    Ok(norm_sim)
+}
+
+pub fn normalized_similarity(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let norm_sim = 1.0 - normalized_distance(s1, s2, weights, None);
+
+    match score_cutoff {
+        Some(cutoff) if norm_sim >= cutoff => norm_sim,
+        Some(_) => 0.0,
+        None => norm_sim,
+    }
+}
+
+/**
+Return Editops describing how to turn s1 into s2 with the cheapest global
+alignment under affine gap costs.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int] or None, optional
+    The costs in the form (gap_open, gap_extend, replace).
+    Default is (1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+
+Returns
+-------
+editops : Editops
+    edit operations required to turn s1 into s2
+
+Examples
+--------
+\>>> from crustyfuzz.distance import gotoh
+\>>> for tag, src_pos, dest_pos in gotoh.editops("ab xy cd", "ab cd", weights=(3, 1, 3)):
+...    print(("%7s s1[%d] s2[%d]" % (tag, src_pos, dest_pos)))
+ delete s1[2] s2[2]
+ delete s1[3] s2[2]
+ delete s1[4] s2[2]
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "editops",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), processor=None)
+)]
+pub fn py_editops(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Editops> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+
+    Ok(editops(&s1, &s2, weights.unwrap_or_default()))
+}
+
+pub fn editops(s1: &[u32], s2: &[u32], weights: Weights) -> Editops {
+    let sources = traceback_matrix(s1, s2, &weights);
+    let cols = s2.len() + 1;
+
+    let mut editop_vec = Vec::new();
+    let (mut i, mut j) = (s1.len(), s2.len());
+    let mut state = FROM_DIAGONAL;
+    while i != 0 || j != 0 {
+        let source = sources[i * cols + j];
+        match state {
+            FROM_INSERT => {
+                j -= 1;
+                editop_vec.push(Editop {
+                    tag: "insert".to_string(),
+                    src_pos: i,
+                    dest_pos: j,
+                });
+                if source & INSERT_EXTENDED == 0 {
+                    state = FROM_DIAGONAL;
+                }
+            }
+            FROM_DELETE => {
+                i -= 1;
+                editop_vec.push(Editop {
+                    tag: "delete".to_string(),
+                    src_pos: i,
+                    dest_pos: j,
+                });
+                if source & DELETE_EXTENDED == 0 {
+                    state = FROM_DIAGONAL;
+                }
+            }
+            _ => match source & SOURCE_MASK {
+                FROM_DIAGONAL => {
+                    i -= 1;
+                    j -= 1;
+                    if s1[i] != s2[j] {
+                        editop_vec.push(Editop {
+                            tag: "replace".to_string(),
+                            src_pos: i,
+                            dest_pos: j,
+                        });
+                    }
+                }
+                gap => state = gap,
+            },
+        }
+    }
+
+    editop_vec.reverse();
+    Editops::new(s1.len(), s2.len(), editop_vec)
+}
+
+/**
+Return Opcodes describing how to turn s1 into s2 with the cheapest global
+alignment under affine gap costs.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int] or None, optional
+    The costs in the form (gap_open, gap_extend, replace).
+    Default is (1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+
+Returns
+-------
+opcodes : Opcodes
+    edit operations required to turn s1 into s2
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "opcodes",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), processor=None)
+)]
+pub fn py_opcodes(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Opcodes> {
+    let editops = py_editops(s1, s2, weights, processor)?;
+    Ok(editops.as_opcodes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::{pseudo_random_seq, to_seq};
+
+    /// Gotoh's recurrences with full matrices.
+    fn distance_naive(s1: &[u32], s2: &[u32], weights: &Weights) -> usize {
+        let (rows, cols) = (s1.len() + 1, s2.len() + 1);
+        let mut d = vec![vec![UNREACHABLE; cols]; rows];
+        let mut e = vec![vec![UNREACHABLE; cols]; rows];
+        let mut f = vec![vec![UNREACHABLE; cols]; rows];
+        d[0][0] = 0;
+        for j in 1..cols {
+            e[0][j] = gap_cost(j, weights);
+            d[0][j] = e[0][j];
+        }
+        for i in 1..rows {
+            f[i][0] = gap_cost(i, weights);
+            d[i][0] = f[i][0];
+            for j in 1..cols {
+                e[i][j] = usize::min(d[i][j - 1] + weights.0, e[i][j - 1] + gap_extend(weights));
+                f[i][j] = usize::min(d[i - 1][j] + weights.0, f[i - 1][j] + gap_extend(weights));
+                let substitution = if s1[i - 1] == s2[j - 1] { 0 } else { weights.2 };
+                d[i][j] = [d[i - 1][j - 1] + substitution, e[i][j], f[i][j]]
+                    .into_iter()
+                    .min()
+                    .unwrap();
+            }
+        }
+        d[rows - 1][cols - 1]
+    }
+
+    /// Cost of the alignment described by the editops.
+    fn rescore(editops: &Editops, weights: &Weights) -> usize {
+        let ops = editops.as_list();
+        let mut cost = 0;
+        for (k, op) in ops.iter().enumerate() {
+            cost += match op.tag.as_str() {
+                "replace" => weights.2,
+                tag => {
+                    let extends = k > 0 && {
+                        let prev = &ops[k - 1];
+                        prev.tag == tag
+                            && match tag {
+                                "insert" => {
+                                    prev.src_pos == op.src_pos && prev.dest_pos + 1 == op.dest_pos
+                                }
+                                _ => prev.src_pos + 1 == op.src_pos && prev.dest_pos == op.dest_pos,
+                            }
+                    };
+                    if extends {
+                        gap_extend(weights)
+                    } else {
+                        weights.0
+                    }
+                }
+            };
+        }
+        cost
+    }
+
+    #[test]
+    fn test_distance() {
+        let s1 = to_seq("total amount due");
+        let s2 = to_seq("total due");
+        assert_eq!(distance(&s1, &s2, Weights::default(), None), 7);
+        assert_eq!(distance(&s1, &s2, Weights(3, 1, 2), None), 9);
+        assert_eq!(distance(&s1, &s2, Weights(3, 1, 2), Some(8)), 9);
+        // two gaps in "total" and one in "amount"
+        let s2 = to_seq("ttl amnt due");
+        assert_eq!(distance(&s1, &s2, Weights(3, 1, 2), None), 10);
+    }
+
+    #[test]
+    fn test_editops() {
+        let s1 = to_seq("ab xy cd");
+        let s2 = to_seq("ab cd");
+        let ops = editops(&s1, &s2, Weights(3, 1, 3)).as_list();
+        let ops: Vec<_> = ops
+            .iter()
+            .map(|op| (op.tag.as_str(), op.src_pos, op.dest_pos))
+            .collect();
+        assert_eq!(ops, [("delete", 2, 2), ("delete", 3, 2), ("delete", 4, 2)]);
+    }
+
+    #[test]
+    fn test_random() {
+        let lens = [0, 1, 2, 5, 17, 40];
+        let all_weights = [
+            Weights(1, 1, 1),
+            Weights(3, 1, 2),
+            Weights(4, 1, 1),
+            Weights(2, 2, 5),
+            Weights(1, 3, 2),
+        ];
+        for (seed, &len1) in lens.iter().enumerate() {
+            for &len2 in &lens {
+                for weights in all_weights {
+                    let s1 = pseudo_random_seq(seed as u64, len1);
+                    let s2 = pseudo_random_seq(seed as u64 + 100, len2);
+                    let expected = distance_naive(&s1, &s2, &weights);
+                    assert_eq!(distance(&s1, &s2, weights, None), expected);
+                    assert!(expected <= gotoh_maximum(&s1, &s2, &weights));
+
+                    let ops = editops(&s1, &s2, weights);
+                    assert_eq!(rescore(&ops, &weights), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_score_cutoff() {
+        let weights = Weights(3, 1, 2);
+        for seed in 0..20 {
+            let s1 = pseudo_random_seq(seed, 30);
+            let s2 = pseudo_random_seq(seed + 100, 25);
+            let dist = distance(&s1, &s2, weights, None);
+            for cutoff in 0..dist + 3 {
+                let expected = if dist <= cutoff { dist } else { cutoff + 1 };
+                assert_eq!(distance(&s1, &s2, weights, Some(cutoff)), expected);
+            }
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use crate::common::error::CrustyError;
 use crate::distance::models::{Editop, Editops, Opcodes};
+#[cfg(feature = "python")]
 use crate::distance::prep_inputs;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "distance",
@@ -24,10 +27,11 @@ pub fn py_distance(
         Err(CrustyError::LengthMismatch) => Err(pyo3::exceptions::PyValueError::new_err(
             "Sequences are not the same length.",
         )),
+        Err(e) => Err(pyo3::exceptions::PyValueError::new_err(e.to_string())),
     }
 }
 
-fn distance(
+pub fn distance(
     s1: &[u32],
     s2: &[u32],
     pad: bool,
@@ -53,6 +57,7 @@ fn distance(
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "similarity",
@@ -74,7 +79,7 @@ pub fn py_similarity(
     Ok(sim)
 }
 
-fn similarity(
+pub fn similarity(
     s1: &[u32],
     s2: &[u32],
     pad: bool,
@@ -91,6 +96,7 @@ fn similarity(
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_distance",
@@ -112,7 +118,7 @@ pub fn py_normalized_distance(
     Ok(norm_dist)
 }
 
-fn normalized_distance(
+pub fn normalized_distance(
     s1: &[u32],
     s2: &[u32],
     pad: bool,
@@ -133,6 +139,7 @@ fn normalized_distance(
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_similarity",
@@ -154,7 +161,7 @@ pub fn py_normalized_similarity(
     Ok(norm_sim)
 }
 
-fn normalized_similarity(
+pub fn normalized_similarity(
     s1: &[u32],
     s2: &[u32],
     pad: bool,
@@ -170,6 +177,7 @@ fn normalized_similarity(
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "editops",
@@ -190,7 +198,7 @@ pub fn py_editops(
     Ok(editops)
 }
 
-fn editops(s1: &[u32], s2: &[u32], pad: bool) -> Result<Editops, CrustyError> {
+pub fn editops(s1: &[u32], s2: &[u32], pad: bool) -> Result<Editops, CrustyError> {
     if !pad && s1.len() != s2.len() {
         return Err(CrustyError::LengthMismatch);
     }
@@ -227,6 +235,7 @@ fn editops(s1: &[u32], s2: &[u32], pad: bool) -> Result<Editops, CrustyError> {
     Ok(Editops::new(s1.len(), s2.len(), ops_vec))
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "opcodes",
@@ -247,6 +256,6 @@ pub fn py_opcodes(
     Ok(opcodes)
 }
 
-fn opcodes(s1: &[u32], s2: &[u32], pad: bool) -> Result<Opcodes, CrustyError> {
+pub fn opcodes(s1: &[u32], s2: &[u32], pad: bool) -> Result<Opcodes, CrustyError> {
     Ok(editops(s1, s2, pad)?.as_opcodes())
 }
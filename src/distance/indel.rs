@@ -1,11 +1,20 @@
-use crate::common::conv_sequences;
+#[cfg(feature = "python")]
+use crate::common::with_views;
+use crate::common::SeqElem;
+#[cfg(feature = "python")]
+use crate::distance::conv_views;
 use crate::distance::lcs_seq::{
-    block_similarity as lcs_seq_block_similarity, py_editops as lcs_seq_py_editops,
-    py_opcodes as lcs_seq_py_opcodes, similarity as lcs_seq_similarity,
+    block_similarity as lcs_seq_block_similarity, similarity as lcs_seq_similarity,
 };
+#[cfg(feature = "python")]
+use crate::distance::lcs_seq::{
+    py_editops as lcs_seq_py_editops, py_opcodes as lcs_seq_py_opcodes,
+};
+#[cfg(feature = "python")]
 use crate::distance::models::{Editops, Opcodes};
+use crate::distance::{distance_cutoff, PatternMatchVector};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
-use std::collections::HashMap;
 
 /**
 Calculates the minimum number of insertions and deletions
@@ -46,6 +55,7 @@ a more efficient implementation:
 \>>> Indel.distance("lewenstein", "levenshtein", score_cutoff=1)
 2
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "distance",
@@ -62,32 +72,25 @@ pub fn py_distance(
         None => (s1.to_owned(), s2.to_owned()),
     };
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    let dist = distance(&s1, &s2, score_cutoff);
+    let dist = with_views!(&s1, &s2, |s1, s2| distance(s1, s2, score_cutoff));
 
     Ok(dist)
 }
 
-pub fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
+pub fn distance<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: Option<usize>,
+) -> usize {
     let maximum = s1.len() + s2.len();
+    // dist = maximum - 2 * lcs, so this is the smallest lcs within the cutoff
+    let lcs_cutoff = score_cutoff.map(|cutoff| maximum.saturating_sub(cutoff).div_ceil(2));
     let lcs_sim = if s1.is_empty() {
         0
     } else {
-        lcs_seq_similarity(s1, s2, None)
+        lcs_seq_similarity(s1, s2, lcs_cutoff)
     };
     let dist = maximum - 2 * lcs_sim;
 
@@ -98,10 +101,10 @@ pub fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
     }
 }
 
-pub fn block_distance(
-    block: &HashMap<u32, u128>,
-    s1: &[u32],
-    s2: &[u32],
+pub fn block_distance<T1: SeqElem, T2: SeqElem>(
+    block: &PatternMatchVector,
+    s1: &[T1],
+    s2: &[T2],
     score_cutoff: Option<f64>,
 ) -> u32 {
     let maximum = (s1.len() + s2.len()) as u32;
@@ -140,6 +143,7 @@ Returns
 similarity : int
     similarity between s1 and s2
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "similarity",
@@ -160,27 +164,18 @@ pub fn py_similarity(
         return Ok(0);
     }
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    let sim = similarity(&s1, &s2, score_cutoff);
+    let sim = with_views!(&s1, &s2, |s1, s2| similarity(s1, s2, score_cutoff));
 
     Ok(sim)
 }
 
-pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
+pub fn similarity<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: Option<usize>,
+) -> usize {
     let maximum = s1.len() + s2.len();
     let dist = distance(s1, s2, None);
     let sim = maximum - dist;
@@ -212,6 +207,7 @@ Returns
 norm_dist : float
     normalized distance between s1 and s2 as a float between 0 and 1.0
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_distance",
@@ -232,34 +228,41 @@ pub fn py_normalized_distance(
         None => (s1.to_owned(), s2.to_owned()),
     };
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    let norm_dist = normalized_distance(&s1, &s2, score_cutoff);
+    let norm_dist = with_views!(&s1, &s2, |s1, s2| normalized_distance(s1, s2, score_cutoff));
 
     Ok(norm_dist)
 }
 
-pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
+/// Normalized distance, where distances above `dist_cutoff` are only known to
+/// be larger than it.
+fn normalized_distance_with_cutoff<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    dist_cutoff: Option<usize>,
+) -> f64 {
     let maximum = (s1.len() + s2.len()) as f64;
-    let norm_dist = if maximum == 0.0 {
+    if maximum == 0.0 {
         0.0
     } else {
-        let dist = distance(s1, s2, None) as f64;
+        let dist = distance(s1, s2, dist_cutoff) as f64;
         dist / maximum
-    };
+    }
+}
+
+pub fn normalized_distance<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let maximum = s1.len() + s2.len();
+    let dist_cutoff = score_cutoff.map(|cutoff| {
+        distance_cutoff(maximum, maximum as f64 * cutoff, |dist| {
+            dist as f64 / maximum as f64 <= cutoff
+        })
+    });
+    let norm_dist = normalized_distance_with_cutoff(s1, s2, dist_cutoff);
 
     match score_cutoff {
         Some(cutoff) if norm_dist <= cutoff => norm_dist,
@@ -268,10 +271,10 @@ pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) ->
     }
 }
 
-pub fn block_normalized_distance(
-    block: &HashMap<u32, u128>,
-    s1: &[u32],
-    s2: &[u32],
+pub fn block_normalized_distance<T1: SeqElem, T2: SeqElem>(
+    block: &PatternMatchVector,
+    s1: &[T1],
+    s2: &[T2],
     score_cutoff: Option<f64>,
 ) -> f64 {
     let maximum = (s1.len() + s2.len()) as f64;
@@ -332,6 +335,7 @@ When a different processor is used s1 and s2 do not have to be strings
 \>>> Indel.normalized_similarity(["lewenstein"], ["levenshtein"], processor=lambda s: s[0])
 0.8571428571428572
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_similarity",
@@ -352,26 +356,29 @@ pub fn py_normalized_similarity(
         None => (s1.to_owned(), s2.to_owned()),
     };
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    Ok(normalized_similarity(&s1, &s2, score_cutoff))
+    let norm_sim = with_views!(&s1, &s2, |s1, s2| normalized_similarity(
+        s1,
+        s2,
+        score_cutoff
+    ));
+
+    Ok(norm_sim)
 }
 
-pub fn normalized_similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
-    let norm_dist = normalized_distance(s1, s2, None);
+pub fn normalized_similarity<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let maximum = s1.len() + s2.len();
+    let dist_cutoff = score_cutoff.map(|cutoff| {
+        distance_cutoff(maximum, maximum as f64 * (1.0 - cutoff), |dist| {
+            1.0 - dist as f64 / maximum as f64 >= cutoff
+        })
+    });
+    let norm_dist = normalized_distance_with_cutoff(s1, s2, dist_cutoff);
     let norm_sim = 1.0 - norm_dist;
 
     match score_cutoff {
@@ -381,10 +388,10 @@ pub fn normalized_similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>)
     }
 }
 
-pub fn block_normalized_similarity(
-    block: &HashMap<u32, u128>,
-    s1: &[u32],
-    s2: &[u32],
+pub fn block_normalized_similarity<T1: SeqElem, T2: SeqElem>(
+    block: &PatternMatchVector,
+    s1: &[T1],
+    s2: &[T2],
     score_cutoff: Option<f64>,
 ) -> f64 {
     let norm_dist = block_normalized_distance(block, s1, s2, None);
@@ -397,6 +404,7 @@ pub fn block_normalized_similarity(
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "editops",
@@ -410,6 +418,7 @@ pub fn py_editops(
     lcs_seq_py_editops(s1, s2, processor)
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "opcodes",
@@ -426,6 +435,7 @@ pub fn py_opcodes(
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "python")]
     use pyo3::types::PyString;
 
     #[test]
@@ -480,6 +490,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_normalized_score_cutoff() {
+        let to_vec = |s: &str| s.chars().map(|c| c as u32).collect::<Vec<_>>();
+        let pairs = [
+            ("lewenstein", "levenshtein"),
+            ("new york mets", "new york meats"),
+            ("new york mets", "new yrok mets"),
+            ("abcdefghij", "abcdefghij"),
+            ("a", "b"),
+            ("", "abc"),
+        ];
+        for (s1, s2) in pairs {
+            let (s1, s2) = (to_vec(s1), to_vec(s2));
+            let norm_sim = normalized_similarity(&s1, &s2, None);
+            let norm_dist = normalized_distance(&s1, &s2, None);
+            for cutoff in [0.0, 0.5, 0.8, 0.9, 0.95, 1.0, norm_sim, norm_dist] {
+                let expected = if norm_sim >= cutoff { norm_sim } else { 0.0 };
+                assert_eq!(normalized_similarity(&s1, &s2, Some(cutoff)), expected);
+                let expected = if norm_dist <= cutoff { norm_dist } else { 1.0 };
+                assert_eq!(normalized_distance(&s1, &s2, Some(cutoff)), expected);
+            }
+        }
+    }
+
+    #[cfg(feature = "python")]
     #[test]
     fn test_empty_input() {
         pyo3::prepare_freethreaded_python();
@@ -1,4 +1,10 @@
-use crate::distance::{conv_sequences, prep_inputs};
+#[cfg(feature = "python")]
+use crate::common::with_views;
+use crate::common::SeqElem;
+#[cfg(feature = "python")]
+use crate::distance::conv_views;
+use crate::distance::PatternMatchVector;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 use std::cmp::min;
 
@@ -37,7 +43,7 @@ fn jaro_common_char_filter(
     sim >= score_cutoff
 }
 
-fn jaro_bounds<'a>(s1: &'a [u32], s2: &'a [u32]) -> (&'a [u32], &'a [u32], usize) {
+fn jaro_bounds<'a, T1, T2>(s1: &'a [T1], s2: &'a [T2]) -> (&'a [T1], &'a [T2], usize) {
     let pattern_len = s1.len();
     let text_len = s2.len();
 
@@ -62,6 +68,84 @@ fn jaro_bounds<'a>(s1: &'a [u32], s2: &'a [u32]) -> (&'a [u32], &'a [u32], usize
     (s1, s2, bound)
 }
 
+/// Flag the common characters of `s1` and `s2`.
+///
+/// Every character of `s1` is matched to the first unflagged occurrence in
+/// `s2` that lies within `bound` of its own position. `block` is the pattern
+/// match vector of `s2`, so the search window is a mask over its words.
+fn jaro_flag_common<T: SeqElem>(
+    block: &PatternMatchVector,
+    s1: &[T],
+    s2_len: usize,
+    bound: usize,
+) -> (Vec<u64>, Vec<u64>, usize) {
+    let mut s1_flags = vec![0u64; s1.len().div_ceil(64)];
+    let mut s2_flags = vec![0u64; block.words()];
+    let mut common_chars = 0;
+
+    for (i, &ch) in s1.iter().enumerate() {
+        let start = i.saturating_sub(bound);
+        let end = min(i + bound, s2_len - 1);
+        if start > end {
+            continue;
+        }
+
+        let pm = block.get(ch.value());
+        for word in start / 64..=end / 64 {
+            let mut candidates = pm[word] & !s2_flags[word];
+            if word == start / 64 {
+                candidates &= u64::MAX << (start % 64);
+            }
+            if word == end / 64 {
+                candidates &= u64::MAX >> (63 - end % 64);
+            }
+            if candidates != 0 {
+                s2_flags[word] |= candidates & candidates.wrapping_neg();
+                s1_flags[i / 64] |= 1 << (i % 64);
+                common_chars += 1;
+                break;
+            }
+        }
+    }
+
+    (s1_flags, s2_flags, common_chars)
+}
+
+/// Count the flagged characters that do not line up in order. Both flag
+/// vectors hold the same number of set bits.
+fn jaro_transpositions<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    s1_flags: &[u64],
+    s2_flags: &[u64],
+) -> usize {
+    let mut trans_count = 0;
+    let mut s2_word = 0;
+    let mut s2_bits = s2_flags.first().copied().unwrap_or(0);
+
+    for (s1_word, &bits) in s1_flags.iter().enumerate() {
+        let mut s1_bits = bits;
+        while s1_bits != 0 {
+            let i = s1_word * 64 + s1_bits.trailing_zeros() as usize;
+            s1_bits &= s1_bits - 1;
+
+            while s2_bits == 0 {
+                s2_word += 1;
+                s2_bits = s2_flags[s2_word];
+            }
+            let j = s2_word * 64 + s2_bits.trailing_zeros() as usize;
+            s2_bits &= s2_bits - 1;
+
+            if s1[i].value() != s2[j].value() {
+                trans_count += 1;
+            }
+        }
+    }
+
+    trans_count
+}
+
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "similarity",
@@ -88,27 +172,14 @@ pub fn py_similarity(
 
     let score_cutoff = score_cutoff.unwrap_or(0.0);
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    let sim = similarity(&s1, &s2, score_cutoff);
+    let sim = with_views!(&s1, &s2, |s1, s2| similarity(s1, s2, score_cutoff));
 
     Ok(sim)
 }
 
-pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: f64) -> f64 {
+pub fn similarity<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2], score_cutoff: f64) -> f64 {
     let pattern_len = s1.len();
     let text_len = s2.len();
 
@@ -118,62 +189,29 @@ pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: f64) -> f64 {
     }
 
     if pattern_len == 1 && text_len == 1 {
-        return if s1[0] == s2[0] { 1.0 } else { 0.0 };
+        return if s1[0].value() == s2[0].value() {
+            1.0
+        } else {
+            0.0
+        };
     }
 
     let (s1, s2, bound) = jaro_bounds(s1, s2);
 
-    let mut s1_flags = vec![false; s1.len()];
-    let mut s2_flags = vec![false; s2.len()];
-
-    // TODO: use bitparallel implementation
-    // looking only within search range, count & flag matched pairs
-    let mut common_chars = 0;
-    for (i, c1) in s1.iter().enumerate() {
-        let start = if i > bound { i - bound } else { 0 };
-        let end = if i + bound < text_len {
-            i + bound
-        } else {
-            text_len - 1
-        };
-        for j in start..=end {
-            if c1 == &s2[j] && !s2_flags[j] {
-                s1_flags[i] = true;
-                s2_flags[j] = true;
-                common_chars += 1;
-                break;
-            }
-        }
-    }
+    let block = PatternMatchVector::new(s2);
+    let (s1_flags, s2_flags, common_chars) = jaro_flag_common(&block, s1, s2.len(), bound);
 
     // short circuit if score_cutoff can not be reached
     if !jaro_common_char_filter(pattern_len, text_len, common_chars, score_cutoff) {
         return 0.0;
     }
 
-    // TODO: use bitparallel implementation
-    // count transpositions
-    let mut k = 0;
-    let mut trans_count = 0;
-    for (i, s1_f) in s1_flags.iter().enumerate() {
-        if *s1_f {
-            let mut j = k;
-            while j < text_len {
-                if s2_flags[j] {
-                    k = j + 1;
-                    if s1[i] != s2[j] {
-                        trans_count += 1;
-                    }
-                    break;
-                }
-                j += 1;
-            }
-        }
-    }
+    let trans_count = jaro_transpositions(s1, s2, &s1_flags, &s2_flags);
 
     jaro_calculate_similarity(pattern_len, text_len, common_chars, trans_count)
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_similarity",
@@ -188,6 +226,7 @@ pub fn py_normalized_similarity(
     py_similarity(s1, s2, processor, score_cutoff)
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "distance",
@@ -203,18 +242,22 @@ pub fn py_distance(
         return Ok(1.0);
     }
 
-    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let (s1, s2) = match processor {
+        Some(proc) => (proc.call1((s1,))?, proc.call1((s2,))?),
+        None => (s1.to_owned(), s2.to_owned()),
+    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    if s1.is_empty() && s2.is_empty() {
+    if with_views!(&s1, &s2, |s1, s2| s1.is_empty() && s2.is_empty()) {
         return Ok(0.0);
     }
 
-    let res = distance(&s1, &s2, score_cutoff);
+    let res = with_views!(&s1, &s2, |s1, s2| distance(s1, s2, score_cutoff));
 
     Ok(res)
 }
 
-fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
+pub fn distance<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2], score_cutoff: Option<f64>) -> f64 {
     let cutoff_distance = match score_cutoff {
         Some(cutoff) if cutoff > 1.0 => 0.0,
         Some(cutoff) => 1.0 - cutoff,
@@ -231,6 +274,7 @@ fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_distance",
@@ -248,6 +292,7 @@ pub fn py_normalized_distance(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::test_utils::pseudo_random_seq;
 
     #[test]
     fn test_similarity() {
@@ -257,4 +302,56 @@ mod tests {
         let sim = similarity(&s1, &s2, 0.0);
         assert_eq!(sim, 1.0);
     }
+
+    fn similarity_naive(s1: &[u32], s2: &[u32]) -> f64 {
+        let (pattern_len, text_len) = (s1.len(), s2.len());
+        if pattern_len == 1 && text_len == 1 {
+            return if s1[0] == s2[0] { 1.0 } else { 0.0 };
+        }
+        let (s1, s2, bound) = jaro_bounds(s1, s2);
+
+        let mut s1_flags = vec![false; s1.len()];
+        let mut s2_flags = vec![false; s2.len()];
+        let mut common_chars = 0;
+        for (i, c1) in s1.iter().enumerate() {
+            let start = i.saturating_sub(bound);
+            let end = min(i + bound, s2.len() - 1);
+            for j in start..=end {
+                if c1 == &s2[j] && !s2_flags[j] {
+                    s1_flags[i] = true;
+                    s2_flags[j] = true;
+                    common_chars += 1;
+                    break;
+                }
+            }
+        }
+        if common_chars == 0 {
+            return 0.0;
+        }
+
+        let s1_common = s1.iter().zip(&s1_flags).filter(|(_, &f)| f);
+        let s2_common = s2.iter().zip(&s2_flags).filter(|(_, &f)| f);
+        let trans_count = s1_common
+            .zip(s2_common)
+            .filter(|((c1, _), (c2, _))| c1 != c2)
+            .count();
+
+        jaro_calculate_similarity(pattern_len, text_len, common_chars, trans_count)
+    }
+
+    #[test]
+    fn test_similarity_blocks() {
+        let lens = [1, 2, 5, 63, 64, 65, 127, 128, 129, 200, 300];
+        for (seed, &len1) in lens.iter().enumerate() {
+            for &len2 in &lens {
+                let s1 = pseudo_random_seq(seed as u64, len1);
+                let s2 = pseudo_random_seq(seed as u64 + 100, len2);
+                assert_eq!(
+                    similarity(&s1, &s2, 0.0),
+                    similarity_naive(&s1, &s2),
+                    "len1={len1} len2={len2}"
+                );
+            }
+        }
+    }
 }
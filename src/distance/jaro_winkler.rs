@@ -1,8 +1,14 @@
+#[cfg(feature = "python")]
+use crate::common::with_views;
+use crate::common::SeqElem;
+#[cfg(feature = "python")]
+use crate::distance::conv_views;
 use crate::distance::jaro::similarity as jaro_similarity;
-use crate::distance::{conv_sequences, prep_inputs};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 use std::cmp::min;
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "similarity",
@@ -30,27 +36,24 @@ pub fn py_similarity(
 
     let score_cutoff = score_cutoff.unwrap_or(0.0);
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    let sim = similarity(&s1, &s2, prefix_weight, score_cutoff);
+    let sim = with_views!(&s1, &s2, |s1, s2| similarity(
+        s1,
+        s2,
+        prefix_weight,
+        score_cutoff
+    ));
 
     Ok(sim)
 }
 
-pub fn similarity(s1: &[u32], s2: &[u32], prefix_weight: f64, score_cutoff: f64) -> f64 {
+pub fn similarity<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    prefix_weight: f64,
+    score_cutoff: f64,
+) -> f64 {
     let p_len = s1.len();
     let t_len = s2.len();
     let min_len = min(p_len, t_len);
@@ -58,7 +61,7 @@ pub fn similarity(s1: &[u32], s2: &[u32], prefix_weight: f64, score_cutoff: f64)
     let max_prefix = min(min_len, 4);
 
     for _ in 0..max_prefix {
-        if s1[prefix] != s2[prefix] {
+        if s1[prefix].value() != s2[prefix].value() {
             break;
         }
         prefix += 1;
@@ -86,6 +89,7 @@ pub fn similarity(s1: &[u32], s2: &[u32], prefix_weight: f64, score_cutoff: f64)
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_similarity",
@@ -101,6 +105,7 @@ pub fn py_normalized_similarity(
     py_similarity(s1, s2, prefix_weight, processor, score_cutoff)
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "distance",
@@ -117,9 +122,13 @@ pub fn py_distance(
         return Ok(0.0);
     }
 
-    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let (s1, s2) = match processor {
+        Some(proc) => (proc.call1((s1,))?, proc.call1((s2,))?),
+        None => (s1.to_owned(), s2.to_owned()),
+    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    if s1.is_empty() && s2.is_empty() {
+    if with_views!(&s1, &s2, |s1, s2| s1.is_empty() && s2.is_empty()) {
         return Ok(0.0);
     }
 
@@ -129,7 +138,12 @@ pub fn py_distance(
         None => 0.0,
     };
 
-    let sim = similarity(&s1, &s2, prefix_weight, cutoff_distance);
+    let sim = with_views!(&s1, &s2, |s1, s2| similarity(
+        s1,
+        s2,
+        prefix_weight,
+        cutoff_distance
+    ));
     let dist = 1.0 - sim;
 
     match score_cutoff {
@@ -139,6 +153,7 @@ pub fn py_distance(
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_distance",
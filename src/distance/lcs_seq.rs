@@ -1,19 +1,132 @@
-use crate::common::{common_affix, conv_sequences};
-use crate::distance::models::{Editop, Editops, Opcodes};
-use num_bigint::BigUint;
+use crate::common::pattern_match::{CheckpointedBitMatrix, PatternMatchVector};
+use crate::common::{common_affix, seq_eq, SeqElem};
+#[cfg(feature = "python")]
+use crate::common::{conv_sequences, with_views};
+#[cfg(feature = "python")]
+use crate::distance::conv_views;
+use crate::distance::distance_cutoff;
+#[cfg(feature = "python")]
+use crate::distance::models::Opcodes;
+use crate::distance::models::{Editop, Editops};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
-use std::collections::HashMap;
 
-trait CountZeros {
-    fn count_zeros(&self) -> u64;
+/// Edit patterns of the mbleven algorithm, indexed by the maximum number of
+/// insertions and deletions and the length difference of the sequences. Every
+/// step of a pattern takes two bits, where 1 skips a character of the longer
+/// sequence and 2 a character of the shorter one.
+const MBLEVEN_MATRIX: [&[u8]; 14] = [
+    // max misses 1
+    &[0x00], // len_diff 0 (does not occur)
+    &[0x01], // len_diff 1
+    // max misses 2
+    &[0x09, 0x06], // len_diff 0
+    &[0x01],       // len_diff 1
+    &[0x05],       // len_diff 2
+    // max misses 3
+    &[0x09, 0x06],       // len_diff 0
+    &[0x25, 0x19, 0x16], // len_diff 1
+    &[0x05],             // len_diff 2
+    &[0x15],             // len_diff 3
+    // max misses 4
+    &[0x96, 0x66, 0x5A, 0x99, 0x69, 0xA5], // len_diff 0
+    &[0x25, 0x19, 0x16],                   // len_diff 1
+    &[0x65, 0x56, 0x95, 0x59],             // len_diff 2
+    &[0x15],                               // len_diff 3
+    &[0x55],                               // len_diff 4
+];
+
+/// LCS for at most 4 insertions and deletions, which walks both sequences for
+/// every possible edit pattern instead of running the bit-parallel scan.
+/// Both sequences have to be non-empty. Returns 0 when the similarity is
+/// below `score_cutoff`.
+fn mbleven<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2], score_cutoff: usize) -> usize {
+    if s1.len() < s2.len() {
+        return mbleven(s2, s1, score_cutoff);
+    }
+
+    let len_diff = s1.len() - s2.len();
+    let max_misses = s1.len() + s2.len() - 2 * score_cutoff;
+    let ops_index = (max_misses + max_misses * max_misses) / 2 + len_diff - 1;
+
+    let mut max_len = 0;
+    for &possible_ops in MBLEVEN_MATRIX[ops_index] {
+        let mut ops = possible_ops;
+        let (mut pos1, mut pos2) = (0, 0);
+        let mut cur_len = 0;
+
+        while pos1 < s1.len() && pos2 < s2.len() {
+            if s1[pos1].value() != s2[pos2].value() {
+                if ops == 0 {
+                    break;
+                }
+                if ops & 1 != 0 {
+                    pos1 += 1;
+                } else if ops & 2 != 0 {
+                    pos2 += 1;
+                }
+                ops >>= 2;
+            } else {
+                cur_len += 1;
+                pos1 += 1;
+                pos2 += 1;
+            }
+        }
+
+        max_len = max_len.max(cur_len);
+    }
+
+    if max_len >= score_cutoff {
+        max_len
+    } else {
+        0
+    }
 }
 
-impl CountZeros for BigUint {
-    fn count_zeros(&self) -> u64 {
-        self.bits() - self.count_ones()
+/// Bit-parallel LCS of Hyyrö for s1 of up to 64 characters. Returns 0 as
+/// soon as `score_cutoff` can no longer be reached.
+fn lcs_single_word<T: SeqElem>(block: &PatternMatchVector, s2: &[T], score_cutoff: usize) -> usize {
+    let mut state = !0_u64;
+    for (j, &ch2) in s2.iter().enumerate() {
+        let matches = block.get(ch2.value())[0];
+        let update = state & matches;
+        state = state.wrapping_add(update) | (state - update);
+        if (state.count_zeros() as usize) + (s2.len() - j - 1) < score_cutoff {
+            return 0;
+        }
+    }
+    state.count_zeros() as usize
+}
+
+/// Advances the blocked bit-parallel LCS state by one character of s2,
+/// propagating the carry of the addition through the words.
+#[inline]
+fn lcs_step(pattern: &[u64], state: &mut [u64]) {
+    let mut carry = false;
+    for (word, &matches) in state.iter_mut().zip(pattern) {
+        let update = *word & matches;
+        let (sum, carry1) = word.overflowing_add(update);
+        let (sum, carry2) = sum.overflowing_add(carry as u64);
+        carry = carry1 || carry2;
+        *word = sum | (*word - update);
     }
 }
 
+/// Blocked variant of the bit-parallel LCS for s1 of any length.
+fn lcs_blocks<T: SeqElem>(block: &PatternMatchVector, s2: &[T], score_cutoff: usize) -> usize {
+    let mut state = vec![!0_u64; block.words()];
+    for (j, &ch2) in s2.iter().enumerate() {
+        lcs_step(block.get(ch2.value()), &mut state);
+        if score_cutoff > 0 {
+            let sim: usize = state.iter().map(|word| word.count_zeros() as usize).sum();
+            if sim + (s2.len() - j - 1) < score_cutoff {
+                return 0;
+            }
+        }
+    }
+    state.iter().map(|word| word.count_zeros() as usize).sum()
+}
+
 /**
 Calculates the length of the longest common subsequence
 
@@ -34,6 +147,7 @@ Returns
 similarity : f64
     similarity between s1 and s2
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "similarity",
@@ -54,53 +168,42 @@ pub fn py_similarity(
         return Ok(0);
     }
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    let sim = similarity(&s1, &s2, score_cutoff);
+    let sim = with_views!(&s1, &s2, |s1, s2| similarity(s1, s2, score_cutoff));
 
     Ok(sim)
 }
 
-pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
-    let len1 = s1.len();
-
-    let b_one = BigUint::from(1u32);
-
-    let mut state = (&b_one << len1) - &b_one;
-    let mut block = HashMap::with_capacity(len1);
-
-    let mut position = b_one.clone();
-    for &ch1 in s1 {
-        block
-            .entry(ch1)
-            .and_modify(|e| *e |= &position)
-            .or_insert_with(|| position.clone());
-        position <<= 1u32;
+pub fn similarity<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: Option<usize>,
+) -> usize {
+    let cutoff = score_cutoff.unwrap_or(0);
+    if s1.len().min(s2.len()) < cutoff {
+        return 0;
     }
 
-    for &ch2 in s2 {
-        let matches = block
-            .get(&ch2)
-            .cloned()
-            .unwrap_or_else(|| BigUint::from(0u32));
-        let update = &state & &matches;
-        state = (&state + &update) | (&state - &update);
+    // insertions and deletions that are allowed by the cutoff
+    let max_misses = s1.len() + s2.len() - 2 * cutoff;
+    if max_misses == 0 || (max_misses == 1 && s1.len() == s2.len()) {
+        return if seq_eq(s1, s2) { s1.len() } else { 0 };
     }
 
-    let result = state.count_zeros() as usize;
+    let (prefix_len, suffix_len) = common_affix(s1, s2);
+    let s1 = &s1[prefix_len..s1.len() - suffix_len];
+    let s2 = &s2[prefix_len..s2.len() - suffix_len];
+    let affix_len = prefix_len + suffix_len;
+
+    let result = if s1.is_empty() || s2.is_empty() {
+        affix_len
+    } else if max_misses <= 4 {
+        affix_len + mbleven(s1, s2, cutoff.saturating_sub(affix_len))
+    } else {
+        let block = PatternMatchVector::new(s1);
+        affix_len + lcs_kernel(&block, s2, cutoff.saturating_sub(affix_len))
+    };
 
     match score_cutoff {
         Some(cutoff) if result >= cutoff => result,
@@ -109,27 +212,20 @@ pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize
     }
 }
 
-pub fn block_similarity(
-    block: &HashMap<u32, u128>,
-    s1: &[u32],
-    s2: &[u32],
+/// Same as `similarity`, but reuses the `PatternMatchVector` of s1 when
+/// comparing s1 against many sequences.
+pub fn block_similarity<T1: SeqElem, T2: SeqElem>(
+    block: &PatternMatchVector,
+    s1: &[T1],
+    s2: &[T2],
     score_cutoff: Option<f64>,
 ) -> u32 {
-    let len1 = s1.len();
-    if len1 == 0 {
-        return 0;
-    }
-
-    let shift = 128 - len1;
-    let mut state = ((1u128 << len1) - 1) << shift;
-
-    for &ch2 in s2 {
-        let matches = block.get(&ch2).copied().unwrap_or(0);
-        let update = state & matches;
-        state = state.wrapping_add(update) | state.wrapping_sub(update);
-    }
-
-    let res = (state.count_zeros() as usize - shift) as u32;
+    let res = if s1.is_empty() {
+        0
+    } else {
+        let cutoff = score_cutoff.map_or(0, |cutoff| cutoff.max(0.0).ceil() as usize);
+        lcs_kernel(block, s2, cutoff) as u32
+    };
 
     match score_cutoff {
         Some(cutoff) if (res as f64) >= cutoff => res,
@@ -138,6 +234,15 @@ pub fn block_similarity(
     }
 }
 
+fn lcs_kernel<T: SeqElem>(block: &PatternMatchVector, s2: &[T], score_cutoff: usize) -> usize {
+    if block.words() == 1 {
+        lcs_single_word(block, s2, score_cutoff)
+    } else {
+        lcs_blocks(block, s2, score_cutoff)
+    }
+}
+
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "distance",
@@ -158,29 +263,21 @@ pub fn py_distance(
         return Ok(0);
     }
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    let dist = distance(&s1, &s2, score_cutoff);
+    let dist = with_views!(&s1, &s2, |s1, s2| distance(s1, s2, score_cutoff));
 
     Ok(dist)
 }
 
-pub fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
+pub fn distance<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: Option<usize>,
+) -> usize {
     let maximum = s1.len().max(s2.len());
-    let sim = similarity(s1, s2, None);
+    let sim_cutoff = score_cutoff.map(|cutoff| maximum.saturating_sub(cutoff));
+    let sim = similarity(s1, s2, sim_cutoff);
     let dist = maximum - sim;
     match score_cutoff {
         Some(cutoff) if dist <= cutoff => dist,
@@ -189,6 +286,7 @@ pub fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_distance",
@@ -213,34 +311,42 @@ pub fn py_normalized_distance(
         return Ok(0.0);
     }
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    let norm_dist = normalized_distance(&s1, &s2, score_cutoff);
+    let norm_dist = with_views!(&s1, &s2, |s1, s2| normalized_distance(s1, s2, score_cutoff));
 
     Ok(norm_dist)
 }
 
-pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
+/// Normalized distance, where distances above `dist_cutoff` are only known to
+/// be larger than it.
+fn normalized_distance_with_cutoff<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    dist_cutoff: Option<usize>,
+) -> f64 {
     let maximum = s1.len().max(s2.len()) as f64;
-    let norm_dist = if maximum == 0.0 {
+    if maximum == 0.0 {
         0.0
     } else {
-        let dist = distance(s1, s2, None) as f64;
+        let dist = distance(s1, s2, dist_cutoff) as f64;
         dist / maximum
-    };
+    }
+}
+
+pub fn normalized_distance<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let maximum = s1.len().max(s2.len());
+    let dist_cutoff = score_cutoff.map(|cutoff| {
+        distance_cutoff(maximum, maximum as f64 * cutoff, |dist| {
+            dist as f64 / maximum as f64 <= cutoff
+        })
+    });
+    let norm_dist = normalized_distance_with_cutoff(s1, s2, dist_cutoff);
+
     match score_cutoff {
         Some(cutoff) if norm_dist <= cutoff => norm_dist,
         Some(_) => 1.0,
@@ -248,6 +354,7 @@ pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) ->
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_similarity",
@@ -276,40 +383,18 @@ pub fn py_normalized_similarity(
     }
 }
 
-fn matrix(s1: &[u32], s2: &[u32]) -> (usize, Vec<BigUint>) {
-    let mut matrix = Vec::new();
-    if s1.is_empty() {
-        return (0, matrix);
-    }
-
-    let len1 = s1.len();
-    let b_one = BigUint::from(1u32);
-    let mut state = (&b_one << len1) - &b_one;
-    let mut block = HashMap::with_capacity(len1);
-
-    let mut position = b_one;
-    for &ch1 in s1 {
-        block
-            .entry(ch1)
-            .and_modify(|e: &mut BigUint| *e |= &position)
-            .or_insert(position.clone());
-        position <<= 1;
-    }
-
-    for &ch2 in s2 {
-        let matches = block
-            .get(&ch2)
-            .cloned()
-            .unwrap_or_else(|| BigUint::from(0u32));
-        let update = &state & &matches;
-        state = (&state + &update) | (&state - &update);
-        matrix.push(state.clone());
-    }
-
-    let sim = (state >> len1).count_zeros() as usize;
-    (sim, matrix)
+fn matrix<'a>(
+    s1: &[u32],
+    s2: &'a [u32],
+) -> CheckpointedBitMatrix<impl FnMut(usize, &mut [u64]) + 'a> {
+    let block = PatternMatchVector::new(s1);
+    let initial = vec![!0_u64; block.words()];
+    CheckpointedBitMatrix::new(s2.len(), initial, move |row, state| {
+        lcs_step(block.get(s2[row]), state)
+    })
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "editops",
@@ -347,7 +432,7 @@ pub fn editops(s1: &[u32], s2: &[u32]) -> Editops {
     let (prefix_len, suffix_len) = common_affix(s1, s2);
     let s1 = &s1[prefix_len..s1.len() - suffix_len];
     let s2 = &s2[prefix_len..s2.len() - suffix_len];
-    let (sim, matrix) = matrix(s1, s2);
+    let mut matrix = matrix(s1, s2);
 
     // take the length after adjusting
     let len1 = s1.len();
@@ -355,19 +440,13 @@ pub fn editops(s1: &[u32], s2: &[u32]) -> Editops {
     let src_len = len1 + prefix_len + suffix_len;
     let dest_len = len2 + prefix_len + suffix_len;
 
-    let dist = len1 + len2 - 2 * sim;
-    if dist == 0 {
-        return Editops::new(src_len, dest_len, Vec::new());
-    }
-
-    let mut editop_vec = Vec::with_capacity(dist);
+    let mut editop_vec = Vec::new();
     let mut col = len1;
     let mut row = len2;
 
     while col != 0 && row != 0 {
-        let mask = BigUint::from(1_u32) << (col - 1);
         // deletion
-        if (&matrix[row - 1] & &mask) != BigUint::from(0_u32) {
+        if matrix.test_bit(row - 1, col - 1) {
             col -= 1;
             editop_vec.push(Editop {
                 tag: "delete".to_string(),
@@ -378,7 +457,7 @@ pub fn editops(s1: &[u32], s2: &[u32]) -> Editops {
             row -= 1;
 
             // insertion
-            if row != 0 && (&matrix[row - 1] & &mask) == BigUint::from(0_u32) {
+            if row != 0 && !matrix.test_bit(row - 1, col - 1) {
                 editop_vec.push(Editop {
                     tag: "insert".to_string(),
                     src_pos: col + prefix_len,
@@ -412,6 +491,7 @@ pub fn editops(s1: &[u32], s2: &[u32]) -> Editops {
     Editops::new(src_len, dest_len, editop_vec)
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "opcodes",
@@ -428,6 +508,7 @@ pub fn py_opcodes(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::test_utils::pseudo_random_seq;
 
     #[test]
     fn test_similarity() {
@@ -467,14 +548,7 @@ mod tests {
             &s1.chars().map(|c| c as u32).collect::<Vec<_>>(),
             &s2.chars().map(|c| c as u32).collect::<Vec<_>>(),
         );
-        let mut block = HashMap::new();
-        let shift = 128 - seq1.len();
-        let mut x = 1u128 << shift;
-
-        for &ch in seq1 {
-            block.entry(ch).and_modify(|e| *e |= &x).or_insert(x);
-            x <<= 1;
-        }
+        let block = PatternMatchVector::new(seq1);
         let result = block_similarity(&block, seq1, seq2, None);
 
         assert_eq!(
@@ -484,6 +558,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_block_similarity_long_needle() {
+        let s1 = "a long product description ".repeat(6);
+        let s2 = format!("{}!", s1.trim_end());
+        let (seq1, seq2) = (
+            &s1.chars().map(|c| c as u32).collect::<Vec<_>>(),
+            &s2.chars().map(|c| c as u32).collect::<Vec<_>>(),
+        );
+        let block = PatternMatchVector::new(seq1);
+        let result = block_similarity(&block, seq1, seq2, None);
+
+        assert_eq!(result as usize, seq1.len() - 1);
+    }
+
     #[test]
     fn test_editops() {
         let s1 = "00";
@@ -556,7 +644,7 @@ mod tests {
             &s2.chars().map(|c| c as u32).collect::<Vec<_>>(),
         );
         let result = editops(seq1, seq2);
-        assert!(result.len() != 0);
+        assert!(!result.is_empty());
     }
 
     #[test]
@@ -571,4 +659,135 @@ mod tests {
         let result2 = similarity(seq2, seq1, None);
         assert_eq!(result1, result2);
     }
+
+    fn similarity_dp(s1: &[u32], s2: &[u32]) -> usize {
+        let mut cache = vec![0; s1.len() + 1];
+        for &ch2 in s2 {
+            let mut diag = 0;
+            for (i, &ch1) in s1.iter().enumerate() {
+                let up = cache[i + 1];
+                cache[i + 1] = if ch1 == ch2 {
+                    diag + 1
+                } else {
+                    up.max(cache[i])
+                };
+                diag = up;
+            }
+        }
+        cache[s1.len()]
+    }
+
+    #[test]
+    fn test_similarity_blocks() {
+        for (i, (len1, len2)) in [(1, 70), (63, 64), (64, 64), (65, 3), (130, 200), (200, 129)]
+            .into_iter()
+            .enumerate()
+        {
+            let s1 = pseudo_random_seq(i as u64, len1);
+            let s2 = pseudo_random_seq(i as u64 + 100, len2);
+            assert_eq!(
+                similarity(&s1, &s2, None),
+                similarity_dp(&s1, &s2),
+                "len1={} len2={}",
+                len1,
+                len2
+            );
+            let ops = editops(&s1, &s2);
+            assert_eq!(ops.len(), len1 + len2 - 2 * similarity_dp(&s1, &s2));
+        }
+    }
+
+    #[test]
+    fn test_score_cutoff() {
+        for (i, (len1, len2)) in [(0, 5), (1, 70), (30, 40), (64, 64), (130, 200), (200, 129)]
+            .into_iter()
+            .enumerate()
+        {
+            let s1 = pseudo_random_seq(i as u64, len1);
+            let mut s2 = pseudo_random_seq(i as u64 + 100, len2);
+            // share a prefix and a suffix to exercise the affix stripping
+            s2.splice(0..0, s1.iter().take(5).copied());
+            s2.extend(s1.iter().rev().take(3));
+            let sim = similarity_dp(&s1, &s2);
+            let dist = len1.max(s2.len()) - sim;
+            for cutoff in [0, 1, sim.saturating_sub(1), sim, sim + 1, sim + 10] {
+                let expected_sim = if sim >= cutoff { sim } else { 0 };
+                assert_eq!(similarity(&s1, &s2, Some(cutoff)), expected_sim);
+                assert_eq!(similarity(&s2, &s1, Some(cutoff)), expected_sim);
+            }
+            for cutoff in [0, 1, dist.saturating_sub(1), dist, dist + 1] {
+                assert_eq!(distance(&s1, &s2, Some(cutoff)), dist.min(cutoff + 1));
+                let indel_dist = len1 + s2.len() - 2 * sim;
+                assert_eq!(
+                    crate::distance::indel::distance(&s1, &s2, Some(cutoff)),
+                    indel_dist.min(cutoff + 1)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalized_distance_score_cutoff() {
+        for (i, (len1, len2)) in [(0, 5), (10, 12), (70, 64), (130, 200)]
+            .into_iter()
+            .enumerate()
+        {
+            let s1 = pseudo_random_seq(i as u64, len1);
+            let s2 = pseudo_random_seq(i as u64 + 100, len2);
+            let norm_dist = normalized_distance(&s1, &s2, None);
+            for cutoff in [0.0, 0.1, 0.3, norm_dist - 1e-9, norm_dist, 0.7, 1.0] {
+                let expected = if norm_dist <= cutoff { norm_dist } else { 1.0 };
+                assert_eq!(normalized_distance(&s1, &s2, Some(cutoff)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_similarity_mbleven() {
+        for i in 0..300 {
+            let s1 = pseudo_random_seq(i, 1 + i as usize % 40);
+            // apply up to 4 insertions or deletions
+            let mut s2 = s1.clone();
+            let edits = pseudo_random_seq(i + 1000, 8);
+            for pair in edits.chunks(2).take(i as usize % 5) {
+                let pos = (pair[1] as usize * 7 + i as usize) % (s2.len() + 1);
+                if pair[0] < 2 || pos == s2.len() {
+                    s2.insert(pos, pair[1] + 4);
+                } else {
+                    s2.remove(pos);
+                }
+            }
+
+            let sim = similarity_dp(&s1, &s2);
+            let max_sim = s1.len().min(s2.len());
+            for cutoff in sim.saturating_sub(2)..=max_sim {
+                let expected = if sim >= cutoff { sim } else { 0 };
+                assert_eq!(similarity(&s1, &s2, Some(cutoff)), expected);
+                assert_eq!(similarity(&s2, &s1, Some(cutoff)), expected);
+                let max_misses = s1.len() + s2.len() - 2 * cutoff;
+                if (1..=4).contains(&max_misses) && !s2.is_empty() {
+                    assert_eq!(mbleven(&s1, &s2, cutoff), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_editops_checkpointed() {
+        // large enough for the matrix to be split into several blocks
+        let s1 = pseudo_random_seq(7, 9000);
+        let s2 = pseudo_random_seq(8, 8000);
+        let ops = editops(&s1, &s2);
+        assert_eq!(
+            ops.len(),
+            s1.len() + s2.len() - 2 * similarity(&s1, &s2, None)
+        );
+
+        let to_str = |s: &[u32]| {
+            s.iter()
+                .map(|&c| char::from(b'a' + c as u8))
+                .collect::<String>()
+        };
+        assert_eq!(ops.apply(&to_str(&s1), &to_str(&s2)), to_str(&s2));
+    }
 }
@@ -0,0 +1,484 @@
+use crate::distance::models::ScoreAlignment;
+#[cfg(feature = "python")]
+use crate::distance::prep_inputs;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+const NO_LINK: usize = usize::MAX;
+
+struct State {
+    /// length of the longest substring ending in this state
+    len: usize,
+    /// state of the longest suffix that ends at other positions as well
+    link: usize,
+    /// end of the first occurrence of the substrings of this state
+    first_end: usize,
+    next: HashMap<u32, usize>,
+}
+
+/// Suffix automaton of a sequence, which accepts exactly its substrings.
+struct SuffixAutomaton {
+    states: Vec<State>,
+}
+
+impl SuffixAutomaton {
+    fn new(s: &[u32]) -> Self {
+        let mut states = Vec::with_capacity(2 * s.len() + 1);
+        states.push(State {
+            len: 0,
+            link: NO_LINK,
+            first_end: 0,
+            next: HashMap::new(),
+        });
+        let mut last = 0;
+
+        for (i, &ch) in s.iter().enumerate() {
+            let cur = states.len();
+            states.push(State {
+                len: states[last].len + 1,
+                link: 0,
+                first_end: i + 1,
+                next: HashMap::new(),
+            });
+
+            let mut p = last;
+            while p != NO_LINK && !states[p].next.contains_key(&ch) {
+                states[p].next.insert(ch, cur);
+                p = states[p].link;
+            }
+
+            if p != NO_LINK {
+                let q = states[p].next[&ch];
+                if states[p].len + 1 == states[q].len {
+                    states[cur].link = q;
+                } else {
+                    let clone = states.len();
+                    states.push(State {
+                        len: states[p].len + 1,
+                        link: states[q].link,
+                        first_end: states[q].first_end,
+                        next: states[q].next.clone(),
+                    });
+                    while p != NO_LINK && states[p].next.get(&ch) == Some(&q) {
+                        states[p].next.insert(ch, clone);
+                        p = states[p].link;
+                    }
+                    states[q].link = clone;
+                    states[cur].link = clone;
+                }
+            }
+
+            last = cur;
+        }
+
+        SuffixAutomaton { states }
+    }
+
+    /// Finds the longest substring of `s` the automaton accepts. Returns its
+    /// length and where it ends in the sequence of the automaton and in `s`.
+    /// Ties are broken by the earliest end in `s` and then in the sequence of
+    /// the automaton.
+    fn longest_match(&self, s: &[u32], max_len: usize) -> (usize, usize, usize) {
+        let mut best = (0, 0, 0);
+        let (mut state, mut len) = (0, 0);
+
+        for (j, ch) in s.iter().enumerate() {
+            while state != 0 && !self.states[state].next.contains_key(ch) {
+                state = self.states[state].link;
+                len = self.states[state].len;
+            }
+            if let Some(&next) = self.states[state].next.get(ch) {
+                state = next;
+                len += 1;
+            }
+
+            if len > best.0 {
+                best = (len, self.states[state].first_end, j + 1);
+                if len == max_len {
+                    break;
+                }
+            }
+        }
+
+        best
+    }
+}
+
+/// Length of the longest common substring and its position in both
+/// sequences. `score_cutoff` is the minimum length that is of interest.
+fn lcs_str_alignment(s1: &[u32], s2: &[u32], score_cutoff: usize) -> Option<ScoreAlignment> {
+    let max_len = usize::min(s1.len(), s2.len());
+    if max_len < score_cutoff {
+        return None;
+    }
+
+    let (len, src_end, dest_end) = if max_len == 0 {
+        (0, 0, 0)
+    } else {
+        SuffixAutomaton::new(s1).longest_match(s2, max_len)
+    };
+
+    if len < score_cutoff {
+        return None;
+    }
+
+    Some(ScoreAlignment {
+        score: len as f64,
+        src_start: src_end - len,
+        src_end,
+        dest_start: dest_end - len,
+        dest_end,
+    })
+}
+
+/**
+Calculates the length of the longest common substring of s1 and s2. In
+contrast to the longest common subsequence, the elements of a substring
+have to be contiguous in both sequences.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Minimum similarity between s1 and s2, that is considered as a result.
+    If the similarity is smaller than score_cutoff, 0 is returned instead.
+    Default is None, which deactivates this behaviour.
+
+Returns
+-------
+similarity : int
+    similarity between s1 and s2
+
+Examples
+--------
+\>>> from crustyfuzz.distance import lcs_str
+\>>> lcs_str.similarity("part XJ-2291 rev b", "XJ-2291-B")
+7
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "similarity",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let sim = similarity(&s1, &s2, score_cutoff);
+
+    Ok(sim)
+}
+
+pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
+    lcs_str_alignment(s1, s2, score_cutoff.unwrap_or(0)).map_or(0, |res| res.score as usize)
+}
+
+/**
+Calculates the longest common substring distance in the range [0, max].
+
+This is calculated as ``max(len1, len2) - similarity``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Maximum distance between s1 and s2, that is
+    considered as a result. If the distance is bigger than score_cutoff,
+    score_cutoff + 1 is returned instead. Default is None, which deactivates
+    this behaviour.
+
+Returns
+-------
+distance : int
+    distance between s1 and s2
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "distance",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let dist = distance(&s1, &s2, score_cutoff);
+
+    Ok(dist)
+}
+
+pub fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
+    let maximum = usize::max(s1.len(), s2.len());
+    let sim_cutoff = score_cutoff.map(|cutoff| maximum.saturating_sub(cutoff));
+    let sim = similarity(s1, s2, sim_cutoff);
+    let dist = maximum - sim;
+
+    match score_cutoff {
+        Some(cutoff) if dist <= cutoff => dist,
+        Some(cutoff) => cutoff + 1,
+        None => dist,
+    }
+}
+
+/**
+Calculates a normalized longest common substring distance in the range
+[1, 0].
+
+This is calculated as ``distance / max(len1, len2)``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_dist > score_cutoff 1.0 is returned instead. Default is 1.0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_dist : float
+    normalized distance between s1 and s2 as a float between 0 and 1.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_distance",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(1.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let norm_dist = normalized_distance(&s1, &s2, score_cutoff);
+
+    Ok(norm_dist)
+}
+
+pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
+    let norm_sim = normalized_similarity(s1, s2, None);
+    let norm_dist = 1.0 - norm_sim;
+
+    match score_cutoff {
+        Some(cutoff) if norm_dist <= cutoff => norm_dist,
+        Some(_) => 1.0,
+        None => norm_dist,
+    }
+}
+
+/**
+Calculates a normalized longest common substring similarity in the range
+[0, 1].
+
+This is calculated as ``1 - normalized_distance``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_sim < score_cutoff 0 is returned instead. Default is 0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_sim : float
+    normalized similarity between s1 and s2 as a float between 0 and 1.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_similarity",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(0.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let norm_sim = normalized_similarity(&s1, &s2, score_cutoff);
+
+    Ok(norm_sim)
+}
+
+pub fn normalized_similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
+    let maximum = usize::max(s1.len(), s2.len()) as f64;
+    let sim = similarity(s1, s2, None) as f64;
+    let norm_sim = if maximum == 0.0 { 1.0 } else { sim / maximum };
+
+    match score_cutoff {
+        Some(cutoff) if norm_sim >= cutoff => norm_sim,
+        Some(_) => 0.0,
+        None => norm_sim,
+    }
+}
+
+/**
+Searches the longest common substring of s1 and s2 and returns where it
+is located in both sequences.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Minimum length of the substring. If the longest common substring is
+    shorter, None is returned instead. Default is None, which deactivates
+    this behaviour.
+
+Returns
+-------
+alignment : ScoreAlignment, optional
+    alignment of the substring with its length as score. When there are
+    several substrings of the same length, the one that ends first in s2
+    is returned.
+
+Examples
+--------
+\>>> from crustyfuzz.distance import lcs_str
+\>>> s1 = "part XJ-2291 rev b"
+\>>> s2 = "XJ-2291-B"
+\>>> res = lcs_str.alignment(s1, s2)
+\>>> res
+ScoreAlignment(score=7.0, src_start=5, src_end=12, dest_start=0, dest_end=7)
+\>>> s1[res.src_start:res.src_end]
+'XJ-2291'
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "alignment",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_alignment(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<Option<ScoreAlignment>> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(None);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+
+    Ok(alignment(&s1, &s2, score_cutoff))
+}
+
+pub fn alignment(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> Option<ScoreAlignment> {
+    lcs_str_alignment(s1, s2, score_cutoff.unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::{pseudo_random_seq, to_seq};
+
+    fn alignment_naive(s1: &[u32], s2: &[u32]) -> (usize, usize, usize) {
+        let mut best = (0, 0, 0);
+        let mut prev = vec![0; s1.len() + 1];
+        for (j, c2) in s2.iter().enumerate() {
+            let mut cur = vec![0; s1.len() + 1];
+            for (i, c1) in s1.iter().enumerate() {
+                if c1 == c2 {
+                    cur[i + 1] = prev[i] + 1;
+                    if cur[i + 1] > best.0 {
+                        best = (cur[i + 1], i + 1, j + 1);
+                    }
+                }
+            }
+            prev = cur;
+        }
+        best
+    }
+
+    #[test]
+    fn test_alignment() {
+        let s1 = to_seq("part XJ-2291 rev b");
+        let s2 = to_seq("XJ-2291-B");
+        let res = alignment(&s1, &s2, None).unwrap();
+        assert_eq!(res.score, 7.0);
+        assert_eq!((res.src_start, res.src_end), (5, 12));
+        assert_eq!((res.dest_start, res.dest_end), (0, 7));
+
+        assert!(alignment(&s1, &s2, Some(8)).is_none());
+        assert_eq!(similarity(&s1, &s2, Some(7)), 7);
+        assert_eq!(similarity(&s1, &s2, Some(8)), 0);
+        assert_eq!(distance(&s1, &s2, None), 11);
+        assert_eq!(distance(&s1, &s2, Some(10)), 11);
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(similarity(&[], &[], None), 0);
+        assert_eq!(similarity(&to_seq("abc"), &[], None), 0);
+        assert_eq!(normalized_similarity(&[], &[], None), 1.0);
+        let res = alignment(&[], &[], None).unwrap();
+        assert_eq!(res.src_end, 0);
+    }
+
+    #[test]
+    fn test_alignment_random() {
+        let lens = [1, 2, 5, 17, 64, 65, 130, 300];
+        for (seed, &len1) in lens.iter().enumerate() {
+            for &len2 in &lens {
+                let s1 = pseudo_random_seq(seed as u64, len1);
+                let s2 = pseudo_random_seq(seed as u64 + 100, len2);
+                let (len, src_end, dest_end) = alignment_naive(&s1, &s2);
+                let res = alignment(&s1, &s2, None).unwrap();
+                assert_eq!(res.score as usize, len, "len1={len1} len2={len2}");
+                assert_eq!(res.src_end, src_end, "len1={len1} len2={len2}");
+                assert_eq!(res.dest_end, dest_end, "len1={len1} len2={len2}");
+            }
+        }
+    }
+}
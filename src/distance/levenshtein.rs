@@ -1,14 +1,27 @@
-use crate::common::{common_affix, conv_sequences};
+mod cost_table;
+
+pub use cost_table::CostTable;
+
+use crate::common::pattern_match::{CheckpointedBitMatrix, PatternMatchVector};
+use crate::common::{common_affix, SeqElem};
+#[cfg(feature = "python")]
+use crate::common::{conv_sequences, with_views};
+#[cfg(feature = "python")]
+use crate::distance::conv_views;
+use crate::distance::distance_cutoff;
 use crate::distance::indel::distance as indel_distance;
-use crate::distance::models::{Editop, Editops, Opcodes};
-use num_bigint::BigUint;
+#[cfg(feature = "python")]
+use crate::distance::models::Opcodes;
+use crate::distance::models::{Editop, Editops};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
-use std::collections::HashMap;
+use std::cmp::min;
 
-#[derive(FromPyObject)]
-pub struct Weights(usize, usize, usize);
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+#[derive(Debug, Clone, Copy)]
+pub struct Weights(pub usize, pub usize, pub usize);
 
-fn levenshtein_maximum(s1: &[u32], s2: &[u32], weights: &Weights) -> usize {
+fn levenshtein_maximum<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2], weights: &Weights) -> usize {
     let len1 = s1.len();
     let len2 = s2.len();
     let (insert, delete, replace) = (weights.0, weights.1, weights.2);
@@ -22,125 +35,346 @@ fn levenshtein_maximum(s1: &[u32], s2: &[u32], weights: &Weights) -> usize {
     }
 }
 
-fn uniform_generic(s1: &[u32], s2: &[u32], weights: Weights) -> usize {
+/// Same as `levenshtein_maximum`, with the costs taken from `costs`: the
+/// cheaper of deleting s1 and inserting s2 or of replacing the elements at the
+/// same positions and deleting or inserting the rest.
+fn cost_table_maximum(s1: &[u32], s2: &[u32], weights: &Weights, costs: &CostTable) -> usize {
+    let common = min(s1.len(), s2.len());
+    let deletions = |s: &[u32]| {
+        s.iter()
+            .map(|&c| costs.delete_cost(c, weights))
+            .sum::<usize>()
+    };
+    let insertions = |s: &[u32]| {
+        s.iter()
+            .map(|&c| costs.insert_cost(c, weights))
+            .sum::<usize>()
+    };
+
+    let replacements = s1[..common]
+        .iter()
+        .zip(&s2[..common])
+        .map(|(&a, &b)| costs.substitution(a, b).unwrap_or(weights.2))
+        .sum::<usize>();
+
+    (deletions(s1) + insertions(s2))
+        .min(replacements + deletions(&s1[common..]) + insertions(&s2[common..]))
+}
+
+/// Fills row `i` of the weighted Levenshtein matrix `dp` using the costs of
+/// `costs`. Row `i` of the matrix is stored at `row_of(i) * (s2.len() + 1)`,
+/// which allows keeping only the rows substitutions can reach back to.
+fn cost_table_fill_row(
+    dp: &mut [usize],
+    row_of: impl Fn(usize) -> usize,
+    s1: &[u32],
+    s2: &[u32],
+    i: usize,
+    weights: &Weights,
+    costs: &CostTable,
+) {
+    let width = s2.len() + 1;
+    let row = row_of(i) * width;
+    if i == 0 {
+        dp[row] = 0;
+        for (j, &ch2) in s2.iter().enumerate() {
+            dp[row + j + 1] = dp[row + j] + costs.insert_cost(ch2, weights);
+        }
+        return;
+    }
+
+    let prev = row_of(i - 1) * width;
+    let ch1 = s1[i - 1];
+    let delete = costs.delete_cost(ch1, weights);
+    dp[row] = dp[prev] + delete;
+
+    for (j, &ch2) in s2.iter().enumerate().map(|(j, c)| (j + 1, c)) {
+        let mut x = (dp[prev + j] + delete)
+            .min(dp[row + j - 1] + costs.insert_cost(ch2, weights))
+            .min(dp[prev + j - 1] + costs.replace_cost(ch1, ch2, weights));
+
+        for (src, dest, cost) in costs.rules() {
+            if src.len() <= i
+                && dest.len() <= j
+                && s1[i - src.len()..i] == src[..]
+                && s2[j - dest.len()..j] == dest[..]
+            {
+                x = x.min(dp[row_of(i - src.len()) * width + j - dest.len()] + cost);
+            }
+        }
+        dp[row + j] = x;
+    }
+}
+
+/// Weighted Levenshtein distance with character specific costs. Substitutions
+/// of whole sequences rule out the bit-parallel and banded implementations, so
+/// every cell is calculated, while only keeping the rows that are still needed.
+fn cost_table_distance(s1: &[u32], s2: &[u32], weights: &Weights, costs: &CostTable) -> usize {
+    let rows = costs.max_src_len() + 1;
+    let mut dp = vec![0; rows * (s2.len() + 1)];
+    for i in 0..=s1.len() {
+        cost_table_fill_row(&mut dp, |i| i % rows, s1, s2, i, weights, costs);
+    }
+    dp[(s1.len() % rows) * (s2.len() + 1) + s2.len()]
+}
+
+/// Weighted Levenshtein distance restricted to the Ukkonen band of cells that
+/// can be reached within `score_cutoff`. Cells outside of the band are set to
+/// `score_cutoff + 1`, which is enough since they can only lead to results
+/// above the cutoff. Returns `score_cutoff + 1` once a whole column exceeds
+/// the cutoff.
+fn uniform_generic<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    weights: Weights,
+    score_cutoff: usize,
+) -> usize {
     let len1 = s1.len();
+    let score_cutoff = score_cutoff.min(levenshtein_maximum(s1, s2, &weights));
     let (insert, delete, replace) = (weights.0, weights.1, weights.2);
-    let mut cache = (0..=(len1 * delete)).step_by(delete).collect::<Vec<_>>();
+    let outside = score_cutoff + 1;
 
-    for &ch2 in s2 {
-        let mut temp = cache[0];
-        cache[0] += insert;
-        for i in 0..len1 {
+    // number of rows the band extends above and below the diagonal
+    let above = score_cutoff.checked_div(insert).unwrap_or(usize::MAX);
+    let below = score_cutoff.checked_div(delete).unwrap_or(usize::MAX);
+
+    let mut cache = (0..=len1).map(|i| i * delete).collect::<Vec<_>>();
+
+    for (j, &ch2) in s2.iter().enumerate() {
+        let first = (j + 1).saturating_sub(above);
+        let last = min(len1, (j + 1).saturating_add(below));
+
+        let mut temp;
+        let mut column_min;
+        if first == 0 {
+            temp = cache[0];
+            cache[0] += insert;
+            column_min = cache[0];
+        } else {
+            temp = cache[first - 1];
+            cache[first - 1] = outside;
+            column_min = outside;
+        }
+
+        for i in first.max(1)..=last {
             let mut x = temp;
-            if s1[i] != ch2 {
-                x = (usize::min(cache[i] + delete, cache[i + 1] + insert)).min(temp + replace);
+            if s1[i - 1].value() != ch2.value() {
+                x = (usize::min(cache[i - 1] + delete, cache[i] + insert)).min(temp + replace);
             }
-            temp = cache[i + 1];
-            cache[i + 1] = x;
+            temp = cache[i];
+            cache[i] = x;
+            column_min = column_min.min(x);
+        }
+
+        if last < len1 {
+            cache[last + 1] = outside;
+        }
+        if column_min > score_cutoff {
+            return score_cutoff + 1;
         }
     }
 
-    cache[cache.len() - 1]
+    min(cache[len1], score_cutoff + 1)
 }
 
-// fn uniform_distance(s1: &[u32], s2: &[u32]) -> usize {
-//     let len1 = s1.len();
-//     let len2 = s2.len();
-//
-//     if len1 == 0 {
-//         return len2;
-//     }
-//
-//     let all_ones = (&BigUint::from(1u32) << len1) - BigUint::from(1u32);
-//
-//     let mut vertical_positive = all_ones.clone();
-//     let mut vertical_negative = BigUint::from(0_u32);
-//     let mut current_dist = len1;
-//     let mask = &BigUint::from(1_u32) << (len1 - 1);
-//
-//     let mut block = HashMap::with_capacity(len1);
-//     let mut position = BigUint::from(1_u32);
-//     for &ch1 in s1 {
-//         block
-//             .entry(ch1)
-//             .and_modify(|e: &mut BigUint| *e |= &position)
-//             .or_insert(position.clone());
-//         position <<= 1;
-//     }
-//
-//     let b_zero = BigUint::from(0_u32);
-//     for &ch2 in s2 {
-//         let pattern_match = block.get(&ch2).unwrap_or(&b_zero);
-//         let matches = pattern_match;
-//         let diagonal_zero = (((matches & &vertical_positive) + &vertical_positive)
-//             ^ &vertical_positive)
-//             | matches
-//             | &vertical_negative;
-//         let mut horizontal_positive =
-//             &vertical_negative | (&all_ones - (&diagonal_zero | &vertical_positive));
-//         let mut horizontal_negative = &diagonal_zero & &vertical_positive;
-//         current_dist += (&horizontal_positive & &mask != BigUint::from(0_u32)) as usize;
-//         current_dist -= (&horizontal_negative & &mask != BigUint::from(0_u32)) as usize;
-//         horizontal_positive = (&horizontal_positive << 1) | BigUint::from(1_u32);
-//         horizontal_negative <<= 1;
-//         vertical_positive =
-//             &horizontal_negative | (&all_ones - (&diagonal_zero | &horizontal_positive));
-//         vertical_negative = &horizontal_positive & &diagonal_zero;
-//     }
-//
-//     current_dist
-// }
-
-fn uniform_distance(s1: &[u32], s2: &[u32]) -> usize {
-    let len1 = s1.len();
-    let len2 = s2.len();
+/// Bit-parallel Levenshtein distance of Hyyrö for s1 of up to 64 characters.
+/// Returns `score_cutoff + 1` as soon as the cutoff can no longer be reached.
+fn hyrroe2003_single_word<T: SeqElem>(
+    block: &PatternMatchVector,
+    len1: usize,
+    s2: &[T],
+    score_cutoff: usize,
+) -> usize {
+    let mut vp = !0_u64;
+    let mut vn = 0_u64;
+    let mut current_dist = len1;
+    let mask = 1_u64 << (len1 - 1);
+
+    for (j, &ch2) in s2.iter().enumerate() {
+        // Step 1: Computing D0
+        let x = block.get(ch2.value())[0];
+        let d0 = (((x & vp).wrapping_add(vp)) ^ vp) | x | vn;
+
+        // Step 2: Computing HP and HN
+        let mut hp = vn | !(d0 | vp);
+        let mut hn = d0 & vp;
+
+        // Step 3: Computing the value D[m,j]
+        current_dist += (hp & mask != 0) as usize;
+        current_dist -= (hn & mask != 0) as usize;
+        if current_dist > score_cutoff.saturating_add(s2.len() - j - 1) {
+            return score_cutoff + 1;
+        }
+
+        // Step 4: Computing VP and VN
+        hp = (hp << 1) | 1;
+        hn <<= 1;
+        vp = hn | !(d0 | hp);
+        vn = hp & d0;
+    }
+
+    current_dist
+}
+
+/// Advances the blocked bit-parallel Levenshtein state by one character of s2
+/// and returns the horizontal deltas of the last row. `last` masks the last
+/// row of s1 in the last word.
+#[inline]
+fn hyrroe2003_step(pattern: &[u64], last: u64, vp: &mut [u64], vn: &mut [u64]) -> (u64, u64) {
+    let words = vp.len();
+    let mut hp_carry = 1_u64;
+    let mut hn_carry = 0_u64;
+
+    for word in 0..words {
+        let x = pattern[word] | hn_carry;
+        let d0 = (((x & vp[word]).wrapping_add(vp[word])) ^ vp[word]) | x | vn[word];
+
+        let hp = vn[word] | !(d0 | vp[word]);
+        let hn = d0 & vp[word];
+
+        let hp_carry_in = hp_carry;
+        let hn_carry_in = hn_carry;
+        if word < words - 1 {
+            hp_carry = hp >> 63;
+            hn_carry = hn >> 63;
+        } else {
+            hp_carry = (hp & last != 0) as u64;
+            hn_carry = (hn & last != 0) as u64;
+        }
 
-    if len1 == 0 {
-        return len2;
+        let hp = (hp << 1) | hp_carry_in;
+        let hn = (hn << 1) | hn_carry_in;
+        vp[word] = hn | !(d0 | hp);
+        vn[word] = hp & d0;
     }
 
-    let b_zero = BigUint::from(0u32);
-    let b_one = BigUint::from(1u32);
+    (hp_carry, hn_carry)
+}
 
-    let all_ones = (&b_one << len1) - &b_one;
-    let mut vp = all_ones.clone();
-    let mut vn = b_zero.clone();
+/// Blocked variant of the bit-parallel Levenshtein distance, which passes the
+/// horizontal deltas of the last row of each word on to the next word.
+fn hyrroe2003_blocks<T: SeqElem>(
+    block: &PatternMatchVector,
+    len1: usize,
+    s2: &[T],
+    score_cutoff: usize,
+) -> usize {
+    let words = block.words();
+    let mut vp = vec![!0_u64; words];
+    let mut vn = vec![0_u64; words];
     let mut current_dist = len1;
-    let mask = &b_one << (len1 - 1);
+    let last = 1_u64 << ((len1 - 1) % 64);
+
+    for (j, &ch2) in s2.iter().enumerate() {
+        let (hp_carry, hn_carry) = hyrroe2003_step(block.get(ch2.value()), last, &mut vp, &mut vn);
 
-    let mut block = HashMap::with_capacity(len1);
-    let mut x = b_one.clone();
-    for &ch1 in s1 {
-        block
-            .entry(ch1)
-            .and_modify(|e: &mut BigUint| *e |= &x)
-            .or_insert(x.clone());
-        x <<= 1;
+        current_dist += hp_carry as usize;
+        current_dist -= hn_carry as usize;
+        if current_dist > score_cutoff.saturating_add(s2.len() - j - 1) {
+            return score_cutoff + 1;
+        }
     }
 
-    for &ch2 in s2 {
+    current_dist
+}
+
+/// Bit-parallel Levenshtein distance for a diagonal band of `2 * score_cutoff + 1`
+/// cells, which has to fit into a single word. The band slides down s1 by one
+/// row for every character of s2, so the distance is tracked along the lower
+/// diagonal of the band until it hits the last row of s1, and along the last
+/// row afterwards. Requires `len1 - score_cutoff <= s2.len()`.
+fn hyrroe2003_small_band<T: SeqElem>(
+    block: &PatternMatchVector,
+    len1: usize,
+    s2: &[T],
+    score_cutoff: usize,
+) -> usize {
+    let mut vp = !0_u64 << (63 - score_cutoff);
+    let mut vn = 0_u64;
+    let mut current_dist = score_cutoff;
+    let diagonal_mask = 1_u64 << 63;
+    let mut horizontal_mask = 1_u64 << 62;
+    // the distance can decrease by at most one per step along the last row
+    let break_score = score_cutoff + s2.len() - (len1 - score_cutoff);
+    for (j, &ch2) in s2.iter().enumerate() {
+        // row of s1 stored in bit 0
+        let start_pos = (j + score_cutoff) as isize + 1 - 64;
+        let pattern = block.get(ch2.value());
+        let x = if start_pos < 0 {
+            pattern[0] << -start_pos
+        } else {
+            let word = start_pos as usize / 64;
+            let word_pos = start_pos as usize % 64;
+            let mut x = pattern[word] >> word_pos;
+            if word + 1 < pattern.len() && word_pos != 0 {
+                x |= pattern[word + 1] << (64 - word_pos);
+            }
+            x
+        };
+
         // Step 1: Computing D0
-        let pm_j = block.get(&ch2).unwrap_or(&b_zero);
-        let x = pm_j;
-        let d0 = (((x & &vp) + &vp) ^ &vp) | x | &vn;
+        let d0 = (((x & vp).wrapping_add(vp)) ^ vp) | x | vn;
 
         // Step 2: Computing HP and HN
-        let mut hp = &vn | (&all_ones ^ (&d0 | &vp));
-        let mut hn = &d0 & &vp;
+        let hp = vn | !(d0 | vp);
+        let hn = d0 & vp;
 
         // Step 3: Computing the value D[m,j]
-        current_dist += (&hp & &mask != b_zero) as usize;
-        current_dist -= (&hn & &mask != b_zero) as usize;
+        if j < len1 - score_cutoff {
+            current_dist += (d0 & diagonal_mask == 0) as usize;
+        } else {
+            current_dist += (hp & horizontal_mask != 0) as usize;
+            current_dist -= (hn & horizontal_mask != 0) as usize;
+            horizontal_mask >>= 1;
+        }
+        if current_dist > break_score {
+            return score_cutoff + 1;
+        }
 
-        // Step 4: Computing VP and VN
-        hp = (&hp << 1) | &b_one;
-        hn <<= 1;
-        vp = &hn | (&all_ones ^ (&d0 | &hp));
-        vn = &hp & &d0;
+        // Step 4: Computing VP and VN, shifted by one row with the band
+        vp = hn | !((d0 >> 1) | hp);
+        vn = (d0 >> 1) & hp;
     }
 
-    current_dist
+    if current_dist <= score_cutoff {
+        current_dist
+    } else {
+        score_cutoff + 1
+    }
+}
+
+fn uniform_distance<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2], score_cutoff: usize) -> usize {
+    // the distance is symmetric, so use the longer sequence as s1
+    if s1.len() < s2.len() {
+        return uniform_distance(s2, s1, score_cutoff);
+    }
+    let score_cutoff = score_cutoff.min(s1.len());
+
+    // the length difference is a lower bound for the distance
+    if s1.len() - s2.len() > score_cutoff {
+        return score_cutoff + 1;
+    }
+
+    let (prefix_len, suffix_len) = common_affix(s1, s2);
+    let s1 = &s1[prefix_len..s1.len() - suffix_len];
+    let s2 = &s2[prefix_len..s2.len() - suffix_len];
+
+    if s2.is_empty() {
+        return s1.len();
+    }
+
+    if s2.len() <= 64 {
+        let block = PatternMatchVector::new(s2);
+        hyrroe2003_single_word(&block, s2.len(), s1, score_cutoff)
+    } else if 2 * score_cutoff < 64 {
+        let block = PatternMatchVector::new(s1);
+        hyrroe2003_small_band(&block, s1.len(), s2, score_cutoff)
+    } else {
+        let block = PatternMatchVector::new(s2);
+        hyrroe2003_blocks(&block, s2.len(), s1, score_cutoff)
+    }
 }
+
 /**
 Calculates the minimum number of insertions, deletions, and substitutions
 required to change one sequence into the other according to Levenshtein with custom
@@ -156,6 +390,10 @@ weights : Tuple[int, int, int] or None, optional
     The weights for the three operations in the form
     (insertion, deletion, substitution). Default is (1, 1, 1),
     which gives all three operations a weight of 1.
+costs : CostTable, optional
+    Character specific costs, e.g. ``CostTable.ocr()``. Costs missing from
+    the table are taken from weights. Default is None, which deactivates
+    this behaviour.
 processor : callable, optional
     Optional callable that is used to preprocess the strings before
     comparing them. Default is None, which deactivates this behaviour.
@@ -197,16 +435,26 @@ tuple.
 
 \>>> Levenshtein.distance("lewenstein", "levenshtein", weights=(1,1,2))
 3
+
+Character specific costs are passed as a `CostTable`, which makes OCR
+confusions cheaper than other substitutions:
+
+\>>> from crustyfuzz.distance.levenshtein import CostTable
+\>>> Levenshtein.distance("m0dern", "modem", weights=(2,2,2), costs=CostTable.ocr())
+2
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "distance",
-    signature = (s1, s2, *, weights=Weights(1, 1, 1), processor=None, score_cutoff=None, score_hint=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), costs=None, processor=None, score_cutoff=None, score_hint=None)
 )]
+#[allow(clippy::too_many_arguments)]
 pub fn py_distance(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
     weights: Option<Weights>,
+    costs: Option<PyRef<'_, CostTable>>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<usize>,
     score_hint: Option<usize>,
@@ -219,41 +467,38 @@ pub fn py_distance(
         None => (s1.to_owned(), s2.to_owned()),
     };
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
+    let (s1, s2) = conv_views(&s1, &s2)?;
+
+    let dist = match costs {
+        Some(costs) => distance_with_costs(
+            &s1.into_vec(),
+            &s2.into_vec(),
+            weights.unwrap_or(Weights(1, 1, 1)),
+            &costs,
+            score_cutoff,
+        ),
+        None => with_views!(&s1, &s2, |s1, s2| distance(s1, s2, weights, score_cutoff)),
     };
 
-    let dist = distance(&s1, &s2, weights, score_cutoff);
-
     Ok(dist)
 }
 
-pub fn distance(
-    s1: &[u32],
-    s2: &[u32],
+pub fn distance<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
     weights: Option<Weights>,
     score_cutoff: Option<usize>,
 ) -> usize {
+    let cutoff = score_cutoff.unwrap_or(usize::MAX);
     let dist = match weights {
-        None | Some(Weights(1, 1, 1)) => uniform_distance(s1, s2),
+        None | Some(Weights(1, 1, 1)) => uniform_distance(s1, s2, cutoff),
         Some(Weights(1, 1, 2)) => {
             if s1.is_empty() {
                 return 0;
             }
-            indel_distance(s1, s2, None)
+            indel_distance(s1, s2, score_cutoff)
         }
-        _ => uniform_generic(s1, s2, weights.unwrap()),
+        _ => uniform_generic(s1, s2, weights.unwrap(), cutoff),
     };
 
     match score_cutoff {
@@ -280,6 +525,10 @@ weights : Tuple[int, int, int] or None, optional
     The weights for the three operations in the form
     (insertion, deletion, substitution). Default is (1, 1, 1),
     which gives all three operations a weight of 1.
+costs : CostTable, optional
+    Character specific costs, e.g. ``CostTable.ocr()``. Costs missing from
+    the table are taken from weights. Default is None, which deactivates
+    this behaviour.
 processor : callable, optional
     Optional callable that is used to preprocess the strings before
     comparing them. Default is None, which deactivates this behaviour.
@@ -302,15 +551,18 @@ Raises
 ValueError
     If unsupported weights are provided a ValueError is thrown
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "similarity",
-    signature = (s1, s2, *, weights=Weights(1, 1, 1), processor=None, score_cutoff=None, score_hint=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), costs=None, processor=None, score_cutoff=None, score_hint=None)
 )]
+#[allow(clippy::too_many_arguments)]
 pub fn py_similarity(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
     weights: Option<Weights>,
+    costs: Option<PyRef<'_, CostTable>>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<usize>,
     score_hint: Option<usize>,
@@ -323,31 +575,33 @@ pub fn py_similarity(
         None => (s1.to_owned(), s2.to_owned()),
     };
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
     let weights = weights.unwrap_or(Weights(1, 1, 1));
-    let sim = similarity(&s1, &s2, weights, score_cutoff);
+    let sim = match costs {
+        Some(costs) => similarity_with_costs(
+            &s1.into_vec(),
+            &s2.into_vec(),
+            weights,
+            &costs,
+            score_cutoff,
+        ),
+        None => with_views!(&s1, &s2, |s1, s2| similarity(s1, s2, weights, score_cutoff)),
+    };
 
     Ok(sim)
 }
 
-pub fn similarity(s1: &[u32], s2: &[u32], weights: Weights, score_cutoff: Option<usize>) -> usize {
+pub fn similarity<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    weights: Weights,
+    score_cutoff: Option<usize>,
+) -> usize {
     let maximum = levenshtein_maximum(s1, s2, &weights);
-    let dist = distance(s1, s2, Some(weights), None);
-    let sim = maximum - dist;
+    let dist_cutoff = score_cutoff.map(|cutoff| maximum.saturating_sub(cutoff));
+    let dist = distance(s1, s2, Some(weights), dist_cutoff);
+    let sim = maximum.saturating_sub(dist);
 
     match score_cutoff {
         Some(cutoff) if sim >= cutoff => sim,
@@ -356,15 +610,18 @@ pub fn similarity(s1: &[u32], s2: &[u32], weights: Weights, score_cutoff: Option
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_distance",
-    signature = (s1, s2, *, weights=Weights(1, 1, 1), processor=None, score_cutoff=None, score_hint=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), costs=None, processor=None, score_cutoff=None, score_hint=None)
 )]
+#[allow(clippy::too_many_arguments)]
 pub fn py_normalized_distance(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
     weights: Option<Weights>,
+    costs: Option<PyRef<'_, CostTable>>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<f64>,
     score_hint: Option<usize>,
@@ -381,39 +638,41 @@ pub fn py_normalized_distance(
         None => (s1.to_owned(), s2.to_owned()),
     };
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
     let weights = weights.unwrap_or(Weights(1, 1, 1));
-    let norm_dist = normalized_distance(&s1, &s2, weights, score_cutoff);
+    let norm_dist = match costs {
+        Some(costs) => normalized_distance_with_costs(
+            &s1.into_vec(),
+            &s2.into_vec(),
+            weights,
+            &costs,
+            score_cutoff,
+        ),
+        None => with_views!(&s1, &s2, |s1, s2| normalized_distance(
+            s1,
+            s2,
+            weights,
+            score_cutoff
+        )),
+    };
 
     Ok(norm_dist)
 }
 
-pub fn normalized_distance(
-    s1: &[u32],
-    s2: &[u32],
+pub fn normalized_distance<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
     weights: Weights,
     score_cutoff: Option<f64>,
 ) -> f64 {
     let maximum = levenshtein_maximum(s1, s2, &weights);
-    let dist = distance(s1, s2, Some(weights), None);
-    let norm_dist = match maximum {
-        0 => 0.0,
-        _ => dist as f64 / maximum as f64,
-    };
+    let dist_cutoff = score_cutoff.map(|cutoff| {
+        distance_cutoff(maximum, maximum as f64 * cutoff, |dist| {
+            dist as f64 / maximum as f64 <= cutoff
+        })
+    });
+    let norm_dist = normalized_distance_with_cutoff(s1, s2, weights, maximum, dist_cutoff);
 
     match score_cutoff {
         Some(cutoff) if norm_dist <= cutoff => norm_dist,
@@ -422,15 +681,33 @@ pub fn normalized_distance(
     }
 }
 
+/// Normalized distance, where distances above `dist_cutoff` are only known to
+/// be larger than it.
+fn normalized_distance_with_cutoff<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    weights: Weights,
+    maximum: usize,
+    dist_cutoff: Option<usize>,
+) -> f64 {
+    match maximum {
+        0 => 0.0,
+        _ => distance(s1, s2, Some(weights), dist_cutoff) as f64 / maximum as f64,
+    }
+}
+
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_similarity",
-    signature = (s1, s2, *, weights=Weights(1, 1, 1), processor=None, score_cutoff=None, score_hint=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), costs=None, processor=None, score_cutoff=None, score_hint=None)
 )]
+#[allow(clippy::too_many_arguments)]
 pub fn py_normalized_similarity(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
     weights: Option<Weights>,
+    costs: Option<PyRef<'_, CostTable>>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<f64>,
     score_hint: Option<usize>,
@@ -447,34 +724,41 @@ pub fn py_normalized_similarity(
         None => (s1.to_owned(), s2.to_owned()),
     };
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
     let weights = weights.unwrap_or(Weights(1, 1, 1));
-    let norm_sim = normalized_similarity(&s1, &s2, weights, score_cutoff);
+    let norm_sim = match costs {
+        Some(costs) => normalized_similarity_with_costs(
+            &s1.into_vec(),
+            &s2.into_vec(),
+            weights,
+            &costs,
+            score_cutoff,
+        ),
+        None => with_views!(&s1, &s2, |s1, s2| normalized_similarity(
+            s1,
+            s2,
+            weights,
+            score_cutoff
+        )),
+    };
 
     Ok(norm_sim)
 }
 
-pub fn normalized_similarity(
-    s1: &[u32],
-    s2: &[u32],
+pub fn normalized_similarity<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
     weights: Weights,
     score_cutoff: Option<f64>,
 ) -> f64 {
-    let norm_dist = normalized_distance(s1, s2, weights, None);
+    let maximum = levenshtein_maximum(s1, s2, &weights);
+    let dist_cutoff = score_cutoff.map(|cutoff| {
+        distance_cutoff(maximum, maximum as f64 * (1.0 - cutoff), |dist| {
+            1.0 - dist as f64 / maximum as f64 >= cutoff
+        })
+    });
+    let norm_dist = normalized_distance_with_cutoff(s1, s2, weights, maximum, dist_cutoff);
     let norm_sim = 1.0 - norm_dist;
 
     match score_cutoff {
@@ -484,59 +768,104 @@ pub fn normalized_similarity(
     }
 }
 
-fn matrix(s1: &[u32], s2: &[u32]) -> (usize, Vec<BigUint>, Vec<BigUint>) {
-    let len1 = s1.len();
-    let len2 = s2.len();
-    if len1 == 0 {
-        return (len2, Vec::new(), Vec::new());
+/// Levenshtein distance using the character specific costs of `costs`. Costs
+/// missing from the table are taken from `weights`.
+pub fn distance_with_costs(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    costs: &CostTable,
+    score_cutoff: Option<usize>,
+) -> usize {
+    if costs.is_empty() {
+        return distance(s1, s2, Some(weights), score_cutoff);
     }
 
-    let b_zero = BigUint::from(0u32);
-    let b_one = BigUint::from(1u32);
+    let dist = cost_table_distance(s1, s2, &weights, costs);
+    match score_cutoff {
+        Some(cutoff) if dist <= cutoff => dist,
+        Some(cutoff) => cutoff + 1,
+        None => dist,
+    }
+}
 
-    let all_ones = (&b_one << len1) - &b_one;
-    let mut vertical_positive = all_ones.clone();
-    let mut vertical_negative = b_zero.clone();
-    let mut current_dist = len1;
-    let mask = BigUint::from(1_u32) << (len1 - 1);
-
-    let mut block = HashMap::with_capacity(len1);
-    let mut position = BigUint::from(1_u32);
-    for &ch1 in s1 {
-        block
-            .entry(ch1)
-            .and_modify(|e: &mut BigUint| *e |= &position)
-            .or_insert(position.clone());
-        position <<= 1;
-    }
-
-    let mut matrix_vp = Vec::new();
-    let mut matrix_vn = Vec::new();
-    for &ch2 in s2 {
-        let pattern_match = block.get(&ch2).unwrap_or(&b_zero);
-        let matches = pattern_match;
-        let diagonal_zero = ((matches & &vertical_positive) + &vertical_positive)
-            ^ &vertical_positive
-            | matches
-            | &vertical_negative;
-
-        let mut horizontal_positive =
-            &vertical_negative | (&all_ones ^ (&diagonal_zero | &vertical_positive));
-        let mut horizontal_negative = &diagonal_zero & &vertical_positive;
-
-        current_dist += (&horizontal_positive & &mask != b_zero) as usize;
-        current_dist -= (&horizontal_negative & &mask != b_zero) as usize;
-
-        horizontal_positive = (horizontal_positive << 1) | &b_one;
-        horizontal_negative <<= 1;
-        vertical_positive =
-            &horizontal_negative | (&all_ones ^ (&diagonal_zero | &horizontal_positive));
-        vertical_negative = &horizontal_positive & &diagonal_zero;
-        matrix_vp.push(vertical_positive.clone());
-        matrix_vn.push(vertical_negative.clone());
-    }
-
-    (current_dist, matrix_vp, matrix_vn)
+pub fn similarity_with_costs(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    costs: &CostTable,
+    score_cutoff: Option<usize>,
+) -> usize {
+    let maximum = cost_table_maximum(s1, s2, &weights, costs);
+    let dist = distance_with_costs(s1, s2, weights, costs, None);
+    let sim = maximum - dist;
+
+    match score_cutoff {
+        Some(cutoff) if sim >= cutoff => sim,
+        Some(_) => 0,
+        None => sim,
+    }
+}
+
+pub fn normalized_distance_with_costs(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    costs: &CostTable,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let maximum = cost_table_maximum(s1, s2, &weights, costs);
+    let dist = distance_with_costs(s1, s2, weights, costs, None);
+    let norm_dist = match maximum {
+        0 => 0.0,
+        _ => dist as f64 / maximum as f64,
+    };
+
+    match score_cutoff {
+        Some(cutoff) if norm_dist <= cutoff => norm_dist,
+        Some(_) => 1.0,
+        None => norm_dist,
+    }
+}
+
+pub fn normalized_similarity_with_costs(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    costs: &CostTable,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let norm_dist = normalized_distance_with_costs(s1, s2, weights, costs, None);
+    let norm_sim = 1.0 - norm_dist;
+
+    match score_cutoff {
+        Some(cutoff) if norm_sim >= cutoff => norm_sim,
+        Some(_) => 0.0,
+        None => norm_sim,
+    }
+}
+
+/// VP and VN of every row, stored next to each other. VN of column `col` is
+/// found at `col + 64 * words`.
+fn matrix<'a>(
+    s1: &[u32],
+    s2: &'a [u32],
+) -> (
+    usize,
+    CheckpointedBitMatrix<impl FnMut(usize, &mut [u64]) + 'a>,
+) {
+    let block = PatternMatchVector::new(s1);
+    let words = block.words();
+    let last = 1_u64 << (s1.len().saturating_sub(1) % 64);
+
+    let mut initial = vec![!0_u64; words];
+    initial.resize(2 * words, 0);
+    let matrix = CheckpointedBitMatrix::new(s2.len(), initial, move |row, state| {
+        let (vp, vn) = state.split_at_mut(words);
+        hyrroe2003_step(block.get(s2[row]), last, vp, vn);
+    });
+
+    (words * 64, matrix)
 }
 
 /**
@@ -548,6 +877,14 @@ s1 : Sequence[Hashable]
     First string to compare.
 s2 : Sequence[Hashable]
     Second string to compare.
+weights : Tuple[int, int, int] or None, optional
+    The weights for the three operations in the form
+    (insertion, deletion, substitution). Default is (1, 1, 1),
+    which gives all three operations a weight of 1.
+costs : CostTable, optional
+    Character specific costs. Substitutions of several elements are split
+    into replacements and insertions or deletions. Default is None, which
+    deactivates this behaviour.
 processor : callable, optional
     Optional callable that is used to preprocess the strings before
     comparing them. Default is None, which deactivates this behaviour.
@@ -564,6 +901,8 @@ Notes
 -----
 The alignment is calculated using an algorithm of Heikki Hyyrö, which is
 described [8]_. It has a time complexity and memory usage of ``O([N/64] * M)``.
+Custom weights or costs require the full matrix, which takes ``O(N * M)`` time.
+Only checkpoints of it are kept, so the memory usage is ``O(sqrt(N) * M)``.
 
 References
 ----------
@@ -579,14 +918,17 @@ Examples
 replace s1[3] s2[2]
     insert s1[6] s2[5]
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "editops",
-    signature = (s1, s2, *, processor=None, score_hint=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), costs=None, processor=None, score_hint=None)
 )]
 pub fn py_editops(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    costs: Option<PyRef<'_, CostTable>>,
     processor: Option<&Bound<'_, PyAny>>,
     score_hint: Option<usize>,
 ) -> PyResult<Editops> {
@@ -613,14 +955,18 @@ pub fn py_editops(
         }
     };
 
-    Ok(editops(&s1, &s2))
+    Ok(match (weights.unwrap_or(Weights(1, 1, 1)), costs) {
+        (Weights(1, 1, 1), None) => editops(&s1, &s2),
+        (weights, None) => editops_with_costs(&s1, &s2, weights, &CostTable::new()),
+        (weights, Some(costs)) => editops_with_costs(&s1, &s2, weights, &costs),
+    })
 }
 
 pub fn editops(s1: &[u32], s2: &[u32]) -> Editops {
     let (prefix_len, suffix_len) = common_affix(s1, s2);
     let s1 = &s1[prefix_len..s1.len() - suffix_len];
     let s2 = &s2[prefix_len..s2.len() - suffix_len];
-    let (dist, matrix_vp, matrix_vn) = matrix(s1, s2);
+    let (vn_offset, mut matrix) = matrix(s1, s2);
 
     // take the length after adjusting
     let len1 = s1.len();
@@ -628,17 +974,12 @@ pub fn editops(s1: &[u32], s2: &[u32]) -> Editops {
     let src_len = len1 + prefix_len + suffix_len;
     let dest_len = len2 + prefix_len + suffix_len;
 
-    if dist == 0 {
-        return Editops::new(src_len, dest_len, Vec::new());
-    }
-
-    let mut editop_vec = Vec::with_capacity(dist);
+    let mut editop_vec = Vec::new();
     let mut col = len1;
     let mut row = len2;
     while row != 0 && col != 0 {
-        let point_mask = BigUint::from(1_u32) << (col - 1);
         // deletion
-        if (&matrix_vp[row - 1] & &point_mask) != BigUint::from(0_u32) {
+        if matrix.test_bit(row - 1, col - 1) {
             col -= 1;
             editop_vec.push(Editop {
                 tag: "delete".to_string(),
@@ -649,7 +990,7 @@ pub fn editops(s1: &[u32], s2: &[u32]) -> Editops {
             row -= 1;
 
             // insertion
-            if (row != 0) && (&matrix_vn[row - 1] & &point_mask) != BigUint::from(0_u32) {
+            if (row != 0) && matrix.test_bit(row - 1, vn_offset + col - 1) {
                 editop_vec.push(Editop {
                     tag: "insert".to_string(),
                     src_pos: col + prefix_len,
@@ -692,24 +1033,149 @@ pub fn editops(s1: &[u32], s2: &[u32]) -> Editops {
     Editops::new(src_len, dest_len, editop_vec)
 }
 
+/// Editops of the cheapest way to turn s1 into s2 using the costs of `costs`.
+/// Substitutions of whole sequences are split into replacements of the
+/// elements at the same offsets and deletions or insertions of the rest.
+pub fn editops_with_costs(s1: &[u32], s2: &[u32], weights: Weights, costs: &CostTable) -> Editops {
+    let block = (s1.len() + 1).isqrt();
+    editops_with_costs_blocked(s1, s2, weights, costs, block)
+}
+
+/// Backtracks the weighted Levenshtein matrix in blocks of `block` rows.
+/// The first pass only keeps the rows in front of every block, which
+/// substitutions can reach back to. The blocks are then recalculated from
+/// these rows one at a time while backtracking, so the matrix never has to be
+/// stored as a whole.
+fn editops_with_costs_blocked(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    costs: &CostTable,
+    block: usize,
+) -> Editops {
+    let width = s2.len() + 1;
+    let context = costs.max_src_len();
+    let block = block.max(context);
+    let blocks = s1.len() / block + 1;
+
+    // checkpoints[k - 1] holds the `context` rows in front of block k
+    let mut checkpoints = vec![0; (blocks - 1) * context * width];
+    let rows = context + 1;
+    let mut ring = vec![0; rows * width];
+    for i in 0..=s1.len() {
+        cost_table_fill_row(&mut ring, |i| i % rows, s1, s2, i, &weights, costs);
+        let k = i / block + 1;
+        if k < blocks && i + context >= k * block {
+            let dest = ((k - 1) * context + i + context - k * block) * width;
+            let src = (i % rows) * width;
+            checkpoints[dest..dest + width].copy_from_slice(&ring[src..src + width]);
+        }
+    }
+    drop(ring);
+
+    // rows start - context ..= start + block - 1 of the current block
+    let mut buf = vec![0; (context + block) * width];
+    let fill_block = |buf: &mut [usize], start: usize| {
+        if start != 0 {
+            let k = start / block;
+            let src = (k - 1) * context * width;
+            buf[..context * width].copy_from_slice(&checkpoints[src..src + context * width]);
+        }
+        let end = min(start + block, s1.len() + 1);
+        for i in start..end {
+            cost_table_fill_row(buf, |i| i + context - start, s1, s2, i, &weights, costs);
+        }
+    };
+
+    let mut start = s1.len() / block * block;
+    fill_block(&mut buf, start);
+    let editop = |tag: &str, src_pos: usize, dest_pos: usize| Editop {
+        tag: tag.to_string(),
+        src_pos,
+        dest_pos,
+    };
+
+    let mut editop_vec = Vec::new();
+    let (mut i, mut j) = (s1.len(), s2.len());
+    while i != 0 || j != 0 {
+        if i < start {
+            start -= block;
+            fill_block(&mut buf, start);
+        }
+        let cell = |i: usize, j: usize| buf[(i + context - start) * width + j];
+        let current = cell(i, j);
+        if i != 0 && j != 0 && s1[i - 1] == s2[j - 1] && current == cell(i - 1, j - 1) {
+            i -= 1;
+            j -= 1;
+        } else if i != 0 && current == cell(i - 1, j) + costs.delete_cost(s1[i - 1], &weights) {
+            i -= 1;
+            editop_vec.push(editop("delete", i, j));
+        } else if j != 0 && current == cell(i, j - 1) + costs.insert_cost(s2[j - 1], &weights) {
+            j -= 1;
+            editop_vec.push(editop("insert", i, j));
+        } else if i != 0
+            && j != 0
+            && current == cell(i - 1, j - 1) + costs.replace_cost(s1[i - 1], s2[j - 1], &weights)
+        {
+            i -= 1;
+            j -= 1;
+            editop_vec.push(editop("replace", i, j));
+        } else {
+            let (src_len, dest_len) = costs
+                .rules()
+                .iter()
+                .find(|(src, dest, cost)| {
+                    src.len() <= i
+                        && dest.len() <= j
+                        && s1[i - src.len()..i] == src[..]
+                        && s2[j - dest.len()..j] == dest[..]
+                        && current == cell(i - src.len(), j - dest.len()) + cost
+                })
+                .map(|(src, dest, _)| (src.len(), dest.len()))
+                .expect("every cell is reached by one of the operations");
+            let common = min(src_len, dest_len);
+            i -= src_len;
+            j -= dest_len;
+
+            // pushed in reverse, since the editops are reversed at the end
+            for k in (common..src_len).rev() {
+                editop_vec.push(editop("delete", i + k, j + common));
+            }
+            for k in (common..dest_len).rev() {
+                editop_vec.push(editop("insert", i + common, j + k));
+            }
+            for k in (0..common).rev() {
+                editop_vec.push(editop("replace", i + k, j + k));
+            }
+        }
+    }
+
+    editop_vec.reverse();
+    Editops::new(s1.len(), s2.len(), editop_vec)
+}
+
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "opcodes",
-    signature = (s1, s2, *, processor=None, score_hint=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1), costs=None, processor=None, score_hint=None)
 )]
 pub fn py_opcodes(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    costs: Option<PyRef<'_, CostTable>>,
     processor: Option<&Bound<'_, PyAny>>,
     score_hint: Option<usize>,
 ) -> PyResult<Opcodes> {
-    let editops = py_editops(s1, s2, processor, score_hint)?;
+    let editops = py_editops(s1, s2, weights, costs, processor, score_hint)?;
     Ok(editops.as_opcodes())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::test_utils::{pseudo_random_seq, to_seq};
 
     #[test]
     fn test_distance() {
@@ -797,4 +1263,324 @@ mod tests {
             result.as_opcodes().as_matching_blocks(),
         );
     }
+
+    #[test]
+    fn test_uniform_distance_blocks() {
+        for (i, (len1, len2)) in [(1, 70), (63, 64), (64, 64), (65, 3), (130, 200), (200, 129)]
+            .into_iter()
+            .enumerate()
+        {
+            let s1 = pseudo_random_seq(i as u64, len1);
+            let s2 = pseudo_random_seq(i as u64 + 100, len2);
+            assert_eq!(
+                uniform_distance(&s1, &s2, usize::MAX),
+                uniform_generic(&s1, &s2, Weights(1, 1, 1), usize::MAX),
+                "len1={} len2={}",
+                len1,
+                len2
+            );
+        }
+    }
+
+    #[test]
+    fn test_mixed_element_widths() {
+        for (i, (len1, len2)) in [(10, 12), (60, 64), (100, 90), (130, 200)]
+            .into_iter()
+            .enumerate()
+        {
+            let s1 = pseudo_random_seq(i as u64, len1);
+            let s2 = pseudo_random_seq(i as u64 + 100, len2);
+            // the same characters stored with one and two bytes each
+            let s1_ucs1 = s1.iter().map(|&c| c as u8).collect::<Vec<_>>();
+            let s2_ucs2 = s2.iter().map(|&c| c as u16).collect::<Vec<_>>();
+            for cutoff in [None, Some(5), Some(40)] {
+                for weights in [None, Some(Weights(1, 1, 2)), Some(Weights(2, 3, 4))] {
+                    assert_eq!(
+                        distance(&s1_ucs1, &s2_ucs2, weights, cutoff),
+                        distance(&s1, &s2, weights, cutoff)
+                    );
+                    assert_eq!(
+                        distance(&s2_ucs2, &s1_ucs1, weights, cutoff),
+                        distance(&s2, &s1, weights, cutoff)
+                    );
+                }
+            }
+        }
+    }
+
+    fn distance_dp(s1: &[u32], s2: &[u32], weights: &Weights) -> usize {
+        let (insert, delete, replace) = (weights.0, weights.1, weights.2);
+        let mut prev = (0..=s1.len()).map(|i| i * delete).collect::<Vec<_>>();
+        for (j, &ch2) in s2.iter().enumerate() {
+            let mut curr = vec![(j + 1) * insert; s1.len() + 1];
+            for (i, &ch1) in s1.iter().enumerate() {
+                let cost = if ch1 == ch2 { 0 } else { replace };
+                curr[i + 1] = (prev[i] + cost)
+                    .min(prev[i + 1] + insert)
+                    .min(curr[i] + delete);
+            }
+            prev = curr;
+        }
+        prev[s1.len()]
+    }
+
+    /// applies `edits` pseudo random insertions, deletions and substitutions
+    fn mutate(seq: &[u32], seed: u64, edits: usize) -> Vec<u32> {
+        let mut result = seq.to_vec();
+        let mut state = seed;
+        for _ in 0..edits {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let pos = (state >> 33) as usize % (result.len() + 1);
+            match state % 3 {
+                0 => result.insert(pos, 7),
+                1 if pos < result.len() => {
+                    result.remove(pos);
+                }
+                _ if pos < result.len() => result[pos] = 8,
+                _ => result.push(9),
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_distance_score_cutoff() {
+        for (i, len) in [0, 1, 10, 64, 65, 100, 300].into_iter().enumerate() {
+            for edits in [0, 1, 2, 5, 40] {
+                let s1 = pseudo_random_seq(i as u64, len);
+                let s2 = mutate(&s1, i as u64 + edits as u64, edits);
+                let expected = distance_dp(&s1, &s2, &Weights(1, 1, 1));
+                for cutoff in [0, 1, 2, 3, 5, 31, 32, 40, 100] {
+                    let expected = expected.min(cutoff + 1);
+                    assert_eq!(distance(&s1, &s2, None, Some(cutoff)), expected);
+                    assert_eq!(distance(&s2, &s1, None, Some(cutoff)), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_distance_score_cutoff() {
+        let weights = [
+            Weights(1, 1, 3),
+            Weights(2, 3, 1),
+            Weights(3, 1, 2),
+            Weights(1, 4, 6),
+        ];
+        for (i, len) in [0, 1, 10, 70].into_iter().enumerate() {
+            for edits in [0, 2, 10] {
+                let s1 = pseudo_random_seq(i as u64, len);
+                let s2 = mutate(&s1, i as u64 + edits as u64, edits);
+                for w in &weights {
+                    let expected = distance_dp(&s1, &s2, w);
+                    assert_eq!(
+                        uniform_generic(&s1, &s2, Weights(w.0, w.1, w.2), usize::MAX),
+                        expected
+                    );
+                    for cutoff in 0..expected + 2 {
+                        assert_eq!(
+                            uniform_generic(&s1, &s2, Weights(w.0, w.1, w.2), cutoff),
+                            expected.min(cutoff + 1),
+                            "len={len} edits={edits} cutoff={cutoff}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_distance_zero_weights() {
+        let (s1, s2) = (to_seq("a"), to_seq("babbbab"));
+        assert_eq!(distance(&s1, &s2, Some(Weights(0, 1, 1)), None), 0);
+        assert_eq!(distance(&s2, &s1, Some(Weights(1, 0, 1)), None), 0);
+
+        let weights = [Weights(0, 1, 1), Weights(1, 0, 1), Weights(0, 0, 1)];
+        for seed in 0..20 {
+            let s1 = pseudo_random_seq(seed, (seed as usize * 3) % 20);
+            let s2 = mutate(&s1, seed + 50, seed as usize % 12);
+            for w in &weights {
+                for (a, b) in [(&s1, &s2), (&s2, &s1)] {
+                    let expected = distance_dp(a, b, w);
+                    for cutoff in [0, 1, 2, usize::MAX] {
+                        assert_eq!(
+                            uniform_generic(a, b, Weights(w.0, w.1, w.2), cutoff),
+                            expected.min(cutoff.saturating_add(1)),
+                            "seed={seed} cutoff={cutoff}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_similarity_score_cutoff() {
+        let weights = [Weights(1, 1, 1), Weights(1, 1, 2), Weights(3, 1, 2)];
+        for (i, len) in [0, 10, 70, 150].into_iter().enumerate() {
+            for edits in [0, 3, 30] {
+                let s1 = pseudo_random_seq(i as u64, len);
+                let s2 = mutate(&s1, i as u64 + edits as u64, edits);
+                for w in weights {
+                    let sim = similarity(&s1, &s2, w, None);
+                    for cutoff in [0, 1, sim.saturating_sub(1), sim, sim + 1, sim + 10] {
+                        let expected = if sim >= cutoff { sim } else { 0 };
+                        assert_eq!(similarity(&s1, &s2, w, Some(cutoff)), expected);
+                    }
+
+                    let norm_dist = normalized_distance(&s1, &s2, w, None);
+                    let norm_sim = normalized_similarity(&s1, &s2, w, None);
+                    for cutoff in [0.0, 0.1, 0.3, 0.5, 0.7, 0.9, 1.0] {
+                        for cutoff in [cutoff, norm_dist, norm_sim] {
+                            let expected = if norm_dist <= cutoff { norm_dist } else { 1.0 };
+                            assert_eq!(normalized_distance(&s1, &s2, w, Some(cutoff)), expected);
+                            let expected = if norm_sim >= cutoff { norm_sim } else { 0.0 };
+                            assert_eq!(normalized_similarity(&s1, &s2, w, Some(cutoff)), expected);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cost_table_matches_weights() {
+        let mut costs = CostTable::new();
+        // never used, so the generic implementation is selected
+        costs.set_insertion(1000, 1);
+        for (i, len) in [0, 1, 10, 70].into_iter().enumerate() {
+            for edits in [0, 2, 10] {
+                let s1 = pseudo_random_seq(i as u64, len);
+                let s2 = mutate(&s1, i as u64 + edits as u64, edits);
+                for w in [Weights(1, 1, 1), Weights(2, 3, 1), Weights(1, 4, 6)] {
+                    assert_eq!(
+                        distance_with_costs(&s1, &s2, w, &costs, None),
+                        distance_dp(&s1, &s2, &w)
+                    );
+                    assert_eq!(
+                        cost_table_maximum(&s1, &s2, &w, &costs),
+                        levenshtein_maximum(&s1, &s2, &w)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cost_table_distance() {
+        let costs = CostTable::ocr(1);
+        let weights = Weights(2, 2, 2);
+        let dist =
+            |a: &str, b: &str| distance_with_costs(&to_seq(a), &to_seq(b), weights, &costs, None);
+        assert_eq!(dist("0CR", "OCR"), 1);
+        assert_eq!(dist("modern", "modem"), 1);
+        assert_eq!(dist("modem", "modern"), 1);
+        assert_eq!(dist("1ast", "last"), 1);
+        assert_eq!(dist("fast", "last"), 2);
+        assert_eq!(dist("", "rn"), 4);
+
+        let s1 = to_seq("clear");
+        let s2 = to_seq("dear");
+        assert_eq!(distance_with_costs(&s1, &s2, weights, &costs, Some(0)), 1);
+        assert_eq!(similarity_with_costs(&s1, &s2, weights, &costs, None), 9);
+        assert_eq!(
+            normalized_distance_with_costs(&s1, &s2, weights, &costs, None),
+            0.1
+        );
+
+        let mut costs = CostTable::new();
+        costs.set_deletion('x' as u32, 5);
+        costs.set_insertion('x' as u32, 1);
+        assert_eq!(
+            distance_with_costs(&to_seq("ax"), &to_seq("a"), Weights(1, 1, 1), &costs, None),
+            2
+        );
+        assert_eq!(
+            distance_with_costs(&to_seq("a"), &to_seq("ax"), Weights(1, 1, 1), &costs, None),
+            1
+        );
+    }
+
+    #[test]
+    fn test_editops_with_costs() {
+        let mut costs = CostTable::ocr(1);
+        costs.merge(&CostTable::qwerty(1));
+        let weights = Weights(3, 3, 3);
+        for (a, b) in [
+            ("modern", "modem"),
+            ("modem", "modern"),
+            ("clown", "down"),
+            ("hello", "jrllp"),
+            ("", "abc"),
+            ("vvord", "world"),
+        ] {
+            let (s1, s2) = (to_seq(a), to_seq(b));
+            let ops = editops_with_costs(&s1, &s2, weights, &costs);
+            assert_eq!(ops.apply(a, b), b, "{a} -> {b}");
+        }
+
+        let ops = editops_with_costs(&to_seq("modern"), &to_seq("modem"), weights, &costs);
+        assert_eq!(
+            ops,
+            Editops::new(
+                6,
+                5,
+                vec![
+                    Editop {
+                        tag: "replace".to_string(),
+                        src_pos: 4,
+                        dest_pos: 4
+                    },
+                    Editop {
+                        tag: "delete".to_string(),
+                        src_pos: 5,
+                        dest_pos: 5
+                    }
+                ]
+            )
+        );
+
+        // without a table the result matches the uniform implementation
+        let (s1, s2) = (to_seq("qabxcd"), to_seq("abycdf"));
+        assert_eq!(
+            editops_with_costs(&s1, &s2, Weights(1, 1, 1), &CostTable::new()).len(),
+            editops(&s1, &s2).len()
+        );
+    }
+
+    #[test]
+    fn test_editops_with_costs_blocked() {
+        let mut costs = CostTable::ocr(1);
+        costs.set_substitution(&[1, 2, 3], &[0], 1).unwrap();
+        costs.set_substitution(&[2], &[3, 3], 1).unwrap();
+        let weights = Weights(2, 3, 4);
+        for seed in 0..20 {
+            let s1 = pseudo_random_seq(seed, (seed as usize * 7) % 60);
+            let s2 = mutate(&s1, seed + 100, 10);
+            let expected = editops_with_costs_blocked(&s1, &s2, weights, &costs, s1.len() + 1);
+            for block in [1, 2, 3, 5, 8] {
+                let ops = editops_with_costs_blocked(&s1, &s2, weights, &costs, block);
+                assert_eq!(ops, expected, "seed {seed}, block {block}");
+            }
+            assert_eq!(editops_with_costs(&s1, &s2, weights, &costs), expected);
+        }
+    }
+
+    #[test]
+    fn test_editops_checkpointed() {
+        // large enough for the matrix to be split into several blocks
+        let s1 = pseudo_random_seq(7, 6000);
+        let s2 = mutate(&s1, 8, 300);
+        let ops = editops(&s1, &s2);
+        assert_eq!(ops.len(), distance(&s1, &s2, None, None));
+
+        let to_str = |s: &[u32]| {
+            s.iter()
+                .map(|&c| char::from(b'a' + c as u8))
+                .collect::<String>()
+        };
+        assert_eq!(ops.apply(&to_str(&s1), &to_str(&s2)), to_str(&s2));
+    }
 }
@@ -0,0 +1,463 @@
+use super::Weights;
+#[cfg(feature = "python")]
+use crate::common::conv_sequence;
+use crate::common::error::CrustyError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyDict;
+use std::collections::HashMap;
+
+/// Rows of a US QWERTY keyboard. Every row is shifted to the right by half a
+/// key compared to the row above it.
+const QWERTY_ROWS: [&str; 4] = ["1234567890-=", "qwertyuiop[]", "asdfghjkl;'", "zxcvbnm,./"];
+
+/// Sequences that are commonly confused by OCR engines.
+const OCR_CONFUSIONS: [(&str, &str); 20] = [
+    ("0", "O"),
+    ("0", "o"),
+    ("0", "D"),
+    ("O", "D"),
+    ("O", "Q"),
+    ("1", "l"),
+    ("1", "I"),
+    ("1", "i"),
+    ("l", "I"),
+    ("5", "S"),
+    ("2", "Z"),
+    ("8", "B"),
+    ("6", "G"),
+    ("9", "g"),
+    ("u", "v"),
+    ("c", "e"),
+    ("rn", "m"),
+    ("cl", "d"),
+    ("vv", "w"),
+    ("VV", "W"),
+];
+
+fn to_seq(s: &str) -> Vec<u32> {
+    s.chars().map(|c| c as u32).collect()
+}
+
+/**
+Character specific costs for the weighted Levenshtein distance.
+
+Substitutions can replace a single element or a whole sequence, e.g. "rn"
+with "m". Costs that are not part of the table fall back to the weights the
+distance is calculated with.
+*/
+#[cfg_attr(
+    feature = "python",
+    pyclass(module = "crustyfuzz.distance.levenshtein")
+)]
+#[derive(Debug, Clone, Default)]
+pub struct CostTable {
+    replace: HashMap<(u32, u32), usize>,
+    /// substitutions with more than one element on either side
+    rules: Vec<(Vec<u32>, Vec<u32>, usize)>,
+    insert: HashMap<u32, usize>,
+    delete: HashMap<u32, usize>,
+}
+
+impl CostTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cheap substitutions between neighbouring keys of a QWERTY keyboard.
+    pub fn qwerty(cost: usize) -> Self {
+        let rows = QWERTY_ROWS.map(|row| row.chars().collect::<Vec<_>>());
+        let mut table = Self::new();
+        let mut add = |a: char, b: char| {
+            table.set_symmetric_substitution(&[a as u32], &[b as u32], cost);
+            if a.is_ascii_alphabetic() && b.is_ascii_alphabetic() {
+                let (a, b) = (a.to_ascii_uppercase(), b.to_ascii_uppercase());
+                table.set_symmetric_substitution(&[a as u32], &[b as u32], cost);
+            }
+        };
+
+        for (r, row) in rows.iter().enumerate() {
+            for (i, &key) in row.iter().enumerate() {
+                if let Some(&right) = row.get(i + 1) {
+                    add(key, right);
+                }
+                let Some(below) = rows.get(r + 1) else {
+                    continue;
+                };
+                for neighbour in [i.checked_sub(1), Some(i)].into_iter().flatten() {
+                    if let Some(&neighbour) = below.get(neighbour) {
+                        add(key, neighbour);
+                    }
+                }
+            }
+        }
+        table
+    }
+
+    /// Cheap substitutions between sequences OCR engines commonly confuse,
+    /// such as "0"/"O", "1"/"l" and "rn"/"m".
+    pub fn ocr(cost: usize) -> Self {
+        let mut table = Self::new();
+        for (a, b) in OCR_CONFUSIONS {
+            table.set_symmetric_substitution(&to_seq(a), &to_seq(b), cost);
+        }
+        table
+    }
+
+    /// Sets the cost of replacing `src` with `dest`.
+    pub fn set_substitution(
+        &mut self,
+        src: &[u32],
+        dest: &[u32],
+        cost: usize,
+    ) -> Result<(), CrustyError> {
+        match (src, dest) {
+            ([], _) | (_, []) => return Err(CrustyError::EmptySubstitution),
+            (&[a], &[b]) => {
+                self.replace.insert((a, b), cost);
+            }
+            _ => match self
+                .rules
+                .iter_mut()
+                .find(|(s, d, _)| s == src && d == dest)
+            {
+                Some(rule) => rule.2 = cost,
+                None => self.rules.push((src.to_vec(), dest.to_vec(), cost)),
+            },
+        }
+        Ok(())
+    }
+
+    /// Sets the cost of replacing `a` with `b` and `b` with `a`.
+    fn set_symmetric_substitution(&mut self, a: &[u32], b: &[u32], cost: usize) {
+        self.set_substitution(a, b, cost)
+            .and_then(|_| self.set_substitution(b, a, cost))
+            .expect("presets contain no empty sequences");
+    }
+
+    pub fn set_insertion(&mut self, ch: u32, cost: usize) {
+        self.insert.insert(ch, cost);
+    }
+
+    pub fn set_deletion(&mut self, ch: u32, cost: usize) {
+        self.delete.insert(ch, cost);
+    }
+
+    /// Adds the costs of `other`, overriding costs set in both tables.
+    pub fn merge(&mut self, other: &CostTable) {
+        self.replace.extend(&other.replace);
+        for (src, dest, cost) in &other.rules {
+            self.set_substitution(src, dest, *cost)
+                .expect("rules are never empty");
+        }
+        self.insert.extend(&other.insert);
+        self.delete.extend(&other.delete);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replace.is_empty()
+            && self.rules.is_empty()
+            && self.insert.is_empty()
+            && self.delete.is_empty()
+    }
+
+    /// Whether distances calculated with the table and `weights` are the same
+    /// for both argument orders.
+    pub fn is_symmetric(&self, weights: &Weights) -> bool {
+        let replace_symmetric = self
+            .replace
+            .iter()
+            .all(|(&(a, b), &cost)| self.substitution(b, a).unwrap_or(weights.2) == cost);
+        let rules_symmetric = self.rules.iter().all(|(src, dest, cost)| {
+            self.rules
+                .iter()
+                .any(|(s, d, c)| s == dest && d == src && c == cost)
+        });
+        let indel_symmetric = self
+            .insert
+            .keys()
+            .chain(self.delete.keys())
+            .all(|&ch| self.insert_cost(ch, weights) == self.delete_cost(ch, weights));
+
+        weights.0 == weights.1 && replace_symmetric && rules_symmetric && indel_symmetric
+    }
+
+    /// The cost set for replacing `a` with `b`, if any.
+    pub(super) fn substitution(&self, a: u32, b: u32) -> Option<usize> {
+        self.replace.get(&(a, b)).copied()
+    }
+
+    pub(super) fn replace_cost(&self, a: u32, b: u32, weights: &Weights) -> usize {
+        if a == b {
+            return 0;
+        }
+        self.substitution(a, b).unwrap_or(weights.2)
+    }
+
+    pub(super) fn insert_cost(&self, ch: u32, weights: &Weights) -> usize {
+        self.insert.get(&ch).copied().unwrap_or(weights.0)
+    }
+
+    pub(super) fn delete_cost(&self, ch: u32, weights: &Weights) -> usize {
+        self.delete.get(&ch).copied().unwrap_or(weights.1)
+    }
+
+    pub(super) fn rules(&self) -> &[(Vec<u32>, Vec<u32>, usize)] {
+        &self.rules
+    }
+
+    /// Number of elements of s1 a single operation can consume at most.
+    pub(super) fn max_src_len(&self) -> usize {
+        self.rules
+            .iter()
+            .map(|(src, _, _)| src.len())
+            .max()
+            .unwrap_or(1)
+            .max(1)
+    }
+}
+
+#[cfg(feature = "python")]
+fn py_seq(s: &Bound<'_, PyAny>) -> PyResult<Vec<u32>> {
+    match conv_sequence(s) {
+        Ok(Some(seq)) => Ok(seq),
+        Ok(None) => Err(pyo3::exceptions::PyValueError::new_err(
+            "Cannot handle None",
+        )),
+        Err(e) => Err(pyo3::exceptions::PyTypeError::new_err(format!(
+            "Failed to convert sequences to u32. {}",
+            e
+        ))),
+    }
+}
+
+#[cfg(feature = "python")]
+fn py_element(s: &Bound<'_, PyAny>) -> PyResult<u32> {
+    match py_seq(s)?.as_slice() {
+        &[ch] => Ok(ch),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "expected a single element, got {}",
+            s.repr()?
+        ))),
+    }
+}
+
+#[cfg(feature = "python")]
+impl CostTable {
+    fn py_set_substitution(
+        &mut self,
+        src: &Bound<'_, PyAny>,
+        dest: &Bound<'_, PyAny>,
+        cost: usize,
+    ) -> PyResult<()> {
+        self.set_substitution(&py_seq(src)?, &py_seq(dest)?, cost)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn py_set_indels(
+        &mut self,
+        insertions: Option<&Bound<'_, PyDict>>,
+        deletions: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<()> {
+        for (ch, cost) in insertions.into_iter().flatten() {
+            self.set_insertion(py_element(&ch)?, cost.extract()?);
+        }
+        for (ch, cost) in deletions.into_iter().flatten() {
+            self.set_deletion(py_element(&ch)?, cost.extract()?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl CostTable {
+    /**
+    Creates a cost table from a dict of substitution costs.
+
+    Parameters
+    ----------
+    substitutions : dict[tuple[Sequence[Hashable], Sequence[Hashable]], int], optional
+        Maps a pair (src, dest) to the cost of replacing src with dest. Both
+        sides can contain several elements, e.g. ("rn", "m").
+    insertions : dict[Hashable, int], optional
+        Cost of inserting the given elements.
+    deletions : dict[Hashable, int], optional
+        Cost of deleting the given elements.
+    symmetric : bool, optional
+        Whether the substitutions apply in both directions. Default is True.
+
+    Raises
+    ------
+    ValueError
+        If a side of a substitution is empty.
+    */
+    #[new]
+    #[pyo3(signature = (substitutions=None, *, insertions=None, deletions=None, symmetric=true))]
+    fn py_new(
+        substitutions: Option<&Bound<'_, PyDict>>,
+        insertions: Option<&Bound<'_, PyDict>>,
+        deletions: Option<&Bound<'_, PyDict>>,
+        symmetric: bool,
+    ) -> PyResult<Self> {
+        let mut table = Self::new();
+        for (pair, cost) in substitutions.into_iter().flatten() {
+            let (src, dest): (Bound<'_, PyAny>, Bound<'_, PyAny>) = pair.extract()?;
+            let cost = cost.extract()?;
+            table.py_set_substitution(&src, &dest, cost)?;
+            if symmetric {
+                table.py_set_substitution(&dest, &src, cost)?;
+            }
+        }
+        table.py_set_indels(insertions, deletions)?;
+        Ok(table)
+    }
+
+    /**
+    Creates a cost table from a dense substitution matrix.
+
+    Parameters
+    ----------
+    alphabet : Sequence[Hashable]
+        Elements the rows and columns of the matrix belong to.
+    matrix : Sequence[Sequence[int]]
+        matrix[i][j] is the cost of replacing alphabet[i] with alphabet[j].
+        The diagonal is ignored.
+    insertions : dict[Hashable, int], optional
+        Cost of inserting the given elements.
+    deletions : dict[Hashable, int], optional
+        Cost of deleting the given elements.
+
+    Raises
+    ------
+    ValueError
+        If the matrix does not have a row and a column per element.
+    */
+    #[staticmethod]
+    #[pyo3(signature = (alphabet, matrix, *, insertions=None, deletions=None))]
+    fn from_matrix(
+        alphabet: &Bound<'_, PyAny>,
+        matrix: Vec<Vec<usize>>,
+        insertions: Option<&Bound<'_, PyDict>>,
+        deletions: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let alphabet = py_seq(alphabet)?;
+        if matrix.len() != alphabet.len() || matrix.iter().any(|row| row.len() != alphabet.len()) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "expected a {0}x{0} matrix",
+                alphabet.len()
+            )));
+        }
+
+        let mut table = Self::new();
+        for (&a, row) in alphabet.iter().zip(&matrix) {
+            for (&b, &cost) in alphabet.iter().zip(row) {
+                if a != b {
+                    table.replace.insert((a, b), cost);
+                }
+            }
+        }
+        table.py_set_indels(insertions, deletions)?;
+        Ok(table)
+    }
+
+    /**
+    Cost table with cheap substitutions between neighbouring keys of a US
+    QWERTY keyboard.
+
+    Parameters
+    ----------
+    cost : int, optional
+        Cost of replacing a key with one of its neighbours. Default is 1.
+    */
+    #[staticmethod]
+    #[pyo3(name = "qwerty", signature = (cost=1))]
+    fn py_qwerty(cost: usize) -> Self {
+        Self::qwerty(cost)
+    }
+
+    /**
+    Cost table with cheap substitutions between sequences OCR engines
+    commonly confuse, such as "0"/"O", "1"/"l" and "rn"/"m".
+
+    Parameters
+    ----------
+    cost : int, optional
+        Cost of replacing a sequence with one it is confused with.
+        Default is 1.
+    */
+    #[staticmethod]
+    #[pyo3(name = "ocr", signature = (cost=1))]
+    fn py_ocr(cost: usize) -> Self {
+        Self::ocr(cost)
+    }
+
+    /**
+    Returns a new cost table with the costs of both tables. Costs of other
+    take precedence.
+    */
+    #[pyo3(name = "merge")]
+    fn py_merge(&self, other: PyRef<'_, CostTable>) -> Self {
+        let mut table = self.clone();
+        table.merge(&other);
+        table
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CostTable(substitutions={}, insertions={}, deletions={})",
+            self.replace.len() + self.rules.len(),
+            self.insert.len(),
+            self.delete.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qwerty() {
+        let table = CostTable::qwerty(1);
+        let w = Weights(1, 1, 3);
+        let cost = |a: char, b: char| table.replace_cost(a as u32, b as u32, &w);
+        assert_eq!(cost('q', 'w'), 1);
+        assert_eq!(cost('w', 'a'), 1);
+        assert_eq!(cost('w', 's'), 1);
+        assert_eq!(cost('s', 'w'), 1);
+        assert_eq!(cost('G', 'H'), 1);
+        assert_eq!(cost('q', 's'), 3);
+        assert_eq!(cost('a', 'l'), 3);
+        assert!(table.is_symmetric(&w));
+    }
+
+    #[test]
+    fn test_ocr() {
+        let table = CostTable::ocr(1);
+        let w = Weights(2, 2, 2);
+        assert_eq!(table.replace_cost('0' as u32, 'O' as u32, &w), 1);
+        assert_eq!(table.replace_cost('O' as u32, '0' as u32, &w), 1);
+        assert_eq!(table.max_src_len(), 2);
+        assert!(table.is_symmetric(&w));
+    }
+
+    #[test]
+    fn test_set_substitution() {
+        let mut table = CostTable::new();
+        assert!(table.set_substitution(&[], &[1], 1).is_err());
+        table.set_substitution(&[1, 2], &[3], 4).unwrap();
+        table.set_substitution(&[1, 2], &[3], 2).unwrap();
+        assert_eq!(table.rules(), &[(vec![1, 2], vec![3], 2)]);
+        assert!(!table.is_symmetric(&Weights(1, 1, 1)));
+
+        table.set_substitution(&[3], &[1, 2], 2).unwrap();
+        assert!(table.is_symmetric(&Weights(1, 1, 1)));
+        table.set_insertion(5, 3);
+        assert!(!table.is_symmetric(&Weights(1, 1, 1)));
+        table.set_deletion(5, 3);
+        assert!(table.is_symmetric(&Weights(1, 1, 1)));
+        assert!(!table.is_symmetric(&Weights(1, 2, 1)));
+    }
+}
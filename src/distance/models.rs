@@ -1,7 +1,56 @@
+use crate::common::error::CrustyError;
+#[cfg(feature = "python")]
 use crate::common::models::{IndexResult, StrOrInt};
-use pyo3::exceptions::{PyIndexError, PyValueError};
+#[cfg(feature = "python")]
+use pyo3::exceptions::{PyIndexError, PyKeyError, PyValueError};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
-use pyo3::types::PyType;
+#[cfg(feature = "python")]
+use pyo3::types::{PyDict, PyList, PyType};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "python")]
+fn dict_item<'py, T: FromPyObject<'py>>(dict: &Bound<'py, PyDict>, key: &str) -> PyResult<T> {
+    dict.get_item(key)?
+        .ok_or_else(|| PyKeyError::new_err(key.to_string()))?
+        .extract()
+}
+
+#[cfg(feature = "python")]
+fn invalid_edit_script(err: CrustyError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+fn invalid_tag(tag: &str) -> CrustyError {
+    CrustyError::InvalidEditScript(format!("unknown tag '{tag}'"))
+}
+
+/// Number of source and destination elements consumed by an Editop.
+fn editop_steps(tag: &str) -> Result<(usize, usize), CrustyError> {
+    match tag {
+        "insert" => Ok((0, 1)),
+        "delete" => Ok((1, 0)),
+        "replace" => Ok((1, 1)),
+        _ => Err(invalid_tag(tag)),
+    }
+}
+
+#[cfg(feature = "python")]
+fn json_dumps(dict: &Bound<'_, PyDict>) -> PyResult<String> {
+    dict.py()
+        .import_bound("json")?
+        .call_method1("dumps", (dict,))?
+        .extract()
+}
+
+#[cfg(feature = "python")]
+fn json_loads<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, PyDict>> {
+    Ok(py
+        .import_bound("json")?
+        .call_method1("loads", (json,))?
+        .downcast_into::<PyDict>()?)
+}
 
 /**
 Tuple like object describing the position of the compared strings in
@@ -10,8 +59,12 @@ src and dest.
 It indicates that the score has been calculated between
 src[src_start:src_end] and dest[dest_start:dest_end]
 */
-#[pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")]
+#[cfg_attr(
+    feature = "python",
+    pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")
+)]
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ScoreAlignment {
     pub score: f64,
     pub src_start: usize,
@@ -30,13 +83,16 @@ impl std::fmt::Display for ScoreAlignment {
     }
 }
 
+#[cfg(feature = "python")]
 #[pyclass]
 struct AlignmentIter {
     inner: std::vec::IntoIter<IndexResult>,
 }
 
+#[cfg(feature = "python")]
 #[pymethods]
 impl AlignmentIter {
+    #[cfg(feature = "python")]
     fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
         slf
     }
@@ -46,8 +102,9 @@ impl AlignmentIter {
     }
 }
 
-#[pymethods]
+#[cfg_attr(feature = "python", pymethods)]
 impl ScoreAlignment {
+    #[cfg(feature = "python")]
     #[new]
     fn py_new(
         score: f64,
@@ -65,18 +122,68 @@ impl ScoreAlignment {
         }
     }
 
+    /**
+    Convert to a dict with the ``score`` and the source and destination range.
+    */
+    #[cfg(feature = "python")]
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("score", self.score)?;
+        dict.set_item("src_start", self.src_start)?;
+        dict.set_item("src_end", self.src_end)?;
+        dict.set_item("dest_start", self.dest_start)?;
+        dict.set_item("dest_end", self.dest_end)?;
+        Ok(dict)
+    }
+
+    /**
+    Create a ScoreAlignment from a dict as returned by ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_dict(_cls: &Bound<'_, PyType>, dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        Ok(ScoreAlignment {
+            score: dict_item(dict, "score")?,
+            src_start: dict_item(dict, "src_start")?,
+            src_end: dict_item(dict, "src_end")?,
+            dest_start: dict_item(dict, "dest_start")?,
+            dest_end: dict_item(dict, "dest_end")?,
+        })
+    }
+
+    /**
+    Serialize to a JSON string, see ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    fn to_json(&self, py: Python<'_>) -> PyResult<String> {
+        json_dumps(&self.to_dict(py)?)
+    }
+
+    /**
+    Create a ScoreAlignment from a JSON string as returned by ``to_json``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_json(cls: &Bound<'_, PyType>, json: &str) -> PyResult<Self> {
+        Self::from_dict(cls, &json_loads(cls.py(), json)?)
+    }
+
+    #[cfg(feature = "python")]
     fn __str__(&self) -> String {
         self.to_string()
     }
 
+    #[cfg(feature = "python")]
     fn __repr__(&self) -> String {
         self.to_string()
     }
 
+    #[cfg(feature = "python")]
     fn __len__(&self) -> usize {
         5
     }
 
+    #[cfg(feature = "python")]
     fn __getitem__(&self, idx: isize) -> PyResult<IndexResult> {
         let idx = if idx < 0 { 5 + idx } else { idx };
 
@@ -90,6 +197,7 @@ impl ScoreAlignment {
         }
     }
 
+    #[cfg(feature = "python")]
     fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<AlignmentIter>> {
         let iter = AlignmentIter {
             inner: vec![
@@ -105,8 +213,12 @@ impl ScoreAlignment {
     }
 }
 
-#[pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")]
+#[cfg_attr(
+    feature = "python",
+    pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")
+)]
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MatchingBlock {
     pub a: usize,
     pub b: usize,
@@ -124,25 +236,72 @@ impl std::fmt::Display for MatchingBlock {
 }
 
 // Triple describing matching subsequences.
-#[pymethods]
+#[cfg_attr(feature = "python", pymethods)]
 impl MatchingBlock {
+    #[cfg(feature = "python")]
     #[new]
     fn py_new(a: usize, b: usize, size: usize) -> Self {
         MatchingBlock { a, b, size }
     }
 
+    /**
+    Convert to a dict with the ``a``, ``b`` and ``size`` of the block.
+    */
+    #[cfg(feature = "python")]
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("a", self.a)?;
+        dict.set_item("b", self.b)?;
+        dict.set_item("size", self.size)?;
+        Ok(dict)
+    }
+
+    /**
+    Create a MatchingBlock from a dict as returned by ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_dict(_cls: &Bound<'_, PyType>, dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        Ok(MatchingBlock {
+            a: dict_item(dict, "a")?,
+            b: dict_item(dict, "b")?,
+            size: dict_item(dict, "size")?,
+        })
+    }
+
+    /**
+    Serialize to a JSON string, see ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    fn to_json(&self, py: Python<'_>) -> PyResult<String> {
+        json_dumps(&self.to_dict(py)?)
+    }
+
+    /**
+    Create a MatchingBlock from a JSON string as returned by ``to_json``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_json(cls: &Bound<'_, PyType>, json: &str) -> PyResult<Self> {
+        Self::from_dict(cls, &json_loads(cls.py(), json)?)
+    }
+
+    #[cfg(feature = "python")]
     fn __str__(&self) -> String {
         self.to_string()
     }
 
+    #[cfg(feature = "python")]
     fn __repr__(&self) -> String {
         self.to_string()
     }
 
+    #[cfg(feature = "python")]
     fn __len__(&self) -> usize {
         3
     }
 
+    #[cfg(feature = "python")]
     fn __getitem__(&self, idx: isize) -> PyResult<IndexResult> {
         let idx = if idx < 0 { 3 + idx } else { idx };
         match idx {
@@ -153,18 +312,30 @@ impl MatchingBlock {
         }
     }
 
-    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
-        slf
+    #[cfg(feature = "python")]
+    fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<AlignmentIter>> {
+        let iter = AlignmentIter {
+            inner: vec![
+                IndexResult::Integer(slf.a),
+                IndexResult::Integer(slf.b),
+                IndexResult::Integer(slf.size),
+            ]
+            .into_iter(),
+        };
+        Py::new(slf.py(), iter)
     }
 }
 
+#[cfg(feature = "python")]
 #[pyclass]
 struct EditopIter {
     inner: std::vec::IntoIter<StrOrInt>,
 }
 
+#[cfg(feature = "python")]
 #[pymethods]
 impl EditopIter {
+    #[cfg(feature = "python")]
     fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
         slf
     }
@@ -190,14 +361,41 @@ The tags are strings, with these meanings:
 | 'insert'  | dest[dest_pos] should be inserted at src[src_pos] |
 +-----------+---------------------------------------------------+
 */
-#[pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")]
+#[cfg_attr(
+    feature = "python",
+    pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")
+)]
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawEditop"))]
 pub struct Editop {
     pub tag: String,
     pub src_pos: usize,
     pub dest_pos: usize,
 }
 
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RawEditop {
+    tag: String,
+    src_pos: usize,
+    dest_pos: usize,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawEditop> for Editop {
+    type Error = CrustyError;
+
+    fn try_from(raw: RawEditop) -> Result<Self, Self::Error> {
+        editop_steps(&raw.tag)?;
+        Ok(Editop {
+            tag: raw.tag,
+            src_pos: raw.src_pos,
+            dest_pos: raw.dest_pos,
+        })
+    }
+}
+
 impl std::fmt::Display for Editop {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
@@ -208,8 +406,9 @@ impl std::fmt::Display for Editop {
     }
 }
 
-#[pymethods]
+#[cfg_attr(feature = "python", pymethods)]
 impl Editop {
+    #[cfg(feature = "python")]
     #[new]
     fn py_new(tag: String, src_pos: usize, dest_pos: usize) -> Self {
         Editop {
@@ -219,18 +418,66 @@ impl Editop {
         }
     }
 
+    /**
+    Convert to a dict with the ``tag`` and the source and destination position.
+    */
+    #[cfg(feature = "python")]
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("tag", &self.tag)?;
+        dict.set_item("src_pos", self.src_pos)?;
+        dict.set_item("dest_pos", self.dest_pos)?;
+        Ok(dict)
+    }
+
+    /**
+    Create an Editop from a dict as returned by ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_dict(_cls: &Bound<'_, PyType>, dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let tag: String = dict_item(dict, "tag")?;
+        editop_steps(&tag).map_err(invalid_edit_script)?;
+        Ok(Editop {
+            tag,
+            src_pos: dict_item(dict, "src_pos")?,
+            dest_pos: dict_item(dict, "dest_pos")?,
+        })
+    }
+
+    /**
+    Serialize to a JSON string, see ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    fn to_json(&self, py: Python<'_>) -> PyResult<String> {
+        json_dumps(&self.to_dict(py)?)
+    }
+
+    /**
+    Create an Editop from a JSON string as returned by ``to_json``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_json(cls: &Bound<'_, PyType>, json: &str) -> PyResult<Self> {
+        Self::from_dict(cls, &json_loads(cls.py(), json)?)
+    }
+
+    #[cfg(feature = "python")]
     fn __str__(&self) -> String {
         self.to_string()
     }
 
+    #[cfg(feature = "python")]
     fn __repr__(&self) -> String {
         self.to_string()
     }
 
+    #[cfg(feature = "python")]
     fn __len__(&self) -> usize {
         3
     }
 
+    #[cfg(feature = "python")]
     fn __getitem__(&self, idx: isize) -> PyResult<IndexResult> {
         let idx = if idx < 0 { 3 + idx } else { idx };
         match idx {
@@ -241,6 +488,7 @@ impl Editop {
         }
     }
 
+    #[cfg(feature = "python")]
     fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<EditopIter>> {
         let iter = EditopIter {
             inner: vec![
@@ -255,13 +503,16 @@ impl Editop {
     }
 }
 
+#[cfg(feature = "python")]
 #[pyclass]
 struct EditopsIter {
     inner: std::vec::IntoIter<Editop>,
 }
 
+#[cfg(feature = "python")]
 #[pymethods]
 impl EditopsIter {
+    #[cfg(feature = "python")]
     fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
         slf
     }
@@ -272,14 +523,38 @@ impl EditopsIter {
 }
 
 // List like object of Editops describing how to turn s1 into s2.
-#[pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")]
+#[cfg_attr(
+    feature = "python",
+    pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")
+)]
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawEditops"))]
 pub struct Editops {
     src_len: usize,
     dest_len: usize,
     editops: Vec<Editop>,
 }
 
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RawEditops {
+    src_len: usize,
+    dest_len: usize,
+    editops: Vec<Editop>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawEditops> for Editops {
+    type Error = CrustyError;
+
+    fn try_from(raw: RawEditops) -> Result<Self, Self::Error> {
+        let editops = Editops::new(raw.src_len, raw.dest_len, raw.editops);
+        editops.validate()?;
+        Ok(editops)
+    }
+}
+
 impl std::fmt::Display for Editops {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let editops_str = self
@@ -326,10 +601,50 @@ impl Editops {
     pub fn len(&self) -> usize {
         self.editops.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.editops.is_empty()
+    }
+
+    /// Checks that the edit operations are ordered and turn a sequence of
+    /// `src_len` elements into one of `dest_len` elements, so they can be
+    /// applied safely.
+    pub fn validate(&self) -> Result<(), CrustyError> {
+        let (mut src_pos, mut dest_pos) = (0, 0);
+        for op in &self.editops {
+            let (src_step, dest_step) = editop_steps(&op.tag)?;
+            if op.src_pos + src_step > self.src_len || op.dest_pos + dest_step > self.dest_len {
+                return Err(CrustyError::InvalidEditScript(format!(
+                    "{op} is out of bounds for src_len={} and dest_len={}",
+                    self.src_len, self.dest_len
+                )));
+            }
+
+            // elements between two operations are kept, so the same number
+            // has to be skipped in both sequences
+            let skipped = op.src_pos.checked_sub(src_pos);
+            if skipped.is_none() || op.dest_pos.checked_sub(dest_pos) != skipped {
+                return Err(CrustyError::InvalidEditScript(format!(
+                    "{op} is out of order"
+                )));
+            }
+            src_pos = op.src_pos + src_step;
+            dest_pos = op.dest_pos + dest_step;
+        }
+
+        if self.src_len - src_pos != self.dest_len - dest_pos {
+            return Err(CrustyError::InvalidEditScript(format!(
+                "the edit operations do not turn src_len={} into dest_len={}",
+                self.src_len, self.dest_len
+            )));
+        }
+        Ok(())
+    }
 }
 
-#[pymethods]
+#[cfg_attr(feature = "python", pymethods)]
 impl Editops {
+    #[cfg(feature = "python")]
     #[new]
     fn py_new(src_len: usize, dest_len: usize, editops: Vec<Editop>) -> Self {
         Editops {
@@ -339,15 +654,75 @@ impl Editops {
         }
     }
 
+    /**
+    Convert to a dict, which can be stored as JSON and replayed later
+    using ``from_dict`` and ``apply``.
+
+    The edit operations are stored as a list of dicts in the ``editops`` entry.
+    */
+    #[cfg(feature = "python")]
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let editops = self
+            .editops
+            .iter()
+            .map(|op| op.to_dict(py))
+            .collect::<PyResult<Vec<_>>>()?;
+        let dict = PyDict::new_bound(py);
+        dict.set_item("src_len", self.src_len)?;
+        dict.set_item("dest_len", self.dest_len)?;
+        dict.set_item("editops", PyList::new_bound(py, editops))?;
+        Ok(dict)
+    }
+
+    /**
+    Create Editops from a dict as returned by ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_dict(cls: &Bound<'_, PyType>, dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let editop_cls = cls.py().get_type_bound::<Editop>();
+        let editops = dict_item::<Vec<Bound<'_, PyDict>>>(dict, "editops")?
+            .iter()
+            .map(|op| Editop::from_dict(&editop_cls, op))
+            .collect::<PyResult<Vec<_>>>()?;
+        let editops = Editops::new(
+            dict_item(dict, "src_len")?,
+            dict_item(dict, "dest_len")?,
+            editops,
+        );
+        editops.validate().map_err(invalid_edit_script)?;
+        Ok(editops)
+    }
+
+    /**
+    Serialize to a JSON string, see ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    fn to_json(&self, py: Python<'_>) -> PyResult<String> {
+        json_dumps(&self.to_dict(py)?)
+    }
+
+    /**
+    Create Editops from a JSON string as returned by ``to_json``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_json(cls: &Bound<'_, PyType>, json: &str) -> PyResult<Self> {
+        Self::from_dict(cls, &json_loads(cls.py(), json)?)
+    }
+
+    #[cfg(feature = "python")]
     fn __len__(&self) -> usize {
         self.len()
     }
 
+    #[cfg(feature = "python")]
     fn __delitem__(&mut self, index: usize) {
         // TODO: make this work with slices
         self.editops.remove(index);
     }
 
+    #[cfg(feature = "python")]
     fn __getitem__(&self, index: usize) -> PyResult<Editop> {
         // TODO: make this work with slices
         if index >= self.len() {
@@ -356,6 +731,7 @@ impl Editops {
         Ok(self.editops[index].clone())
     }
 
+    #[cfg(feature = "python")]
     fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<EditopsIter>> {
         let iter = EditopsIter {
             inner: slf.editops.clone().into_iter(),
@@ -364,10 +740,12 @@ impl Editops {
         Py::new(slf.py(), iter)
     }
 
+    #[cfg(feature = "python")]
     fn __str__(&self) -> String {
         self.to_string()
     }
 
+    #[cfg(feature = "python")]
     fn __repr__(&self) -> String {
         self.to_string()
     }
@@ -385,6 +763,7 @@ impl Editops {
     editops : Editops
         Opcodes converted to Editops
     */
+    #[cfg(feature = "python")]
     #[classmethod]
     fn from_opcodes(_cls: &Bound<'_, PyType>, opcodes: Opcodes) -> Editops {
         opcodes.as_editops()
@@ -477,16 +856,8 @@ impl Editops {
             if src_pos < op.src_pos || dest_pos < op.dest_pos {
                 dbg!(op, src_pos, dest_pos);
                 let length = usize::min(
-                    if src_pos < op.src_pos {
-                        op.src_pos - src_pos
-                    } else {
-                        0
-                    },
-                    if dest_pos < op.dest_pos {
-                        op.dest_pos - dest_pos
-                    } else {
-                        0
-                    },
+                    op.src_pos.saturating_sub(src_pos),
+                    op.dest_pos.saturating_sub(dest_pos),
                 );
                 if length > 0 {
                     blocks.push(MatchingBlock {
@@ -541,16 +912,16 @@ impl Editops {
 
     This is the equivalent of ``[x for x in editops]``
     */
-    fn as_list(&self) -> Vec<Editop> {
+    pub fn as_list(&self) -> Vec<Editop> {
         self.editops.clone()
     }
 
     // Copy the Editops.
-    fn copy(&self) -> Self {
+    pub fn copy(&self) -> Self {
         self.clone()
     }
 
-    fn inverse(&self) -> Self {
+    pub fn inverse(&self) -> Self {
         let mut blocks = vec![];
         for op in self {
             let tag = match op.tag.as_str() {
@@ -576,6 +947,7 @@ impl Editops {
     }
 
     // Remove a subsequence from the editops.
+    #[cfg(feature = "python")]
     fn remove_subsequence(&self, subsequence: Editops) -> PyResult<Editops> {
         let mut result = self.editops.clone();
 
@@ -626,7 +998,7 @@ impl Editops {
         })
     }
 
-    fn apply(&self, source_string: &str, destination_string: &str) -> String {
+    pub fn apply(&self, source_string: &str, destination_string: &str) -> String {
         let mut res_str = String::new();
         let mut src_pos = 0;
 
@@ -715,8 +1087,13 @@ Note
 Opcode is compatible with the tuples returned by difflib's SequenceMatcher to make them
 interoperable
 */
-#[pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")]
+#[cfg_attr(
+    feature = "python",
+    pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")
+)]
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawOpcode"))]
 pub struct Opcode {
     pub tag: String,
     pub src_start: usize,
@@ -725,6 +1102,58 @@ pub struct Opcode {
     pub dest_end: usize,
 }
 
+/// Checks the tag of an Opcode and that its ranges fit the tag.
+fn validate_opcode(op: &Opcode) -> Result<(), CrustyError> {
+    let src_len = op.src_end.checked_sub(op.src_start);
+    let dest_len = op.dest_end.checked_sub(op.dest_start);
+    let (Some(src_len), Some(dest_len)) = (src_len, dest_len) else {
+        return Err(CrustyError::InvalidEditScript(format!(
+            "{op} ends before it starts"
+        )));
+    };
+
+    let valid = match op.tag.as_str() {
+        "equal" => src_len == dest_len,
+        "replace" => src_len > 0 && dest_len > 0,
+        "insert" => src_len == 0 && dest_len > 0,
+        "delete" => src_len > 0 && dest_len == 0,
+        tag => return Err(invalid_tag(tag)),
+    };
+    if !valid {
+        return Err(CrustyError::InvalidEditScript(format!(
+            "{op} has ranges that do not fit its tag"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RawOpcode {
+    tag: String,
+    src_start: usize,
+    src_end: usize,
+    dest_start: usize,
+    dest_end: usize,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawOpcode> for Opcode {
+    type Error = CrustyError;
+
+    fn try_from(raw: RawOpcode) -> Result<Self, Self::Error> {
+        let op = Opcode {
+            tag: raw.tag,
+            src_start: raw.src_start,
+            src_end: raw.src_end,
+            dest_start: raw.dest_start,
+            dest_end: raw.dest_end,
+        };
+        validate_opcode(&op)?;
+        Ok(op)
+    }
+}
+
 impl std::fmt::Display for Opcode {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
@@ -735,8 +1164,9 @@ impl std::fmt::Display for Opcode {
     }
 }
 
-#[pymethods]
+#[cfg_attr(feature = "python", pymethods)]
 impl Opcode {
+    #[cfg(feature = "python")]
     #[new]
     fn py_new(
         tag: String,
@@ -754,10 +1184,60 @@ impl Opcode {
         }
     }
 
+    /**
+    Convert to a dict with the ``tag`` and the source and destination range.
+    */
+    #[cfg(feature = "python")]
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("tag", &self.tag)?;
+        dict.set_item("src_start", self.src_start)?;
+        dict.set_item("src_end", self.src_end)?;
+        dict.set_item("dest_start", self.dest_start)?;
+        dict.set_item("dest_end", self.dest_end)?;
+        Ok(dict)
+    }
+
+    /**
+    Create an Opcode from a dict as returned by ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_dict(_cls: &Bound<'_, PyType>, dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let op = Opcode {
+            tag: dict_item(dict, "tag")?,
+            src_start: dict_item(dict, "src_start")?,
+            src_end: dict_item(dict, "src_end")?,
+            dest_start: dict_item(dict, "dest_start")?,
+            dest_end: dict_item(dict, "dest_end")?,
+        };
+        validate_opcode(&op).map_err(invalid_edit_script)?;
+        Ok(op)
+    }
+
+    /**
+    Serialize to a JSON string, see ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    fn to_json(&self, py: Python<'_>) -> PyResult<String> {
+        json_dumps(&self.to_dict(py)?)
+    }
+
+    /**
+    Create an Opcode from a JSON string as returned by ``to_json``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_json(cls: &Bound<'_, PyType>, json: &str) -> PyResult<Self> {
+        Self::from_dict(cls, &json_loads(cls.py(), json)?)
+    }
+
+    #[cfg(feature = "python")]
     fn __len__(&self) -> usize {
         5
     }
 
+    #[cfg(feature = "python")]
     fn __getitem__(slf: PyRef<'_, Self>, idx: isize) -> PyResult<IndexResult> {
         let idx = if idx < 0 { 5 + idx } else { idx };
         match idx {
@@ -770,13 +1250,44 @@ impl Opcode {
         }
     }
 
+    #[cfg(feature = "python")]
     fn __repr__(&self) -> String {
         self.to_string()
     }
 
+    #[cfg(feature = "python")]
+    fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<AlignmentIter>> {
+        let iter = AlignmentIter {
+            inner: vec![
+                IndexResult::String(slf.tag.clone()),
+                IndexResult::Integer(slf.src_start),
+                IndexResult::Integer(slf.src_end),
+                IndexResult::Integer(slf.dest_start),
+                IndexResult::Integer(slf.dest_end),
+            ]
+            .into_iter(),
+        };
+        Py::new(slf.py(), iter)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pyclass]
+struct OpcodesIter {
+    inner: std::vec::IntoIter<Opcode>,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl OpcodesIter {
+    #[cfg(feature = "python")]
     fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
         slf
     }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>) -> Option<Opcode> {
+        slf.inner.next()
+    }
 }
 
 /**
@@ -785,14 +1296,38 @@ The first Opcode has src_start == dest_start == 0, and remaining tuples
 have src_start == the src_end from the tuple preceding it,
 and likewise for dest_start == the previous dest_end.
 */
-#[pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")]
+#[cfg_attr(
+    feature = "python",
+    pyclass(eq, mapping, get_all, module = "crustyfuzz.distance")
+)]
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawOpcodes"))]
 pub struct Opcodes {
     src_len: usize,
     dest_len: usize,
     opcodes: Vec<Opcode>,
 }
 
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RawOpcodes {
+    src_len: usize,
+    dest_len: usize,
+    opcodes: Vec<Opcode>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawOpcodes> for Opcodes {
+    type Error = CrustyError;
+
+    fn try_from(raw: RawOpcodes) -> Result<Self, Self::Error> {
+        let opcodes = Opcodes::new(raw.src_len, raw.dest_len, raw.opcodes);
+        opcodes.validate()?;
+        Ok(opcodes)
+    }
+}
+
 impl std::fmt::Display for Opcodes {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
@@ -819,8 +1354,44 @@ impl<'a> IntoIterator for &'a Opcodes {
     }
 }
 
-#[pymethods]
 impl Opcodes {
+    pub fn new(src_len: usize, dest_len: usize, opcodes: Vec<Opcode>) -> Self {
+        Opcodes {
+            src_len,
+            dest_len,
+            opcodes,
+        }
+    }
+
+    /// Checks that the opcodes are contiguous and cover a sequence of
+    /// `src_len` and one of `dest_len` elements, so they can be applied
+    /// safely.
+    pub fn validate(&self) -> Result<(), CrustyError> {
+        let (mut src_pos, mut dest_pos) = (0, 0);
+        for op in &self.opcodes {
+            validate_opcode(op)?;
+            if op.src_start != src_pos || op.dest_start != dest_pos {
+                return Err(CrustyError::InvalidEditScript(format!(
+                    "{op} does not start where the previous opcode ends"
+                )));
+            }
+            src_pos = op.src_end;
+            dest_pos = op.dest_end;
+        }
+
+        if src_pos != self.src_len || dest_pos != self.dest_len {
+            return Err(CrustyError::InvalidEditScript(format!(
+                "the opcodes do not cover src_len={} and dest_len={}",
+                self.src_len, self.dest_len
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(feature = "python", pymethods)]
+impl Opcodes {
+    #[cfg(feature = "python")]
     #[new]
     fn py_new(src_len: usize, dest_len: usize, opcodes: Vec<Opcode>) -> Self {
         Opcodes {
@@ -830,10 +1401,69 @@ impl Opcodes {
         }
     }
 
+    /**
+    Convert to a dict, which can be stored as JSON and replayed later
+    using ``from_dict`` and ``apply``.
+
+    The opcodes are stored as a list of dicts in the ``opcodes`` entry.
+    */
+    #[cfg(feature = "python")]
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let opcodes = self
+            .opcodes
+            .iter()
+            .map(|op| op.to_dict(py))
+            .collect::<PyResult<Vec<_>>>()?;
+        let dict = PyDict::new_bound(py);
+        dict.set_item("src_len", self.src_len)?;
+        dict.set_item("dest_len", self.dest_len)?;
+        dict.set_item("opcodes", PyList::new_bound(py, opcodes))?;
+        Ok(dict)
+    }
+
+    /**
+    Create Opcodes from a dict as returned by ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_dict(cls: &Bound<'_, PyType>, dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let opcode_cls = cls.py().get_type_bound::<Opcode>();
+        let opcodes = dict_item::<Vec<Bound<'_, PyDict>>>(dict, "opcodes")?
+            .iter()
+            .map(|op| Opcode::from_dict(&opcode_cls, op))
+            .collect::<PyResult<Vec<_>>>()?;
+        let opcodes = Opcodes::new(
+            dict_item(dict, "src_len")?,
+            dict_item(dict, "dest_len")?,
+            opcodes,
+        );
+        opcodes.validate().map_err(invalid_edit_script)?;
+        Ok(opcodes)
+    }
+
+    /**
+    Serialize to a JSON string, see ``to_dict``.
+    */
+    #[cfg(feature = "python")]
+    fn to_json(&self, py: Python<'_>) -> PyResult<String> {
+        json_dumps(&self.to_dict(py)?)
+    }
+
+    /**
+    Create Opcodes from a JSON string as returned by ``to_json``.
+    */
+    #[cfg(feature = "python")]
+    #[classmethod]
+    fn from_json(cls: &Bound<'_, PyType>, json: &str) -> PyResult<Self> {
+        Self::from_dict(cls, &json_loads(cls.py(), json)?)
+    }
+
+    #[cfg(feature = "python")]
     fn __len__(&self) -> usize {
         self.opcodes.len()
     }
 
+    #[cfg(feature = "python")]
     fn __getitem__(&self, index: usize) -> PyResult<Opcode> {
         if index >= self.opcodes.len() {
             return Err(PyIndexError::new_err("Opcode index out of range"));
@@ -841,20 +1471,27 @@ impl Opcodes {
         Ok(self.opcodes[index].clone())
     }
 
-    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
-        slf
+    #[cfg(feature = "python")]
+    fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<OpcodesIter>> {
+        let iter = OpcodesIter {
+            inner: slf.opcodes.clone().into_iter(),
+        };
+
+        Py::new(slf.py(), iter)
     }
 
+    #[cfg(feature = "python")]
     fn __repr__(&self) -> String {
         self.to_string()
     }
 
+    #[cfg(feature = "python")]
     #[classmethod]
     fn from_editops(_cls: &Bound<'_, PyType>, editops: Editops) -> Opcodes {
         editops.as_opcodes()
     }
 
-    fn as_editops(&self) -> Editops {
+    pub fn as_editops(&self) -> Editops {
         let mut blocks = vec![];
         for op in self {
             match op.tag.as_str() {
@@ -922,11 +1559,11 @@ impl Opcodes {
         blocks
     }
 
-    fn as_list(&self) -> Vec<Opcode> {
+    pub fn as_list(&self) -> Vec<Opcode> {
         self.opcodes.clone()
     }
 
-    fn copy(&self) -> Self {
+    pub fn copy(&self) -> Self {
         self.clone()
     }
 
@@ -954,7 +1591,7 @@ impl Opcodes {
         Opcode(tag=replace, src_start=2, src_end=3, dest_start=3, dest_end=4),
         Opcode(tag=delete, src_start=3, src_end=4, dest_start=4, dest_end=4)]
     */
-    fn inverse(&self) -> Opcodes {
+    pub fn inverse(&self) -> Opcodes {
         let mut blocks = vec![];
         for op in self {
             let tag = match op.tag.as_str() {
@@ -995,7 +1632,7 @@ impl Opcodes {
     mod_string : str
         modified source_string
     */
-    fn apply(&self, source_string: &str, destination_string: &str) -> String {
+    pub fn apply(&self, source_string: &str, destination_string: &str) -> String {
         let mut res_str = String::new();
 
         for op in self.opcodes.iter() {
@@ -1025,3 +1662,187 @@ impl Opcodes {
         res_str
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::to_seq;
+    use crate::distance::{gotoh, hamming, lcs_seq, smith_waterman};
+
+    fn editop(tag: &str, src_pos: usize, dest_pos: usize) -> Editop {
+        Editop {
+            tag: tag.to_string(),
+            src_pos,
+            dest_pos,
+        }
+    }
+
+    fn opcode(tag: &str, src: (usize, usize), dest: (usize, usize)) -> Opcode {
+        Opcode {
+            tag: tag.to_string(),
+            src_start: src.0,
+            src_end: src.1,
+            dest_start: dest.0,
+            dest_end: dest.1,
+        }
+    }
+
+    #[test]
+    fn test_generated_edit_scripts_are_valid() {
+        let pairs = [
+            ("", ""),
+            ("", "abc"),
+            ("abc", ""),
+            ("kitten", "sitting"),
+            ("lewenstein", "levenshtein"),
+            ("sitting", "kitten"),
+            ("aaaa", "aaaa"),
+            ("abcdef", "fedcba"),
+        ];
+        for (s1, s2) in pairs {
+            let (s1, s2) = (to_seq(s1), to_seq(s2));
+            let scripts = [
+                lcs_seq::editops(&s1, &s2),
+                gotoh::editops(&s1, &s2, gotoh::Weights(3, 1, 2)),
+                smith_waterman::editops(&s1, &s2, smith_waterman::Weights::default()),
+                hamming::editops(&s1, &s2, true).unwrap(),
+                #[cfg(feature = "levenshtein")]
+                crate::distance::levenshtein::editops(&s1, &s2),
+            ];
+            for ops in scripts {
+                assert!(ops.validate().is_ok(), "{ops}");
+                assert!(ops.as_opcodes().validate().is_ok(), "{ops}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_editops_validate() {
+        let ops = lcs_seq::editops(&to_seq("kitten"), &to_seq("sitting"));
+        assert!(ops.validate().is_ok());
+
+        let mut out_of_bounds = ops.clone();
+        out_of_bounds.editops[0].src_pos = 100;
+        assert!(out_of_bounds.validate().is_err());
+
+        let mut bogus = ops.clone();
+        bogus.editops[0].tag = "bogus".to_string();
+        assert!(bogus.validate().is_err());
+
+        let mut reversed = ops.clone();
+        reversed.editops.reverse();
+        assert!(reversed.validate().is_err());
+
+        let mut wrong_len = ops.clone();
+        wrong_len.dest_len += 1;
+        assert!(wrong_len.validate().is_err());
+
+        // both operations consume the same source element
+        let twice = Editops::new(2, 1, vec![editop("delete", 0, 0), editop("replace", 0, 0)]);
+        assert!(twice.validate().is_err());
+        let once = Editops::new(2, 1, vec![editop("delete", 0, 0), editop("replace", 1, 0)]);
+        assert!(once.validate().is_ok());
+    }
+
+    #[test]
+    fn test_opcodes_validate() {
+        let ops = lcs_seq::editops(&to_seq("kitten"), &to_seq("sitting")).as_opcodes();
+        assert!(ops.validate().is_ok());
+        assert!(Opcodes::new(0, 0, vec![]).validate().is_ok());
+        assert!(Opcodes::new(1, 0, vec![]).validate().is_err());
+
+        let gap = Opcodes::new(
+            3,
+            3,
+            vec![
+                opcode("equal", (0, 1), (0, 1)),
+                opcode("equal", (2, 3), (2, 3)),
+            ],
+        );
+        assert!(gap.validate().is_err());
+
+        let mismatched = Opcodes::new(2, 1, vec![opcode("equal", (0, 2), (0, 1))]);
+        assert!(mismatched.validate().is_err());
+
+        let backwards = Opcodes::new(2, 0, vec![opcode("delete", (2, 0), (0, 0))]);
+        assert!(backwards.validate().is_err());
+
+        let bogus = Opcodes::new(1, 1, vec![opcode("bogus", (0, 1), (0, 1))]);
+        assert!(bogus.validate().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    fn round_trip<T: Serialize + for<'de> Deserialize<'de>>(value: &T) -> T {
+        let json = serde_json::to_string(value).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_score_alignment_round_trip() {
+        let alignment = ScoreAlignment {
+            score: 83.33,
+            src_start: 0,
+            src_end: 5,
+            dest_start: 2,
+            dest_end: 7,
+        };
+        assert_eq!(round_trip(&alignment), alignment);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_matching_block_round_trip() {
+        let block = MatchingBlock {
+            a: 1,
+            b: 2,
+            size: 3,
+        };
+        assert_eq!(round_trip(&block), block);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_editops_round_trip() {
+        let s1 = "lewenstein".chars().map(|c| c as u32).collect::<Vec<_>>();
+        let s2 = "levenshtein".chars().map(|c| c as u32).collect::<Vec<_>>();
+        let ops = lcs_seq::editops(&s1, &s2);
+        let restored = round_trip(&ops);
+        assert_eq!(restored, ops);
+        assert_eq!(round_trip(&ops.editops[0]), ops.editops[0]);
+        assert_eq!(restored.apply("lewenstein", "levenshtein"), "levenshtein");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_opcodes_round_trip() {
+        let s1 = "lewenstein".chars().map(|c| c as u32).collect::<Vec<_>>();
+        let s2 = "levenshtein".chars().map(|c| c as u32).collect::<Vec<_>>();
+        let ops = lcs_seq::editops(&s1, &s2).as_opcodes();
+        let restored = round_trip(&ops);
+        assert_eq!(restored, ops);
+        assert_eq!(round_trip(&ops.opcodes[0]), ops.opcodes[0]);
+        assert_eq!(restored.apply("lewenstein", "levenshtein"), "levenshtein");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_rejects_invalid_edit_scripts() {
+        let ops = lcs_seq::editops(&to_seq("kitten"), &to_seq("sitting"));
+        let json = serde_json::to_string(&ops).unwrap();
+
+        let out_of_bounds = json.replacen("\"src_pos\":0", "\"src_pos\":100", 1);
+        assert!(serde_json::from_str::<Editops>(&out_of_bounds).is_err());
+        let bogus = json.replacen("\"insert\"", "\"bogus\"", 1);
+        assert!(serde_json::from_str::<Editops>(&bogus).is_err());
+        assert!(
+            serde_json::from_str::<Editop>(r#"{"tag":"bogus","src_pos":0,"dest_pos":0}"#).is_err()
+        );
+
+        let json = serde_json::to_string(&ops.as_opcodes()).unwrap();
+        let wrong_len = json.replacen("\"src_len\":6", "\"src_len\":7", 1);
+        assert!(serde_json::from_str::<Opcodes>(&wrong_len).is_err());
+        let bogus = json.replacen("\"equal\"", "\"bogus\"", 1);
+        assert!(serde_json::from_str::<Opcodes>(&bogus).is_err());
+    }
+}
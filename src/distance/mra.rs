@@ -0,0 +1,368 @@
+#[cfg(feature = "python")]
+use crate::distance::prep_inputs;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+/// Rating of two identical codices. Since codices are at most six letters
+/// long, it is the maximum similarity as well.
+const MAXIMUM: usize = 6;
+
+/// Codices are shortened to their first and last three letters.
+const MAX_CODEX_LEN: usize = 6;
+
+/// Match Rating Approach codex of `s`. Vowels after the first letter and
+/// the second letter of doubled letters are removed. Whitespace is ignored
+/// and ASCII letters are uppercased.
+fn codex(s: &[u32]) -> Vec<u32> {
+    let mut codex = Vec::new();
+    let mut prev = None;
+
+    let letters = s
+        .iter()
+        .filter(|&&c| !char::from_u32(c).is_some_and(char::is_whitespace))
+        .map(|&c| match char::from_u32(c) {
+            Some(ch) if ch.is_ascii_lowercase() => ch.to_ascii_uppercase() as u32,
+            _ => c,
+        });
+    for c in letters {
+        let is_vowel = c < 128 && b"AEIOU".contains(&(c as u8));
+        if prev.is_none() || (!is_vowel && prev != Some(c)) {
+            codex.push(c);
+        }
+        prev = Some(c);
+    }
+
+    if codex.len() > MAX_CODEX_LEN {
+        codex.drain(3..codex.len() - 3);
+    }
+    codex
+}
+
+/// Letters of `a` and `b` that differ at the same position.
+fn unmatched<'a>(
+    a: impl Iterator<Item = &'a u32>,
+    b: impl Iterator<Item = &'a u32>,
+) -> (Vec<u32>, Vec<u32>) {
+    let (mut a, mut b) = (a.peekable(), b.peekable());
+    let (mut rest1, mut rest2) = (Vec::new(), Vec::new());
+
+    while a.peek().is_some() || b.peek().is_some() {
+        let (ch1, ch2) = (a.next(), b.next());
+        if ch1 != ch2 {
+            rest1.extend(ch1);
+            rest2.extend(ch2);
+        }
+    }
+    (rest1, rest2)
+}
+
+/// Similarity rating of the codices of `s1` and `s2` between 0 and 6.
+fn mra_similarity(s1: &[u32], s2: &[u32]) -> usize {
+    let codex1 = codex(s1);
+    let codex2 = codex(s2);
+    if codex1 == codex2 {
+        return MAXIMUM;
+    }
+    if codex1.is_empty() || codex2.is_empty() || codex1.len().abs_diff(codex2.len()) >= 3 {
+        return 0;
+    }
+
+    // identical letters are removed from left to right, then from right to
+    // left, and the letters remaining in the longer codex are unmatched
+    let (rest1, rest2) = unmatched(codex1.iter(), codex2.iter());
+    let (rest1, rest2) = unmatched(rest1.iter().rev(), rest2.iter().rev());
+    MAXIMUM.saturating_sub(usize::max(rest1.len(), rest2.len()))
+}
+
+/**
+Calculates the Match Rating Approach distance between two strings.
+
+This is calculated as ``6 - similarity``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Maximum distance between s1 and s2, that is
+    considered as a result. If the distance is bigger than score_cutoff,
+    score_cutoff + 1 is returned instead. Default is None, which deactivates
+    this behaviour.
+
+Returns
+-------
+distance : int
+    distance between s1 and s2
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "distance",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let dist = distance(&s1, &s2, score_cutoff);
+
+    Ok(dist)
+}
+
+pub fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
+    let dist = MAXIMUM - similarity(s1, s2, None);
+
+    match score_cutoff {
+        Some(cutoff) if dist <= cutoff => dist,
+        Some(cutoff) => cutoff + 1,
+        None => dist,
+    }
+}
+
+/**
+Calculates the Match Rating Approach similarity in the range [6, 0].
+
+Both strings are encoded by removing vowels after the first letter and
+doubled letters, keeping the first and last three letters of longer
+codices. Identical letters at the same position are then removed from
+left to right and from right to left, and the similarity is 6 minus the
+number of letters left in the longer codex. Codices whose lengths differ
+by 3 or more are not compared and have a similarity of 0.
+
+The original approach considers two names a match when the similarity
+reaches a minimum rating depending on the summed length of the codices:
+5 up to a length of 4, 4 up to 7, 3 up to 11 and 2 otherwise.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Minimum similarity between s1 and s2, that is considered as a result.
+    If the similarity is smaller than score_cutoff, 0 is returned instead.
+    Default is None, which deactivates this behaviour.
+
+Returns
+-------
+similarity : int
+    similarity between s1 and s2
+
+Examples
+--------
+\>>> from crustyfuzz.distance import mra
+\>>> mra.similarity("Byrne", "Boern")
+5
+\>>> mra.similarity("Smith", "Schmidt")
+2
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "similarity",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let sim = similarity(&s1, &s2, score_cutoff);
+
+    Ok(sim)
+}
+
+pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
+    let sim = mra_similarity(s1, s2);
+
+    match score_cutoff {
+        Some(cutoff) if sim >= cutoff => sim,
+        Some(_) => 0,
+        None => sim,
+    }
+}
+
+/**
+Calculates a normalized Match Rating Approach distance in the range [1, 0].
+
+This is calculated as ``distance / 6``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_dist > score_cutoff 1.0 is returned instead. Default is 1.0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_dist : float
+    normalized distance between s1 and s2 as a float between 0 and 1.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_distance",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(1.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let norm_dist = normalized_distance(&s1, &s2, score_cutoff);
+
+    Ok(norm_dist)
+}
+
+pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
+    let norm_dist = distance(s1, s2, None) as f64 / MAXIMUM as f64;
+
+    match score_cutoff {
+        Some(cutoff) if norm_dist <= cutoff => norm_dist,
+        Some(_) => 1.0,
+        None => norm_dist,
+    }
+}
+
+/**
+Calculates a normalized Match Rating Approach similarity in the range
+[0, 1].
+
+This is calculated as ``1 - normalized_distance``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_sim < score_cutoff 0 is returned instead. Default is 0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_sim : float
+    normalized similarity between s1 and s2 as a float between 0 and 1.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_similarity",
+    signature = (s1, s2, *, processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(0.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let norm_sim = normalized_similarity(&s1, &s2, score_cutoff);
+
+    Ok(norm_sim)
+}
+
+pub fn normalized_similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
+    let norm_sim = 1.0 - normalized_distance(s1, s2, None);
+
+    match score_cutoff {
+        Some(cutoff) if norm_sim >= cutoff => norm_sim,
+        Some(_) => 0.0,
+        None => norm_sim,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::to_seq;
+
+    fn to_string(s: &[u32]) -> String {
+        s.iter().map(|&c| char::from_u32(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_codex() {
+        let cases = [
+            ("Byrne", "BYRN"),
+            ("Boern", "BRN"),
+            ("Smith", "SMTH"),
+            ("Schmidt", "SCHMDT"),
+            ("Catherine", "CTHRN"),
+            ("Abbott", "ABT"),
+            ("Van der Berg", "VNDBRG"),
+            ("Bartholomew", "BRTLMW"),
+            ("", ""),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(to_string(&codex(&to_seq(s))), expected, "{s}");
+        }
+    }
+
+    #[test]
+    fn test_similarity() {
+        let cases = [
+            ("Byrne", "Boern", 5),
+            ("Smith", "Schmidt", 2),
+            ("Catherine", "Kathryn", 4),
+            ("Smith", "Smith", 6),
+            ("", "", 6),
+            ("", "Smith", 0),
+            ("Al", "Bartholomew", 0),
+        ];
+        for (s1, s2, expected) in cases {
+            let (s1, s2) = (to_seq(s1), to_seq(s2));
+            assert_eq!(similarity(&s1, &s2, None), expected, "{s1:?} {s2:?}");
+            assert_eq!(similarity(&s2, &s1, None), expected);
+            assert_eq!(distance(&s1, &s2, None), 6 - expected);
+        }
+    }
+
+    #[test]
+    fn test_score_cutoff() {
+        let s1 = to_seq("Smith");
+        let s2 = to_seq("Schmidt");
+        assert_eq!(similarity(&s1, &s2, Some(2)), 2);
+        assert_eq!(similarity(&s1, &s2, Some(3)), 0);
+        assert_eq!(distance(&s1, &s2, Some(3)), 4);
+        assert!((normalized_distance(&s1, &s2, None) - 4.0 / 6.0).abs() < 1e-9);
+        assert_eq!(normalized_similarity(&s1, &s2, Some(0.5)), 0.0);
+    }
+}
@@ -1,121 +1,228 @@
+use crate::common::common_affix;
+#[cfg(feature = "python")]
 use crate::distance::prep_inputs;
+use crate::distance::PatternMatchVector;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 use std::cmp::min;
-use std::collections::HashMap;
-use std::mem;
 
-fn _osa_distance_hyrroe2003(s1: &[u32], s2: &[u32]) -> usize {
+/// Costs in the form `(insert, delete, substitute, transpose)`, where a
+/// transposition swaps two adjacent characters.
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weights(pub usize, pub usize, pub usize, pub usize);
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights(1, 1, 1, 1)
+    }
+}
+
+/// Transpositions are never required to turn s1 into s2, so this is the same
+/// as the maximum of the weighted Levenshtein distance.
+fn osa_maximum(s1: &[u32], s2: &[u32], weights: &Weights) -> usize {
     let len1 = s1.len();
     let len2 = s2.len();
+    let (insert, delete, substitute) = (weights.0, weights.1, weights.2);
+
+    let max_dist = len1 * delete + len2 * insert;
+
+    if len1 >= len2 {
+        max_dist.min(len2 * substitute + (len1 - len2) * delete)
+    } else {
+        max_dist.min(len1 * substitute + (len2 - len1) * insert)
+    }
+}
+
+/// Bit-parallel OSA distance by Hyyrö (2003) for patterns of up to 64
+/// characters. Returns `score_cutoff + 1` as soon as the cutoff can no
+/// longer be reached.
+fn hyrroe2003_single_word(
+    block: &PatternMatchVector,
+    len1: usize,
+    s2: &[u32],
+    score_cutoff: usize,
+) -> usize {
+    let mut vp = !0_u64;
+    let mut vn = 0_u64;
+    let mut d0 = 0_u64;
+    let mut pm_old = 0_u64;
+    let mut current_dist = len1;
+    let mask = 1_u64 << (len1 - 1);
+
+    for (j, &ch2) in s2.iter().enumerate() {
+        // Step 1: Computing D0
+        let x = block.get(ch2)[0];
+        let tr = (((!d0) & x) << 1) & pm_old;
+        d0 = (((x & vp).wrapping_add(vp)) ^ vp) | x | vn | tr;
+
+        // Step 2: Computing HP and HN
+        let mut hp = vn | !(d0 | vp);
+        let mut hn = d0 & vp;
+
+        // Step 3: Computing the value D[m,j]
+        current_dist += (hp & mask != 0) as usize;
+        current_dist -= (hn & mask != 0) as usize;
+        if current_dist > score_cutoff.saturating_add(s2.len() - j - 1) {
+            return score_cutoff + 1;
+        }
 
-    if len1 == 0 {
-        return len2;
-    }
-
-    let mut vert_pos = (1_u128 << len1) - 1_u128;
-    let mut vert_neg = 0_u128;
-    let mut diagonal_zero = 0_u128;
-    let mut pattern_match_j_old = 0_u128;
-    let mut current_distance = len1;
-    let mask = 1_u128 << (len1 - 1);
-
-    let mut block = HashMap::with_capacity(len1);
-    let mut position = 1_u128;
-    for &ch1 in s1 {
-        block
-            .entry(ch1)
-            .and_modify(|e| *e |= position)
-            .or_insert(position);
-        position <<= 1;
-    }
-
-    for &ch2 in s2 {
-        // step 1: computing D0
-        let pattern_match_j = *block.get(&ch2).unwrap_or(&0);
-        let tr = (((!diagonal_zero) & pattern_match_j) << 1) & pattern_match_j_old;
-        diagonal_zero =
-            (((pattern_match_j & vert_pos) + vert_pos) ^ vert_pos) | pattern_match_j | vert_neg;
-        diagonal_zero |= tr;
-        // step 2: computing HP and HN
-        let horizontal_pos = vert_neg | !(diagonal_zero | vert_pos);
-        let horizontal_neg = diagonal_zero & vert_pos;
-        // step 3: computing the value D[m,j]
-        current_distance += if (horizontal_pos & mask) == 0 { 0 } else { 1 };
-        current_distance -= if (horizontal_neg & mask) == 0 { 0 } else { 1 };
-        // step 4: computing VP and VN
-        let horizontal_pos = (horizontal_pos << 1) | 1;
-        let horizontal_neg = horizontal_neg << 1;
-        vert_pos = horizontal_neg | !(diagonal_zero | horizontal_pos);
-        vert_neg = horizontal_pos & diagonal_zero;
-        pattern_match_j_old = pattern_match_j;
-    }
-
-    current_distance
+        // Step 4: Computing VP and VN
+        hp = (hp << 1) | 1;
+        hn <<= 1;
+        vp = hn | !(d0 | hp);
+        vn = hp & d0;
+        pm_old = x;
+    }
+
+    current_dist
 }
 
-fn osa_distance(a: &[u32], b: &[u32]) -> usize {
-    // implementation from `strsim` crate, since it can handle arbitrary string lengths
-    let b_len = b.len();
-    // 0..=b_len behaves like 0..b_len.saturating_add(1) which could be a different size
-    // this leads to significantly worse code gen when swapping the vectors below
-    let mut prev_two_distances: Vec<usize> = (0..b_len + 1).collect();
-    let mut prev_distances: Vec<usize> = (0..b_len + 1).collect();
-    let mut curr_distances: Vec<usize> = vec![0; b_len + 1];
-
-    let mut prev_a_char = u32::from(char::MAX);
-    let mut prev_b_char = u32::from(char::MAX);
-
-    for (i, a_char) in a.iter().enumerate() {
-        curr_distances[0] = i + 1;
-
-        for (j, b_char) in b.iter().enumerate() {
-            let cost = usize::from(a_char != b_char);
-            curr_distances[j + 1] = min(
-                curr_distances[j] + 1,
-                min(prev_distances[j + 1] + 1, prev_distances[j] + cost),
-            );
-            if i > 0
-                && j > 0
-                && a_char != b_char
-                && *a_char == prev_b_char
-                && *b_char == prev_a_char
-            {
-                curr_distances[j + 1] = min(curr_distances[j + 1], prev_two_distances[j - 1] + 1);
+/// Blocked variant of the bit-parallel OSA distance. Besides the horizontal
+/// deltas, the transposition term needs the top bit of D0 and of the match
+/// vector of the previous word.
+fn hyrroe2003_blocks(
+    block: &PatternMatchVector,
+    len1: usize,
+    s2: &[u32],
+    score_cutoff: usize,
+) -> usize {
+    let words = block.words();
+    let mut vp = vec![!0_u64; words];
+    let mut vn = vec![0_u64; words];
+    let mut d0 = vec![0_u64; words];
+    let mut pm_old = vec![0_u64; words];
+    let mut current_dist = len1;
+    let last = 1_u64 << ((len1 - 1) % 64);
+
+    for (j, &ch2) in s2.iter().enumerate() {
+        let pattern = block.get(ch2);
+        let mut hp_carry = 1_u64;
+        let mut hn_carry = 0_u64;
+        // D0 of the previous row and match vector of the current row for
+        // the word below, used to carry the transposition into this word
+        let mut d0_last = 0_u64;
+        let mut pm_last = 0_u64;
+
+        for word in 0..words {
+            let pm = pattern[word];
+            let tr = ((((!d0[word]) & pm) << 1) | (((!d0_last) & pm_last) >> 63)) & pm_old[word];
+            d0_last = d0[word];
+            pm_last = pm;
+
+            let x = pm | hn_carry;
+            let d = (((x & vp[word]).wrapping_add(vp[word])) ^ vp[word]) | x | vn[word] | tr;
+
+            let hp = vn[word] | !(d | vp[word]);
+            let hn = d & vp[word];
+
+            let hp_carry_in = hp_carry;
+            let hn_carry_in = hn_carry;
+            if word < words - 1 {
+                hp_carry = hp >> 63;
+                hn_carry = hn >> 63;
+            } else {
+                hp_carry = (hp & last != 0) as u64;
+                hn_carry = (hn & last != 0) as u64;
             }
 
-            prev_b_char = *b_char;
+            let hp = (hp << 1) | hp_carry_in;
+            let hn = (hn << 1) | hn_carry_in;
+            vp[word] = hn | !(d | hp);
+            vn[word] = hp & d;
+            d0[word] = d;
+            pm_old[word] = pm;
         }
 
-        mem::swap(&mut prev_two_distances, &mut prev_distances);
-        mem::swap(&mut prev_distances, &mut curr_distances);
-        prev_a_char = *a_char;
+        current_dist += hp_carry as usize;
+        current_dist -= hn_carry as usize;
+        if current_dist > score_cutoff.saturating_add(s2.len() - j - 1) {
+            return score_cutoff + 1;
+        }
+    }
+
+    current_dist
+}
+
+fn osa_distance(s1: &[u32], s2: &[u32], score_cutoff: usize) -> usize {
+    // the distance is symmetric, so use the shorter sequence as pattern
+    let (s1, s2) = if s1.len() > s2.len() {
+        (s2, s1)
+    } else {
+        (s1, s2)
+    };
+
+    // the length difference is a lower bound for the distance
+    if s2.len() - s1.len() > score_cutoff {
+        return score_cutoff + 1;
+    }
+
+    let (prefix_len, suffix_len) = common_affix(s1, s2);
+    let s1 = &s1[prefix_len..s1.len() - suffix_len];
+    let s2 = &s2[prefix_len..s2.len() - suffix_len];
+
+    if s1.is_empty() {
+        return s2.len();
+    }
+
+    let block = PatternMatchVector::new(s1);
+    if s1.len() <= 64 {
+        hyrroe2003_single_word(&block, s1.len(), s2, score_cutoff)
+    } else {
+        hyrroe2003_blocks(&block, s1.len(), s2, score_cutoff)
     }
+}
 
-    // access prev_distances instead of curr_distances since we swapped
-    // them above. In case a is empty this would still contain the correct value
-    // from initializing the last element to b_len
-    prev_distances[b_len]
+/// OSA distance for arbitrary weights using the classic dynamic program,
+/// which only needs the previous two rows.
+fn osa_weighted(s1: &[u32], s2: &[u32], weights: &Weights) -> usize {
+    let (insert, delete, substitute, transpose) = (weights.0, weights.1, weights.2, weights.3);
+    let mut prev2 = vec![0; s2.len() + 1];
+    let mut prev: Vec<usize> = (0..=s2.len()).map(|j| j * insert).collect();
+    let mut curr = vec![0; s2.len() + 1];
+
+    for (i, &ch1) in s1.iter().enumerate() {
+        curr[0] = (i + 1) * delete;
+        for (j, &ch2) in s2.iter().enumerate() {
+            let cost = if ch1 == ch2 { 0 } else { substitute };
+            curr[j + 1] = min(prev[j] + cost, min(prev[j + 1] + delete, curr[j] + insert));
+
+            if i > 0 && j > 0 && ch1 != ch2 && ch1 == s2[j - 1] && ch2 == s1[i - 1] {
+                curr[j + 1] = curr[j + 1].min(prev2[j - 1] + transpose);
+            }
+        }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[s2.len()]
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "distance",
-    signature = (s1, s2, *, processor=None, score_cutoff=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
 )]
 pub fn py_distance(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<usize>,
 ) -> PyResult<usize> {
     let (s1, s2) = prep_inputs(s1, s2, processor)?;
-    let res = distance(&s1, &s2, score_cutoff);
+    let res = distance(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
 
     Ok(res)
 }
 
-fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
-    let dist = osa_distance(s1, s2);
+pub fn distance(s1: &[u32], s2: &[u32], weights: Weights, score_cutoff: Option<usize>) -> usize {
+    let dist = if weights == Weights::default() {
+        osa_distance(s1, s2, score_cutoff.unwrap_or(usize::MAX))
+    } else {
+        osa_weighted(s1, s2, &weights)
+    };
 
     match score_cutoff {
         Some(cutoff) if dist <= cutoff => dist,
@@ -124,26 +231,28 @@ fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "similarity",
-    signature = (s1, s2, *, processor=None, score_cutoff=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
 )]
 pub fn py_similarity(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<usize>,
 ) -> PyResult<usize> {
     let (s1, s2) = prep_inputs(s1, s2, processor)?;
-    let sim = similarity(&s1, &s2, score_cutoff);
+    let sim = similarity(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
 
     Ok(sim)
 }
 
-pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
-    let maximum = usize::max(s1.len(), s2.len());
-    let dist = distance(s1, s2, score_cutoff);
+pub fn similarity(s1: &[u32], s2: &[u32], weights: Weights, score_cutoff: Option<usize>) -> usize {
+    let maximum = osa_maximum(s1, s2, &weights);
+    let dist = distance(s1, s2, weights, None);
     let sim = maximum - dist;
 
     match score_cutoff {
@@ -153,14 +262,16 @@ pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_distance",
-    signature = (s1, s2, *, processor=None, score_cutoff=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
 )]
 pub fn py_normalized_distance(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<f64>,
 ) -> PyResult<f64> {
@@ -169,17 +280,22 @@ pub fn py_normalized_distance(
     }
 
     let (s1, s2) = prep_inputs(s1, s2, processor)?;
-    let norm_dist = normalized_distance(&s1, &s2, score_cutoff);
+    let norm_dist = normalized_distance(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
 
     Ok(norm_dist)
 }
 
-pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
-    let maximum = usize::max(s1.len(), s2.len()) as f64;
+pub fn normalized_distance(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let maximum = osa_maximum(s1, s2, &weights) as f64;
     let norm_dist = if maximum == 0.0 {
         0.0
     } else {
-        let dist = distance(s1, s2, None) as f64;
+        let dist = distance(s1, s2, weights, None) as f64;
         dist / maximum
     };
 
@@ -190,14 +306,16 @@ pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) ->
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_similarity",
-    signature = (s1, s2, *, processor=None, score_cutoff=None)
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
 )]
 pub fn py_normalized_similarity(
     s1: &Bound<'_, PyAny>,
     s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
     processor: Option<&Bound<'_, PyAny>>,
     score_cutoff: Option<f64>,
 ) -> PyResult<f64> {
@@ -206,13 +324,18 @@ pub fn py_normalized_similarity(
     }
 
     let (s1, s2) = prep_inputs(s1, s2, processor)?;
-    let norm_sim = normalized_similarity(&s1, &s2, score_cutoff);
+    let norm_sim = normalized_similarity(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
 
     Ok(norm_sim)
 }
 
-pub fn normalized_similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
-    let norm_dist = normalized_distance(s1, s2, None);
+pub fn normalized_similarity(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let norm_dist = normalized_distance(s1, s2, weights, None);
     let norm_sim = 1.0 - norm_dist;
 
     match score_cutoff {
@@ -225,6 +348,67 @@ pub fn normalized_similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>)
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::test_utils::pseudo_random_seq;
+    use std::mem;
+
+    fn distance_dp(a: &[u32], b: &[u32]) -> usize {
+        let b_len = b.len();
+        let mut prev_two_distances: Vec<usize> = (0..b_len + 1).collect();
+        let mut prev_distances: Vec<usize> = (0..b_len + 1).collect();
+        let mut curr_distances: Vec<usize> = vec![0; b_len + 1];
+
+        for (i, a_char) in a.iter().enumerate() {
+            curr_distances[0] = i + 1;
+            for (j, b_char) in b.iter().enumerate() {
+                let cost = usize::from(a_char != b_char);
+                curr_distances[j + 1] = min(
+                    curr_distances[j] + 1,
+                    min(prev_distances[j + 1] + 1, prev_distances[j] + cost),
+                );
+                if i > 0 && j > 0 && a_char != b_char && *a_char == b[j - 1] && *b_char == a[i - 1]
+                {
+                    curr_distances[j + 1] =
+                        min(curr_distances[j + 1], prev_two_distances[j - 1] + 1);
+                }
+            }
+            mem::swap(&mut prev_two_distances, &mut prev_distances);
+            mem::swap(&mut prev_distances, &mut curr_distances);
+        }
+
+        prev_distances[b_len]
+    }
+
+    #[test]
+    fn test_distance_blocks() {
+        let lens = [0, 1, 5, 63, 64, 65, 127, 128, 129, 200];
+        for (seed, &len1) in lens.iter().enumerate() {
+            for &len2 in &lens {
+                let s1 = pseudo_random_seq(seed as u64, len1);
+                let s2 = pseudo_random_seq(seed as u64 + 100, len2);
+                let expected = distance_dp(&s1, &s2);
+                assert_eq!(
+                    distance(&s1, &s2, Weights::default(), None),
+                    expected,
+                    "len1={len1} len2={len2}"
+                );
+                assert_eq!(
+                    distance(&s2, &s1, Weights::default(), None),
+                    expected,
+                    "len1={len1} len2={len2}"
+                );
+                if expected > 0 {
+                    assert_eq!(
+                        distance(&s1, &s2, Weights::default(), Some(expected - 1)),
+                        expected
+                    );
+                }
+                assert_eq!(
+                    distance(&s1, &s2, Weights::default(), Some(expected)),
+                    expected
+                );
+            }
+        }
+    }
 
     #[test]
     fn test_distance() {
@@ -234,9 +418,43 @@ mod tests {
             super::distance(
                 &s1.chars().map(|c| c as u32).collect::<Vec<_>>(),
                 &s2.chars().map(|c| c as u32).collect::<Vec<_>>(),
+                Weights::default(),
                 None
             ),
             3
         );
     }
+
+    #[test]
+    fn test_weighted_distance() {
+        let seq = |s: &str| s.chars().map(|c| c as u32).collect::<Vec<_>>();
+        let dist = |a: &str, b: &str, w: Weights| distance(&seq(a), &seq(b), w, None);
+        assert_eq!(dist("ab", "ba", Weights(1, 1, 2, 1)), 1);
+        assert_eq!(dist("ab", "ba", Weights(1, 1, 2, 3)), 2);
+        // the transposed characters can not be edited again
+        assert_eq!(dist("ca", "abc", Weights(1, 1, 1, 1)), 3);
+        assert_eq!(dist("abc", "", Weights(1, 3, 1, 1)), 9);
+        assert_eq!(dist("", "abc", Weights(1, 3, 1, 1)), 3);
+
+        let s1 = seq("abcdef");
+        let s2 = seq("bacdfe");
+        let w = Weights(2, 2, 3, 1);
+        assert_eq!(distance(&s1, &s2, w, None), 2);
+        assert_eq!(distance(&s1, &s2, w, Some(1)), 2);
+        assert_eq!(osa_maximum(&s1, &s2, &w), 18);
+        assert_eq!(similarity(&s1, &s2, w, None), 16);
+        assert_eq!(normalized_distance(&s1, &s2, w, None), 2.0 / 18.0);
+    }
+
+    #[test]
+    fn test_weighted_matches_bit_parallel() {
+        for (seed, len) in [0, 1, 7, 30, 70].into_iter().enumerate() {
+            let s1 = pseudo_random_seq(seed as u64, len);
+            let s2 = pseudo_random_seq(seed as u64 + 100, len + seed);
+            assert_eq!(
+                osa_weighted(&s1, &s2, &Weights::default()),
+                distance_dp(&s1, &s2)
+            );
+        }
+    }
 }
@@ -1,7 +1,10 @@
+#[cfg(feature = "python")]
 use crate::distance::prep_inputs;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 use std::iter::zip;
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "distance",
@@ -19,7 +22,7 @@ pub fn py_distance(
     Ok(res)
 }
 
-fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
+pub fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
     let maximum = usize::max(s1.len(), s2.len());
     let sim = similarity(s1, s2, None);
     let dist = maximum - sim;
@@ -31,6 +34,7 @@ fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "similarity",
@@ -64,6 +68,7 @@ pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_distance",
@@ -96,6 +101,7 @@ pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) ->
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_similarity",
@@ -1,4 +1,6 @@
+#[cfg(feature = "python")]
 use crate::distance::prep_inputs;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 use std::iter::zip;
 
@@ -25,6 +27,7 @@ Returns
 distance : int
     distance between s1 and s2
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "distance",
@@ -42,7 +45,7 @@ pub fn py_distance(
     Ok(res)
 }
 
-fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
+pub fn distance(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize {
     let maximum = usize::max(s1.len(), s2.len());
     let sim = similarity(s1, s2, None);
     let dist = maximum - sim;
@@ -79,6 +82,7 @@ Returns
 distance : int
     distance between s1 and s2
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "similarity",
@@ -112,6 +116,7 @@ pub fn similarity(s1: &[u32], s2: &[u32], score_cutoff: Option<usize>) -> usize
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_distance",
@@ -144,6 +149,7 @@ pub fn normalized_distance(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) ->
     }
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "normalized_similarity",
@@ -0,0 +1,289 @@
+#[cfg(feature = "python")]
+use crate::distance::prep_inputs;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// How the q-grams of a sequence are formed and counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QGramOptions {
+    /// Length of every q-gram. Has to be greater than 0.
+    pub q: usize,
+    /// Element that is added `q - 1` times on both sides of the sequence, so
+    /// the first and last elements are part of as many q-grams as the rest.
+    pub pad: Option<u32>,
+    /// Count repeated q-grams instead of treating the q-grams as a set.
+    pub multiset: bool,
+}
+
+impl Default for QGramOptions {
+    fn default() -> Self {
+        Self {
+            q: 2,
+            pad: None,
+            multiset: false,
+        }
+    }
+}
+
+/// Sizes of two q-gram profiles and of their overlap. With set semantics
+/// every q-gram is counted once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QGramStats {
+    /// Number of q-grams of `s1`.
+    pub len1: usize,
+    /// Number of q-grams of `s2`.
+    pub len2: usize,
+    /// Number of q-grams the sequences have in common.
+    pub intersection: usize,
+    /// Dot product of the q-gram counts.
+    pub dot: usize,
+    /// Squared norm of the q-gram counts of `s1`.
+    pub norm1: usize,
+    /// Squared norm of the q-gram counts of `s2`.
+    pub norm2: usize,
+}
+
+impl QGramStats {
+    /// Number of q-grams in either sequence.
+    pub fn union(&self) -> usize {
+        self.len1 + self.len2 - self.intersection
+    }
+}
+
+fn padded<'a>(s: &'a [u32], options: &QGramOptions) -> Cow<'a, [u32]> {
+    match options.pad {
+        Some(pad) if options.q > 1 => {
+            let padding = std::iter::repeat_n(pad, options.q - 1);
+            Cow::Owned(
+                padding
+                    .clone()
+                    .chain(s.iter().copied())
+                    .chain(padding)
+                    .collect(),
+            )
+        }
+        _ => Cow::Borrowed(s),
+    }
+}
+
+fn profile<'a>(s: &'a [u32], options: &QGramOptions) -> HashMap<&'a [u32], usize> {
+    let mut counts = HashMap::new();
+    for gram in s.windows(options.q) {
+        let count = counts.entry(gram).or_insert(0);
+        if *count == 0 || options.multiset {
+            *count += 1;
+        }
+    }
+    counts
+}
+
+/// Counts the q-grams of `s1` and `s2` and the q-grams they share.
+pub fn stats(s1: &[u32], s2: &[u32], options: &QGramOptions) -> QGramStats {
+    assert!(options.q > 0, "q has to be greater than 0");
+
+    let s1 = padded(s1, options);
+    let s2 = padded(s2, options);
+    let profile1 = profile(&s1, options);
+    let profile2 = profile(&s2, options);
+
+    let mut stats = QGramStats {
+        len1: profile1.values().sum(),
+        len2: profile2.values().sum(),
+        intersection: 0,
+        dot: 0,
+        norm1: profile1.values().map(|c| c * c).sum(),
+        norm2: profile2.values().map(|c| c * c).sum(),
+    };
+
+    let (smaller, larger) = if profile1.len() <= profile2.len() {
+        (&profile1, &profile2)
+    } else {
+        (&profile2, &profile1)
+    };
+    for (gram, &count1) in smaller {
+        if let Some(&count2) = larger.get(gram) {
+            stats.intersection += usize::min(count1, count2);
+            stats.dot += count1 * count2;
+        }
+    }
+
+    stats
+}
+
+/// Computes a coefficient in the range [0, 1] over the q-grams of `s1` and
+/// `s2`. Sequences without any q-grams are only similar to equal sequences.
+pub(crate) fn similarity_with(
+    s1: &[u32],
+    s2: &[u32],
+    options: &QGramOptions,
+    score_cutoff: f64,
+    coefficient: fn(&QGramStats) -> f64,
+) -> f64 {
+    let stats = stats(s1, s2, options);
+    let sim = match (stats.len1, stats.len2) {
+        (0, 0) if s1 == s2 => 1.0,
+        (0, _) | (_, 0) => 0.0,
+        _ => coefficient(&stats),
+    };
+
+    if sim >= score_cutoff {
+        sim
+    } else {
+        0.0
+    }
+}
+
+pub(crate) fn distance_with(
+    s1: &[u32],
+    s2: &[u32],
+    options: &QGramOptions,
+    score_cutoff: Option<f64>,
+    coefficient: fn(&QGramStats) -> f64,
+) -> f64 {
+    let cutoff_similarity = match score_cutoff {
+        Some(cutoff) if cutoff > 1.0 => 0.0,
+        Some(cutoff) => 1.0 - cutoff,
+        None => 0.0,
+    };
+
+    let sim = similarity_with(s1, s2, options, cutoff_similarity, coefficient);
+    let dist = 1.0 - sim;
+
+    match score_cutoff {
+        Some(cutoff) if dist <= cutoff => dist,
+        Some(_) => 1.0,
+        None => dist,
+    }
+}
+
+#[cfg(feature = "python")]
+fn py_options(q: usize, pad: Option<char>, multiset: bool) -> PyResult<QGramOptions> {
+    if q == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "q has to be greater than 0",
+        ));
+    }
+
+    Ok(QGramOptions {
+        q,
+        pad: pad.map(|c| c as u32),
+        multiset,
+    })
+}
+
+#[cfg(feature = "python")]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn py_similarity_with(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    q: usize,
+    pad: Option<char>,
+    multiset: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+    coefficient: fn(&QGramStats) -> f64,
+) -> PyResult<f64> {
+    let options = py_options(q, pad, multiset)?;
+
+    if s1.is_none() || s2.is_none() {
+        return Ok(0.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let sim = similarity_with(&s1, &s2, &options, score_cutoff.unwrap_or(0.0), coefficient);
+
+    Ok(sim)
+}
+
+#[cfg(feature = "python")]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn py_distance_with(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    q: usize,
+    pad: Option<char>,
+    multiset: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+    coefficient: fn(&QGramStats) -> f64,
+) -> PyResult<f64> {
+    let options = py_options(q, pad, multiset)?;
+
+    if s1.is_none() || s2.is_none() {
+        return Ok(1.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let dist = distance_with(&s1, &s2, &options, score_cutoff, coefficient);
+
+    Ok(dist)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::to_seq;
+
+    #[test]
+    fn test_stats_set() {
+        let options = QGramOptions::default();
+        // ab bc cd | bc cd de
+        let stats = stats(&to_seq("abcd"), &to_seq("bcde"), &options);
+        assert_eq!(stats.len1, 3);
+        assert_eq!(stats.len2, 3);
+        assert_eq!(stats.intersection, 2);
+        assert_eq!(stats.union(), 4);
+        assert_eq!(stats.dot, 2);
+    }
+
+    #[test]
+    fn test_stats_multiset() {
+        let set = QGramOptions::default();
+        let multiset = QGramOptions {
+            multiset: true,
+            ..set
+        };
+        // aa aa aa | aa
+        let s1 = to_seq("aaaa");
+        let s2 = to_seq("aa");
+        assert_eq!(stats(&s1, &s2, &set).len1, 1);
+
+        let stats = stats(&s1, &s2, &multiset);
+        assert_eq!(stats.len1, 3);
+        assert_eq!(stats.len2, 1);
+        assert_eq!(stats.intersection, 1);
+        assert_eq!(stats.union(), 3);
+        assert_eq!(stats.dot, 3);
+        assert_eq!(stats.norm1, 9);
+        assert_eq!(stats.norm2, 1);
+    }
+
+    #[test]
+    fn test_stats_padding() {
+        let options = QGramOptions {
+            q: 3,
+            pad: Some('#' as u32),
+            multiset: false,
+        };
+        // ##a #ab ab# b## | ##a #a# a##
+        let stats = stats(&to_seq("ab"), &to_seq("a"), &options);
+        assert_eq!(stats.len1, 4);
+        assert_eq!(stats.len2, 3);
+        assert_eq!(stats.intersection, 1);
+    }
+
+    #[test]
+    fn test_similarity_without_qgrams() {
+        let options = QGramOptions::default();
+        let coefficient = |_: &QGramStats| unreachable!();
+        assert_eq!(similarity_with(&[], &[], &options, 0.0, coefficient), 1.0);
+        assert_eq!(similarity_with(&[1], &[1], &options, 0.0, coefficient), 1.0);
+        assert_eq!(similarity_with(&[1], &[2], &options, 0.0, coefficient), 0.0);
+        assert_eq!(
+            similarity_with(&[1], &[1, 2], &options, 0.0, coefficient),
+            0.0
+        );
+    }
+}
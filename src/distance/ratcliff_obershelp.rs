@@ -0,0 +1,713 @@
+use crate::distance::models::{MatchingBlock, Opcode, Opcodes};
+#[cfg(feature = "python")]
+use crate::distance::prep_inputs;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Sequences at least this long are subject to the autojunk heuristic.
+const AUTOJUNK_MIN_LEN: usize = 200;
+
+/// Which elements of the second sequence are ignored when searching for
+/// matching blocks, following `difflib.SequenceMatcher`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatcherOptions {
+    /// Elements of `s2` that never start a match. Junk elements adjacent to
+    /// a match are still added to it.
+    pub junk: HashSet<u32>,
+    /// Treat elements that make up more than 1% of `s2` as junk, if `s2` has
+    /// at least 200 elements.
+    pub autojunk: bool,
+}
+
+impl Default for MatcherOptions {
+    fn default() -> Self {
+        Self {
+            junk: HashSet::new(),
+            autojunk: true,
+        }
+    }
+}
+
+/// Port of the matching block search of `difflib.SequenceMatcher`, which
+/// repeatedly takes the longest matching block and recurses into the parts
+/// left and right of it.
+struct SequenceMatcher<'a> {
+    a: &'a [u32],
+    b: &'a [u32],
+    /// positions of the elements of `b`, without junk and popular elements
+    b2j: HashMap<u32, Vec<usize>>,
+    junk: &'a HashSet<u32>,
+}
+
+impl<'a> SequenceMatcher<'a> {
+    fn new(a: &'a [u32], b: &'a [u32], options: &'a MatcherOptions) -> Self {
+        let mut b2j: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (j, &elem) in b.iter().enumerate() {
+            b2j.entry(elem).or_default().push(j);
+        }
+
+        b2j.retain(|elem, _| !options.junk.contains(elem));
+        if options.autojunk && b.len() >= AUTOJUNK_MIN_LEN {
+            let popular = b.len() / 100 + 1;
+            b2j.retain(|_, positions| positions.len() <= popular);
+        }
+
+        Self {
+            a,
+            b,
+            b2j,
+            junk: &options.junk,
+        }
+    }
+
+    /// Longest matching block in `a[alo..ahi]` and `b[blo..bhi]` that does
+    /// not start with junk. Ties are broken by the earliest start in `a` and
+    /// then in `b`.
+    fn find_longest_match(&self, alo: usize, ahi: usize, blo: usize, bhi: usize) -> MatchingBlock {
+        let (a, b) = (self.a, self.b);
+        let (mut besti, mut bestj, mut bestsize) = (alo, blo, 0);
+
+        // j2len[j] is the length of the longest match ending in a[i - 1]
+        // and b[j]
+        let mut j2len: HashMap<usize, usize> = HashMap::new();
+        let mut new_j2len: HashMap<usize, usize> = HashMap::new();
+        for (i, elem) in a.iter().enumerate().take(ahi).skip(alo) {
+            new_j2len.clear();
+            for &j in self.b2j.get(elem).into_iter().flatten() {
+                if j < blo {
+                    continue;
+                }
+                if j >= bhi {
+                    break;
+                }
+                let k = j
+                    .checked_sub(1)
+                    .and_then(|prev| j2len.get(&prev))
+                    .map_or(1, |len| len + 1);
+                new_j2len.insert(j, k);
+                if k > bestsize {
+                    (besti, bestj, bestsize) = (i + 1 - k, j + 1 - k, k);
+                }
+            }
+            std::mem::swap(&mut j2len, &mut new_j2len);
+        }
+
+        // extend the match with equal elements that were removed from b2j,
+        // first the popular ones and then the junk
+        for is_junk in [false, true] {
+            let extends =
+                |ai: usize, bj: usize| self.junk.contains(&b[bj]) == is_junk && a[ai] == b[bj];
+            while besti > alo && bestj > blo && extends(besti - 1, bestj - 1) {
+                besti -= 1;
+                bestj -= 1;
+                bestsize += 1;
+            }
+            while besti + bestsize < ahi
+                && bestj + bestsize < bhi
+                && extends(besti + bestsize, bestj + bestsize)
+            {
+                bestsize += 1;
+            }
+        }
+
+        MatchingBlock {
+            a: besti,
+            b: bestj,
+            size: bestsize,
+        }
+    }
+
+    fn matching_blocks(&self) -> Vec<MatchingBlock> {
+        let (len1, len2) = (self.a.len(), self.b.len());
+
+        let mut blocks = Vec::new();
+        let mut queue = vec![(0, len1, 0, len2)];
+        while let Some((alo, ahi, blo, bhi)) = queue.pop() {
+            let block = self.find_longest_match(alo, ahi, blo, bhi);
+            if block.size == 0 {
+                continue;
+            }
+            if alo < block.a && blo < block.b {
+                queue.push((alo, block.a, blo, block.b));
+            }
+            if block.a + block.size < ahi && block.b + block.size < bhi {
+                queue.push((block.a + block.size, ahi, block.b + block.size, bhi));
+            }
+            blocks.push(block);
+        }
+        blocks.sort_by_key(|block| (block.a, block.b));
+
+        // merge adjacent blocks
+        let mut merged: Vec<MatchingBlock> = Vec::with_capacity(blocks.len() + 1);
+        for block in blocks {
+            match merged.last_mut() {
+                Some(last) if last.a + last.size == block.a && last.b + last.size == block.b => {
+                    last.size += block.size;
+                }
+                _ => merged.push(block),
+            }
+        }
+        merged.push(MatchingBlock {
+            a: len1,
+            b: len2,
+            size: 0,
+        });
+        merged
+    }
+}
+
+/// Summed size of the matching blocks of `s1` and `s2`.
+fn matches(s1: &[u32], s2: &[u32], options: &MatcherOptions) -> usize {
+    SequenceMatcher::new(s1, s2, options)
+        .matching_blocks()
+        .iter()
+        .map(|block| block.size)
+        .sum()
+}
+
+/**
+Calculates the Ratcliff/Obershelp similarity in the range [0, len1 + len2].
+
+This is the number of elements of s1 and s2 that are part of a matching
+block, i.e. twice the summed size of the blocks
+``difflib.SequenceMatcher.get_matching_blocks`` finds.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+isjunk : callable, optional
+    Called with each element of s2, elements it returns True for are junk
+    and never start a matching block. Default is None, which treats no
+    element as junk.
+autojunk : bool, optional
+    Treat elements that make up more than 1% of s2 as junk if s2 has at
+    least 200 elements, like difflib. Default is True.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Minimum similarity between s1 and s2, that is considered as a result.
+    If the similarity is smaller than score_cutoff, 0 is returned instead.
+    Default is None, which deactivates this behaviour.
+
+Returns
+-------
+similarity : int
+    similarity between s1 and s2
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "similarity",
+    signature = (s1, s2, *, isjunk=None, autojunk=true, processor=None, score_cutoff=None)
+)]
+pub fn py_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    isjunk: Option<&Bound<'_, PyAny>>,
+    autojunk: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2, options) = py_prep_inputs(s1, s2, isjunk, autojunk, processor)?;
+    let sim = similarity(&s1, &s2, &options, score_cutoff);
+
+    Ok(sim)
+}
+
+pub fn similarity(
+    s1: &[u32],
+    s2: &[u32],
+    options: &MatcherOptions,
+    score_cutoff: Option<usize>,
+) -> usize {
+    let sim = 2 * matches(s1, s2, options);
+
+    match score_cutoff {
+        Some(cutoff) if sim < cutoff => 0,
+        _ => sim,
+    }
+}
+
+/**
+Calculates the Ratcliff/Obershelp distance in the range [0, len1 + len2].
+
+This is calculated as ``len1 + len2 - similarity``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+isjunk : callable, optional
+    Called with each element of s2, elements it returns True for are junk
+    and never start a matching block. Default is None, which treats no
+    element as junk.
+autojunk : bool, optional
+    Treat elements that make up more than 1% of s2 as junk if s2 has at
+    least 200 elements, like difflib. Default is True.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Maximum distance between s1 and s2, that is
+    considered as a result. If the distance is bigger than score_cutoff,
+    score_cutoff + 1 is returned instead. Default is None, which deactivates
+    this behaviour.
+
+Returns
+-------
+distance : int
+    distance between s1 and s2
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "distance",
+    signature = (s1, s2, *, isjunk=None, autojunk=true, processor=None, score_cutoff=None)
+)]
+pub fn py_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    isjunk: Option<&Bound<'_, PyAny>>,
+    autojunk: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2, options) = py_prep_inputs(s1, s2, isjunk, autojunk, processor)?;
+    let dist = distance(&s1, &s2, &options, score_cutoff);
+
+    Ok(dist)
+}
+
+pub fn distance(
+    s1: &[u32],
+    s2: &[u32],
+    options: &MatcherOptions,
+    score_cutoff: Option<usize>,
+) -> usize {
+    let dist = s1.len() + s2.len() - similarity(s1, s2, options, None);
+
+    match score_cutoff {
+        Some(cutoff) if dist > cutoff => cutoff + 1,
+        _ => dist,
+    }
+}
+
+/**
+Calculates a normalized Ratcliff/Obershelp distance in the range [1, 0].
+
+This is calculated as ``1 - normalized_similarity``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+isjunk : callable, optional
+    Called with each element of s2, elements it returns True for are junk
+    and never start a matching block. Default is None, which treats no
+    element as junk.
+autojunk : bool, optional
+    Treat elements that make up more than 1% of s2 as junk if s2 has at
+    least 200 elements, like difflib. Default is True.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_dist > score_cutoff 1.0 is returned instead. Default is 1.0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_dist : float
+    normalized distance between s1 and s2 as a float between 0 and 1.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_distance",
+    signature = (s1, s2, *, isjunk=None, autojunk=true, processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    isjunk: Option<&Bound<'_, PyAny>>,
+    autojunk: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(1.0);
+    }
+
+    let (s1, s2, options) = py_prep_inputs(s1, s2, isjunk, autojunk, processor)?;
+    let norm_dist = normalized_distance(&s1, &s2, &options, score_cutoff);
+
+    Ok(norm_dist)
+}
+
+pub fn normalized_distance(
+    s1: &[u32],
+    s2: &[u32],
+    options: &MatcherOptions,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let norm_dist = 1.0 - normalized_similarity(s1, s2, options, None);
+
+    match score_cutoff {
+        Some(cutoff) if norm_dist > cutoff => 1.0,
+        _ => norm_dist,
+    }
+}
+
+/**
+Calculates a normalized Ratcliff/Obershelp similarity in the range [0, 1].
+
+This is calculated as ``similarity / (len1 + len2)`` and is the same as
+``difflib.SequenceMatcher(isjunk, s1, s2, autojunk).ratio()``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+isjunk : callable, optional
+    Called with each element of s2, elements it returns True for are junk
+    and never start a matching block. Default is None, which treats no
+    element as junk.
+autojunk : bool, optional
+    Treat elements that make up more than 1% of s2 as junk if s2 has at
+    least 200 elements, like difflib. Default is True.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_sim < score_cutoff 0 is returned instead. Default is 0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_sim : float
+    normalized similarity between s1 and s2 as a float between 0 and 1.0
+
+Examples
+--------
+\>>> from crustyfuzz.distance import ratcliff_obershelp
+\>>> ratcliff_obershelp.normalized_similarity("abcd", "bcde")
+0.75
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_similarity",
+    signature = (s1, s2, *, isjunk=None, autojunk=true, processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    isjunk: Option<&Bound<'_, PyAny>>,
+    autojunk: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(0.0);
+    }
+
+    let (s1, s2, options) = py_prep_inputs(s1, s2, isjunk, autojunk, processor)?;
+    let norm_sim = normalized_similarity(&s1, &s2, &options, score_cutoff);
+
+    Ok(norm_sim)
+}
+
+pub fn normalized_similarity(
+    s1: &[u32],
+    s2: &[u32],
+    options: &MatcherOptions,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let maximum = s1.len() + s2.len();
+    let norm_sim = if maximum == 0 {
+        1.0
+    } else {
+        similarity(s1, s2, options, None) as f64 / maximum as f64
+    };
+
+    match score_cutoff {
+        Some(cutoff) if norm_sim < cutoff => 0.0,
+        _ => norm_sim,
+    }
+}
+
+/**
+Returns the matching blocks of s1 and s2, the same blocks
+``difflib.SequenceMatcher.get_matching_blocks`` returns.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+isjunk : callable, optional
+    Called with each element of s2, elements it returns True for are junk
+    and never start a matching block. Default is None, which treats no
+    element as junk.
+autojunk : bool, optional
+    Treat elements that make up more than 1% of s2 as junk if s2 has at
+    least 200 elements, like difflib. Default is True.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+
+Returns
+-------
+matching_blocks : list[MatchingBlock]
+    non overlapping blocks sorted by their position, followed by the
+    dummy block ``MatchingBlock(len1, len2, 0)``
+
+Examples
+--------
+\>>> from crustyfuzz.distance import ratcliff_obershelp
+\>>> ratcliff_obershelp.matching_blocks("abxcd", "abcd")
+[MatchingBlock(a=0, b=0, size=2), MatchingBlock(a=3, b=2, size=2), MatchingBlock(a=5, b=4, size=0)]
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "matching_blocks",
+    signature = (s1, s2, *, isjunk=None, autojunk=true, processor=None)
+)]
+pub fn py_matching_blocks(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    isjunk: Option<&Bound<'_, PyAny>>,
+    autojunk: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Vec<MatchingBlock>> {
+    let (s1, s2, options) = py_prep_inputs(s1, s2, isjunk, autojunk, processor)?;
+
+    Ok(matching_blocks(&s1, &s2, &options))
+}
+
+pub fn matching_blocks(s1: &[u32], s2: &[u32], options: &MatcherOptions) -> Vec<MatchingBlock> {
+    SequenceMatcher::new(s1, s2, options).matching_blocks()
+}
+
+/**
+Returns Opcodes describing how to turn s1 into s2, the same opcodes
+``difflib.SequenceMatcher.get_opcodes`` returns.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+isjunk : callable, optional
+    Called with each element of s2, elements it returns True for are junk
+    and never start a matching block. Default is None, which treats no
+    element as junk.
+autojunk : bool, optional
+    Treat elements that make up more than 1% of s2 as junk if s2 has at
+    least 200 elements, like difflib. Default is True.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+
+Returns
+-------
+opcodes : Opcodes
+    edit operations required to turn s1 into s2
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "opcodes",
+    signature = (s1, s2, *, isjunk=None, autojunk=true, processor=None)
+)]
+pub fn py_opcodes(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    isjunk: Option<&Bound<'_, PyAny>>,
+    autojunk: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Opcodes> {
+    let (s1, s2, options) = py_prep_inputs(s1, s2, isjunk, autojunk, processor)?;
+
+    Ok(opcodes(&s1, &s2, &options))
+}
+
+pub fn opcodes(s1: &[u32], s2: &[u32], options: &MatcherOptions) -> Opcodes {
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for block in matching_blocks(s1, s2, options) {
+        let tag = match (i < block.a, j < block.b) {
+            (true, true) => Some("replace"),
+            (true, false) => Some("delete"),
+            (false, true) => Some("insert"),
+            (false, false) => None,
+        };
+        if let Some(tag) = tag {
+            ops.push(Opcode {
+                tag: tag.to_string(),
+                src_start: i,
+                src_end: block.a,
+                dest_start: j,
+                dest_end: block.b,
+            });
+        }
+
+        (i, j) = (block.a + block.size, block.b + block.size);
+        if block.size > 0 {
+            ops.push(Opcode {
+                tag: "equal".to_string(),
+                src_start: block.a,
+                src_end: i,
+                dest_start: block.b,
+                dest_end: j,
+            });
+        }
+    }
+
+    Opcodes::new(s1.len(), s2.len(), ops)
+}
+
+/// Applies the processor and collects the elements of `s2` `isjunk` returns
+/// true for. `isjunk` is called once per distinct element, like difflib.
+#[cfg(feature = "python")]
+fn py_prep_inputs(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    isjunk: Option<&Bound<'_, PyAny>>,
+    autojunk: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+) -> PyResult<(Vec<u32>, Vec<u32>, MatcherOptions)> {
+    let (s1, s2) = match processor {
+        Some(proc) => (proc.call1((s1,))?, proc.call1((s2,))?),
+        None => (s1.to_owned(), s2.to_owned()),
+    };
+    let (seq1, seq2) = prep_inputs(&s1, &s2, None)?;
+
+    let mut options = MatcherOptions {
+        junk: HashSet::new(),
+        autojunk,
+    };
+    if let Some(isjunk) = isjunk {
+        let mut checked = HashSet::new();
+        for (elem, &code) in s2.iter()?.zip(&seq2) {
+            if checked.insert(code) && isjunk.call1((elem?,))?.is_truthy()? {
+                options.junk.insert(code);
+            }
+        }
+    }
+
+    Ok((seq1, seq2, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::to_seq;
+
+    fn blocks(s1: &str, s2: &str, options: &MatcherOptions) -> Vec<(usize, usize, usize)> {
+        matching_blocks(&to_seq(s1), &to_seq(s2), options)
+            .iter()
+            .map(|block| (block.a, block.b, block.size))
+            .collect()
+    }
+
+    #[test]
+    fn test_matching_blocks() {
+        let options = MatcherOptions::default();
+        // examples from the difflib documentation
+        assert_eq!(
+            blocks("abxcd", "abcd", &options),
+            vec![(0, 0, 2), (3, 2, 2), (5, 4, 0)]
+        );
+        assert_eq!(
+            blocks("qabxcd", "abycdf", &options),
+            vec![(1, 0, 2), (4, 3, 2), (6, 6, 0)]
+        );
+        assert_eq!(blocks("", "", &options), vec![(0, 0, 0)]);
+    }
+
+    #[test]
+    fn test_junk() {
+        // difflib: SequenceMatcher(lambda x: x == " ", " abcd", "abcd abcd")
+        let s1 = " abcd";
+        let s2 = "abcd abcd";
+        assert_eq!(
+            blocks(s1, s2, &MatcherOptions::default()),
+            vec![(0, 4, 5), (5, 9, 0)]
+        );
+
+        let options = MatcherOptions {
+            junk: HashSet::from([' ' as u32]),
+            autojunk: true,
+        };
+        assert_eq!(blocks(s1, s2, &options), vec![(1, 0, 4), (5, 9, 0)]);
+    }
+
+    #[test]
+    fn test_autojunk() {
+        // 'c' makes up more than 1% of s2, so it can only extend a match
+        let s2 = "ab".to_string() + &"c".repeat(200);
+        let options = MatcherOptions::default();
+        assert_eq!(blocks("abccc", &s2, &options), vec![(0, 0, 5), (5, 202, 0)]);
+        assert_eq!(blocks("ccc", &s2, &options), vec![(3, 202, 0)]);
+
+        let options = MatcherOptions {
+            autojunk: false,
+            ..options
+        };
+        assert_eq!(blocks("ccc", &s2, &options), vec![(0, 2, 3), (3, 202, 0)]);
+    }
+
+    #[test]
+    fn test_ratio() {
+        let options = MatcherOptions::default();
+        let sim = normalized_similarity(&to_seq("abcd"), &to_seq("bcde"), &options, None);
+        assert_eq!(sim, 0.75);
+        assert_eq!(normalized_similarity(&[], &[], &options, None), 1.0);
+        assert_eq!(
+            distance(&to_seq("abcd"), &to_seq("bcde"), &options, None),
+            2
+        );
+        assert_eq!(
+            distance(&to_seq("abcd"), &to_seq("bcde"), &options, Some(1)),
+            2
+        );
+    }
+
+    #[test]
+    fn test_opcodes() {
+        let options = MatcherOptions::default();
+        let ops: Vec<(String, usize, usize, usize, usize)> =
+            opcodes(&to_seq("qabxcd"), &to_seq("abycdf"), &options)
+                .into_iter()
+                .map(|op| (op.tag, op.src_start, op.src_end, op.dest_start, op.dest_end))
+                .collect();
+        let expected = [
+            ("delete", 0, 1, 0, 0),
+            ("equal", 1, 3, 0, 2),
+            ("replace", 3, 4, 2, 3),
+            ("equal", 4, 6, 3, 5),
+            ("insert", 6, 6, 5, 6),
+        ];
+        assert_eq!(ops.len(), expected.len());
+        for (op, (tag, a1, a2, b1, b2)) in ops.iter().zip(expected) {
+            assert_eq!(*op, (tag.to_string(), a1, a2, b1, b2));
+        }
+    }
+}
@@ -0,0 +1,792 @@
+#[cfg(feature = "python")]
+use crate::distance::models::Opcodes;
+use crate::distance::models::{Editop, Editops, ScoreAlignment};
+#[cfg(feature = "python")]
+use crate::distance::prep_inputs;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+/// Scoring scheme in the form `(match, mismatch, gap_open, gap_extend)`.
+/// Matches are rewarded, everything else is penalized. A gap of length `k`
+/// costs `gap_open + (k - 1) * gap_extend`.
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weights(pub usize, pub usize, pub usize, pub usize);
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights(1, 1, 1, 1)
+    }
+}
+
+const UNREACHABLE: i64 = i64::MIN / 4;
+
+// sources of a cell in the traceback matrix
+const FROM_START: u8 = 0;
+const FROM_DIAGONAL: u8 = 1;
+const FROM_INSERT: u8 = 2;
+const FROM_DELETE: u8 = 3;
+const SOURCE_MASK: u8 = 3;
+const INSERT_EXTENDED: u8 = 1 << 2;
+const DELETE_EXTENDED: u8 = 1 << 3;
+
+/// Gotoh's recurrences for a single cell. `diagonal` is the best local
+/// score of the previous cell on the diagonal, `insert` and `delete` the
+/// scores of alignments ending in a gap. Returns the best local score of the
+/// cell and its source.
+#[inline]
+fn best_cell(diagonal: i64, insert: i64, delete: i64) -> (i64, u8) {
+    let mut best = (0, FROM_START);
+    if diagonal > best.0 {
+        best = (diagonal, FROM_DIAGONAL);
+    }
+    if delete > best.0 {
+        best = (delete, FROM_DELETE);
+    }
+    if insert > best.0 {
+        best = (insert, FROM_INSERT);
+    }
+    best
+}
+
+#[inline]
+fn substitution(a: u32, b: u32, weights: &Weights) -> i64 {
+    if a == b {
+        weights.0 as i64
+    } else {
+        -(weights.1 as i64)
+    }
+}
+
+/// Best local alignment score with memory linear in the length of s2.
+fn local_score(s1: &[u32], s2: &[u32], weights: &Weights) -> usize {
+    let (gap_open, gap_extend) = (weights.2 as i64, weights.3 as i64);
+    let mut scores = vec![0i64; s2.len() + 1];
+    let mut deletes = vec![UNREACHABLE; s2.len() + 1];
+    let mut best = 0;
+
+    for &ch1 in s1 {
+        let mut diagonal = 0;
+        let mut insert = UNREACHABLE;
+        for (j, &ch2) in s2.iter().enumerate() {
+            insert = i64::max(scores[j] - gap_open, insert - gap_extend);
+            deletes[j + 1] = i64::max(scores[j + 1] - gap_open, deletes[j + 1] - gap_extend);
+
+            let (score, _) = best_cell(
+                diagonal + substitution(ch1, ch2, weights),
+                insert,
+                deletes[j + 1],
+            );
+            diagonal = scores[j + 1];
+            scores[j + 1] = score;
+            best = i64::max(best, score);
+        }
+    }
+
+    best as usize
+}
+
+/// Fills the traceback matrix and returns it together with the best score
+/// and the cell it ends in. Ties are broken by the earliest end in s1 and
+/// then in s2.
+fn traceback_matrix(s1: &[u32], s2: &[u32], weights: &Weights) -> (Vec<u8>, i64, usize, usize) {
+    let (gap_open, gap_extend) = (weights.2 as i64, weights.3 as i64);
+    let cols = s2.len() + 1;
+    let mut sources = vec![FROM_START; (s1.len() + 1) * cols];
+    let mut scores = vec![0i64; cols];
+    let mut deletes = vec![UNREACHABLE; cols];
+    let mut best = (0, 0, 0);
+
+    for (i, &ch1) in s1.iter().enumerate() {
+        let mut diagonal = 0;
+        let mut insert = UNREACHABLE;
+        for (j, &ch2) in s2.iter().enumerate() {
+            let cell = (i + 1) * cols + j + 1;
+
+            let insert_open = scores[j] - gap_open;
+            if insert_open < insert - gap_extend {
+                sources[cell] |= INSERT_EXTENDED;
+                insert -= gap_extend;
+            } else {
+                insert = insert_open;
+            }
+
+            let delete_open = scores[j + 1] - gap_open;
+            if delete_open < deletes[j + 1] - gap_extend {
+                sources[cell] |= DELETE_EXTENDED;
+                deletes[j + 1] -= gap_extend;
+            } else {
+                deletes[j + 1] = delete_open;
+            }
+
+            let (score, source) = best_cell(
+                diagonal + substitution(ch1, ch2, weights),
+                insert,
+                deletes[j + 1],
+            );
+            sources[cell] |= source;
+            diagonal = scores[j + 1];
+            scores[j + 1] = score;
+
+            if score > best.0 {
+                best = (score, i + 1, j + 1);
+            }
+        }
+    }
+
+    (sources, best.0, best.1, best.2)
+}
+
+/// Best local alignment of s1 and s2 together with the edit operations that
+/// turn the aligned part of s1 into the aligned part of s2. The positions of
+/// the edit operations are relative to the aligned parts.
+fn local_alignment(s1: &[u32], s2: &[u32], weights: &Weights) -> (ScoreAlignment, Editops) {
+    let (sources, score, src_end, dest_end) = traceback_matrix(s1, s2, weights);
+    let cols = s2.len() + 1;
+
+    let mut editop_vec = Vec::new();
+    let (mut i, mut j) = (src_end, dest_end);
+    let mut state = FROM_DIAGONAL;
+    if score > 0 {
+        loop {
+            let source = sources[i * cols + j];
+            match state {
+                FROM_INSERT => {
+                    j -= 1;
+                    editop_vec.push(("insert", i, j));
+                    if source & INSERT_EXTENDED == 0 {
+                        state = FROM_DIAGONAL;
+                    }
+                }
+                FROM_DELETE => {
+                    i -= 1;
+                    editop_vec.push(("delete", i, j));
+                    if source & DELETE_EXTENDED == 0 {
+                        state = FROM_DIAGONAL;
+                    }
+                }
+                _ => match source & SOURCE_MASK {
+                    FROM_START => break,
+                    FROM_DIAGONAL => {
+                        i -= 1;
+                        j -= 1;
+                        if s1[i] != s2[j] {
+                            editop_vec.push(("replace", i, j));
+                        }
+                    }
+                    gap => state = gap,
+                },
+            }
+        }
+    }
+
+    let (src_start, dest_start) = (i, j);
+    let editop_vec = editop_vec
+        .into_iter()
+        .rev()
+        .map(|(tag, src_pos, dest_pos)| Editop {
+            tag: tag.to_string(),
+            src_pos: src_pos - src_start,
+            dest_pos: dest_pos - dest_start,
+        })
+        .collect();
+
+    let alignment = ScoreAlignment {
+        score: score as f64,
+        src_start,
+        src_end,
+        dest_start,
+        dest_end,
+    };
+    let editops = Editops::new(src_end - src_start, dest_end - dest_start, editop_vec);
+
+    (alignment, editops)
+}
+
+fn smith_waterman_maximum(s1: &[u32], s2: &[u32], weights: &Weights) -> usize {
+    usize::min(s1.len(), s2.len()) * weights.0
+}
+
+/**
+Calculates the Smith-Waterman local alignment score of s1 and s2. This
+is the score of the best alignment of a part of s1 with a part of s2.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int, int] or None, optional
+    The scoring scheme in the form (match, mismatch, gap_open, gap_extend).
+    Matches are rewarded with match, while mismatches and gaps are
+    penalized. A gap of length k costs gap_open + (k - 1) * gap_extend.
+    Default is (1, 1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Minimum similarity between s1 and s2, that is considered as a result.
+    If the similarity is smaller than score_cutoff, 0 is returned instead.
+    Default is None, which deactivates this behaviour.
+
+Returns
+-------
+similarity : int
+    local alignment score of s1 and s2
+
+Examples
+--------
+\>>> from crustyfuzz.distance import smith_waterman
+\>>> smith_waterman.similarity("invoice no A1B2-77 total", "A1B2-77")
+7
+
+Rewarding matches higher makes short mismatches inside of the aligned
+part acceptable:
+
+\>>> smith_waterman.similarity("ref A1B2-77", "ref A1 B2-77", weights=(2, 1, 2, 1))
+20
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "similarity",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
+)]
+pub fn py_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let sim = similarity(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
+
+    Ok(sim)
+}
+
+pub fn similarity(s1: &[u32], s2: &[u32], weights: Weights, score_cutoff: Option<usize>) -> usize {
+    let cutoff = score_cutoff.unwrap_or(0);
+    let sim = if smith_waterman_maximum(s1, s2, &weights) < cutoff {
+        0
+    } else {
+        local_score(s1, s2, &weights)
+    };
+
+    match score_cutoff {
+        Some(cutoff) if sim >= cutoff => sim,
+        Some(_) => 0,
+        None => sim,
+    }
+}
+
+/**
+Calculates the Smith-Waterman distance in the range [0, max].
+
+This is calculated as ``max - similarity``, where max is the score of
+the shorter sequence matching completely.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int, int] or None, optional
+    The scoring scheme in the form (match, mismatch, gap_open, gap_extend).
+    Default is (1, 1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Maximum distance between s1 and s2, that is
+    considered as a result. If the distance is bigger than score_cutoff,
+    score_cutoff + 1 is returned instead. Default is None, which deactivates
+    this behaviour.
+
+Returns
+-------
+distance : int
+    distance between s1 and s2
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "distance",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
+)]
+pub fn py_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<usize> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let dist = distance(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
+
+    Ok(dist)
+}
+
+pub fn distance(s1: &[u32], s2: &[u32], weights: Weights, score_cutoff: Option<usize>) -> usize {
+    let maximum = smith_waterman_maximum(s1, s2, &weights);
+    let sim_cutoff = score_cutoff.map(|cutoff| maximum.saturating_sub(cutoff));
+    let sim = similarity(s1, s2, weights, sim_cutoff);
+    let dist = maximum - sim;
+
+    match score_cutoff {
+        Some(cutoff) if dist <= cutoff => dist,
+        Some(cutoff) => cutoff + 1,
+        None => dist,
+    }
+}
+
+/**
+Calculates a normalized Smith-Waterman distance in the range [1, 0].
+
+This is calculated as ``distance / max``, where max is the score of the
+shorter sequence matching completely.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int, int] or None, optional
+    The scoring scheme in the form (match, mismatch, gap_open, gap_extend).
+    Default is (1, 1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_dist > score_cutoff 1.0 is returned instead. Default is 1.0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_dist : float
+    normalized distance between s1 and s2 as a float between 0 and 1.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_distance",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(1.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let norm_dist = normalized_distance(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
+
+    Ok(norm_dist)
+}
+
+pub fn normalized_distance(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let norm_sim = normalized_similarity(s1, s2, weights, None);
+    let norm_dist = 1.0 - norm_sim;
+
+    match score_cutoff {
+        Some(cutoff) if norm_dist <= cutoff => norm_dist,
+        Some(_) => 1.0,
+        None => norm_dist,
+    }
+}
+
+/**
+Calculates a normalized Smith-Waterman similarity in the range [0, 1].
+
+This is calculated as ``similarity / max``, where max is the score of
+the shorter sequence matching completely.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int, int] or None, optional
+    The scoring scheme in the form (match, mismatch, gap_open, gap_extend).
+    Default is (1, 1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For norm_sim < score_cutoff 0 is returned instead. Default is 0,
+    which deactivates this behaviour.
+
+Returns
+-------
+norm_sim : float
+    normalized similarity between s1 and s2 as a float between 0 and 1.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_similarity",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
+)]
+pub fn py_normalized_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(0.0);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+    let norm_sim = normalized_similarity(&s1, &s2, weights.unwrap_or_default(), score_cutoff);
+
+    Ok(norm_sim)
+}
+
+pub fn normalized_similarity(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    let maximum = smith_waterman_maximum(s1, s2, &weights) as f64;
+    let sim = similarity(s1, s2, weights, None) as f64;
+    let norm_sim = if maximum == 0.0 { 1.0 } else { sim / maximum };
+
+    match score_cutoff {
+        Some(cutoff) if norm_sim >= cutoff => norm_sim,
+        Some(_) => 0.0,
+        None => norm_sim,
+    }
+}
+
+/**
+Searches the best local alignment of s1 and s2 and returns where it is
+located in both sequences.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int, int] or None, optional
+    The scoring scheme in the form (match, mismatch, gap_open, gap_extend).
+    Default is (1, 1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : int, optional
+    Minimum score of the alignment. If the best alignment has a lower
+    score, None is returned instead. Default is None, which deactivates
+    this behaviour.
+
+Returns
+-------
+alignment : ScoreAlignment, optional
+    alignment between s1 and s2 with the local alignment score as score.
+    When several alignments have the best score, the one that ends first
+    in s1 is returned.
+
+Examples
+--------
+\>>> from crustyfuzz.distance import smith_waterman
+\>>> s1 = "invoice no A1B2-77 total"
+\>>> smith_waterman.alignment(s1, "A1B2-77")
+ScoreAlignment(score=7.0, src_start=11, src_end=18, dest_start=0, dest_end=7)
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "alignment",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None, score_cutoff=None)
+)]
+pub fn py_alignment(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<usize>,
+) -> PyResult<Option<ScoreAlignment>> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(None);
+    }
+
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+
+    Ok(alignment(
+        &s1,
+        &s2,
+        weights.unwrap_or_default(),
+        score_cutoff,
+    ))
+}
+
+pub fn alignment(
+    s1: &[u32],
+    s2: &[u32],
+    weights: Weights,
+    score_cutoff: Option<usize>,
+) -> Option<ScoreAlignment> {
+    if smith_waterman_maximum(s1, s2, &weights) < score_cutoff.unwrap_or(0) {
+        return None;
+    }
+
+    let (res, _) = local_alignment(s1, s2, &weights);
+    match score_cutoff {
+        Some(cutoff) if res.score < cutoff as f64 => None,
+        _ => Some(res),
+    }
+}
+
+/**
+Return Editops describing how to turn the aligned part of s1 into the
+aligned part of s2. The positions are relative to the start of the
+alignment returned by `alignment`.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int, int] or None, optional
+    The scoring scheme in the form (match, mismatch, gap_open, gap_extend).
+    Default is (1, 1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+
+Returns
+-------
+editops : Editops
+    edit operations required to turn the aligned part of s1 into the
+    aligned part of s2
+
+Examples
+--------
+\>>> from crustyfuzz.distance import smith_waterman
+\>>> s1 = "ref A1B2-77"
+\>>> s2 = "A1 B2-77"
+\>>> res = smith_waterman.alignment(s1, s2, weights=(2, 1, 2, 1))
+\>>> ops = smith_waterman.editops(s1, s2, weights=(2, 1, 2, 1))
+\>>> ops.apply(s1[res.src_start:res.src_end], s2[res.dest_start:res.dest_end])
+'A1 B2-77'
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "editops",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None)
+)]
+pub fn py_editops(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Editops> {
+    let (s1, s2) = prep_inputs(s1, s2, processor)?;
+
+    Ok(editops(&s1, &s2, weights.unwrap_or_default()))
+}
+
+pub fn editops(s1: &[u32], s2: &[u32], weights: Weights) -> Editops {
+    let (_, editops) = local_alignment(s1, s2, &weights);
+    editops
+}
+
+/**
+Return Opcodes describing how to turn the aligned part of s1 into the
+aligned part of s2. The positions are relative to the start of the
+alignment returned by `alignment`.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+weights : Tuple[int, int, int, int] or None, optional
+    The scoring scheme in the form (match, mismatch, gap_open, gap_extend).
+    Default is (1, 1, 1, 1).
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+
+Returns
+-------
+opcodes : Opcodes
+    edit operations required to turn the aligned part of s1 into the
+    aligned part of s2
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "opcodes",
+    signature = (s1, s2, *, weights=Weights(1, 1, 1, 1), processor=None)
+)]
+pub fn py_opcodes(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    weights: Option<Weights>,
+    processor: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Opcodes> {
+    let editops = py_editops(s1, s2, weights, processor)?;
+    Ok(editops.as_opcodes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::{pseudo_random_seq, to_seq};
+
+    /// Gotoh's recurrences with full matrices.
+    fn score_naive(s1: &[u32], s2: &[u32], weights: &Weights) -> i64 {
+        let (rows, cols) = (s1.len() + 1, s2.len() + 1);
+        let (gap_open, gap_extend) = (weights.2 as i64, weights.3 as i64);
+        let mut h = vec![vec![0i64; cols]; rows];
+        let mut e = vec![vec![UNREACHABLE; cols]; rows];
+        let mut f = vec![vec![UNREACHABLE; cols]; rows];
+        let mut best = 0;
+        for i in 1..rows {
+            for j in 1..cols {
+                e[i][j] = i64::max(h[i][j - 1] - gap_open, e[i][j - 1] - gap_extend);
+                f[i][j] = i64::max(h[i - 1][j] - gap_open, f[i - 1][j] - gap_extend);
+                let diagonal = h[i - 1][j - 1] + substitution(s1[i - 1], s2[j - 1], weights);
+                h[i][j] = [0, diagonal, e[i][j], f[i][j]].into_iter().max().unwrap();
+                best = best.max(h[i][j]);
+            }
+        }
+        best
+    }
+
+    /// Scores the alignment described by the editops.
+    fn rescore(s1: &[u32], s2: &[u32], editops: &Editops, weights: &Weights) -> i64 {
+        let ops = editops.as_list();
+        let mut score = 0;
+        let (mut i, mut j) = (0, 0);
+        let mut last_gap: Option<(&str, usize, usize)> = None;
+        let mut op_iter = ops.iter().peekable();
+        while i < s1.len() || j < s2.len() {
+            match op_iter.peek() {
+                Some(op) if op.src_pos == i && op.dest_pos == j => {
+                    let tag = op.tag.as_str();
+                    match tag {
+                        "replace" => {
+                            score -= weights.1 as i64;
+                            i += 1;
+                            j += 1;
+                        }
+                        _ => {
+                            let extends = matches!(last_gap, Some((t, li, lj)) if t == tag && li == i && lj == j);
+                            score -= if extends { weights.3 } else { weights.2 } as i64;
+                            if tag == "insert" {
+                                j += 1;
+                            } else {
+                                i += 1;
+                            }
+                            last_gap = Some((tag, i, j));
+                            op_iter.next();
+                            continue;
+                        }
+                    }
+                    op_iter.next();
+                }
+                _ => {
+                    assert_eq!(s1[i], s2[j]);
+                    score += weights.0 as i64;
+                    i += 1;
+                    j += 1;
+                }
+            }
+            last_gap = last_gap.filter(|&(_, li, lj)| li == i && lj == j);
+        }
+        score
+    }
+
+    #[test]
+    fn test_similarity() {
+        let s1 = to_seq("invoice no A1B2-77 total");
+        let s2 = to_seq("A1B2-77");
+        let weights = Weights::default();
+        assert_eq!(similarity(&s1, &s2, weights, None), 7);
+        assert_eq!(similarity(&s1, &s2, weights, Some(8)), 0);
+        assert_eq!(distance(&s1, &s2, weights, None), 0);
+        assert_eq!(normalized_similarity(&s1, &s2, weights, None), 1.0);
+
+        let res = alignment(&s1, &s2, weights, None).unwrap();
+        assert_eq!(res.score, 7.0);
+        assert_eq!((res.src_start, res.src_end), (11, 18));
+        assert_eq!((res.dest_start, res.dest_end), (0, 7));
+        assert!(editops(&s1, &s2, weights).is_empty());
+    }
+
+    #[test]
+    fn test_affine_gap() {
+        let s1 = to_seq("ref A1B2-77");
+        let s2 = to_seq("ref A1 B2-77");
+        // the gap costs more than the 5 matches behind it
+        assert_eq!(similarity(&s1, &s2, Weights(1, 1, 6, 6), None), 6);
+        assert_eq!(similarity(&s1, &s2, Weights(2, 1, 2, 1), None), 20);
+
+        let ops = editops(&s1, &s2, Weights(2, 1, 2, 1));
+        let ops = ops.as_list();
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].tag, "insert");
+        assert_eq!((ops[0].src_pos, ops[0].dest_pos), (6, 6));
+    }
+
+    #[test]
+    fn test_empty() {
+        let weights = Weights::default();
+        assert_eq!(similarity(&[], &[], weights, None), 0);
+        assert_eq!(normalized_similarity(&[], &[], weights, None), 1.0);
+        let res = alignment(&to_seq("abc"), &to_seq("xyz"), weights, None).unwrap();
+        assert_eq!(res.score, 0.0);
+        assert_eq!((res.src_start, res.src_end), (0, 0));
+    }
+
+    #[test]
+    fn test_random() {
+        let lens = [1, 2, 5, 17, 40];
+        let all_weights = [
+            Weights(1, 1, 1, 1),
+            Weights(2, 1, 2, 1),
+            Weights(3, 2, 4, 1),
+            Weights(1, 3, 1, 2),
+        ];
+        for (seed, &len1) in lens.iter().enumerate() {
+            for &len2 in &lens {
+                for weights in all_weights {
+                    let s1 = pseudo_random_seq(seed as u64, len1);
+                    let s2 = pseudo_random_seq(seed as u64 + 100, len2);
+                    let expected = score_naive(&s1, &s2, &weights);
+                    assert_eq!(similarity(&s1, &s2, weights, None) as i64, expected);
+
+                    let (res, ops) = local_alignment(&s1, &s2, &weights);
+                    assert_eq!(res.score as i64, expected);
+                    let src = &s1[res.src_start..res.src_end];
+                    let dest = &s2[res.dest_start..res.dest_end];
+                    assert_eq!(rescore(src, dest, &ops, &weights), expected);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use crate::distance::qgram::{distance_with, similarity_with, QGramOptions, QGramStats};
+#[cfg(feature = "python")]
+use crate::distance::qgram::{py_distance_with, py_similarity_with};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+fn coefficient(stats: &QGramStats) -> f64 {
+    2.0 * stats.intersection as f64 / (stats.len1 + stats.len2) as f64
+}
+
+/**
+Calculates the Sørensen-Dice similarity in the range [0, 1].
+
+This is twice the number of q-grams both sequences share divided by
+the total number of q-grams of both sequences.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+q : int, optional
+    Length of the q-grams. Default is 2.
+pad : str, optional
+    Character that is added q - 1 times on both sides of the strings.
+    Default is None, which deactivates this behaviour.
+multiset : bool, optional
+    Count repeated q-grams instead of comparing the sets of q-grams.
+    Default is False.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For similarity < score_cutoff 0 is returned instead. Default is 0,
+    which deactivates this behaviour.
+
+Returns
+-------
+similarity : float
+    similarity between s1 and s2 as a float between 0 and 1.0
+
+Examples
+--------
+\>>> from crustyfuzz.distance import sorensen_dice
+\>>> sorensen_dice.similarity("night", "nacht")
+0.25
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "similarity",
+    signature = (s1, s2, *, q=2, pad=None, multiset=false, processor=None, score_cutoff=None)
+)]
+#[allow(clippy::too_many_arguments)]
+pub fn py_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    q: usize,
+    pad: Option<char>,
+    multiset: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    py_similarity_with(
+        s1,
+        s2,
+        q,
+        pad,
+        multiset,
+        processor,
+        score_cutoff,
+        coefficient,
+    )
+}
+
+pub fn similarity(s1: &[u32], s2: &[u32], options: &QGramOptions, score_cutoff: f64) -> f64 {
+    similarity_with(s1, s2, options, score_cutoff, coefficient)
+}
+
+/**
+Calculates the normalized Sørensen-Dice similarity in the range [0, 1].
+
+This is the same as the Sørensen-Dice similarity.
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_similarity",
+    signature = (s1, s2, *, q=2, pad=None, multiset=false, processor=None, score_cutoff=None)
+)]
+#[allow(clippy::too_many_arguments)]
+pub fn py_normalized_similarity(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    q: usize,
+    pad: Option<char>,
+    multiset: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    py_similarity(s1, s2, q, pad, multiset, processor, score_cutoff)
+}
+
+/**
+Calculates the Sørensen-Dice distance in the range [1, 0].
+
+This is calculated as ``1 - similarity``.
+
+Parameters
+----------
+s1 : Sequence[Hashable]
+    First string to compare.
+s2 : Sequence[Hashable]
+    Second string to compare.
+q : int, optional
+    Length of the q-grams. Default is 2.
+pad : str, optional
+    Character that is added q - 1 times on both sides of the strings.
+    Default is None, which deactivates this behaviour.
+multiset : bool, optional
+    Count repeated q-grams instead of comparing the sets of q-grams.
+    Default is False.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 1.0.
+    For distance > score_cutoff 1.0 is returned instead. Default is 1.0,
+    which deactivates this behaviour.
+
+Returns
+-------
+distance : float
+    distance between s1 and s2 as a float between 1.0 and 0.0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "distance",
+    signature = (s1, s2, *, q=2, pad=None, multiset=false, processor=None, score_cutoff=None)
+)]
+#[allow(clippy::too_many_arguments)]
+pub fn py_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    q: usize,
+    pad: Option<char>,
+    multiset: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    py_distance_with(
+        s1,
+        s2,
+        q,
+        pad,
+        multiset,
+        processor,
+        score_cutoff,
+        coefficient,
+    )
+}
+
+pub fn distance(s1: &[u32], s2: &[u32], options: &QGramOptions, score_cutoff: Option<f64>) -> f64 {
+    distance_with(s1, s2, options, score_cutoff, coefficient)
+}
+
+/**
+Calculates the normalized Sørensen-Dice distance in the range [1, 0].
+
+This is the same as the Sørensen-Dice distance.
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "normalized_distance",
+    signature = (s1, s2, *, q=2, pad=None, multiset=false, processor=None, score_cutoff=None)
+)]
+#[allow(clippy::too_many_arguments)]
+pub fn py_normalized_distance(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    q: usize,
+    pad: Option<char>,
+    multiset: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    py_distance(s1, s2, q, pad, multiset, processor, score_cutoff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::to_seq;
+
+    #[test]
+    fn test_similarity() {
+        let options = QGramOptions::default();
+        let sim = similarity(&to_seq("abcd"), &to_seq("bcd"), &options, 0.0);
+        assert_eq!(sim, 0.8);
+        let sim = similarity(&to_seq("abcd"), &to_seq("bcd"), &options, 0.9);
+        assert_eq!(sim, 0.0);
+    }
+}
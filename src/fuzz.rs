@@ -1,13 +1,31 @@
-use crate::common::conv_sequences;
 use crate::common::models::{Token, TokenIterator, TokenSequence};
+#[cfg(feature = "python")]
+use crate::common::with_views;
+use crate::common::SeqElem;
+#[cfg(feature = "python")]
+use crate::distance::indel::py_normalized_similarity as indel_py_normalized_similarity;
 use crate::distance::indel::{
+    block_distance as indel_block_distance,
     block_normalized_similarity as indel_block_normalized_similarity, distance as indel_distance,
     normalized_similarity as indel_normalized_similarity,
-    py_normalized_similarity as indel_py_normalized_similarity,
+};
+#[cfg(feature = "jaro")]
+use crate::distance::jaro::similarity as jaro_similarity;
+#[cfg(feature = "jaro")]
+use crate::distance::jaro_winkler::similarity as jaro_winkler_similarity;
+#[cfg(feature = "levenshtein")]
+use crate::distance::levenshtein::{
+    normalized_similarity as levenshtein_normalized_similarity, Weights as LevenshteinWeights,
 };
 use crate::distance::models::ScoreAlignment;
+use crate::distance::PatternMatchVector;
+#[cfg(feature = "python")]
+use crate::distance::{conv_views, get_scorer_flags, ScorerFlag};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
-use std::collections::{HashMap, HashSet};
+#[cfg(feature = "python")]
+use std::collections::HashMap;
+use std::collections::HashSet;
 
 fn norm_distance(dist: usize, lensum: usize, score_cutoff: f64) -> f64 {
     let score = if lensum != 0 {
@@ -22,17 +40,17 @@ fn norm_distance(dist: usize, lensum: usize, score_cutoff: f64) -> f64 {
     }
 }
 
-fn split_into_tokens(seq: &[u32]) -> TokenIterator {
+fn split_into_tokens<T: SeqElem>(seq: &[T]) -> TokenIterator<'_, T> {
     TokenIterator::new(seq)
 }
 
-fn sort_tokens(seq: &[u32]) -> Vec<u32> {
+fn sort_tokens<T: SeqElem>(seq: &[T]) -> Vec<u32> {
     if seq.is_empty() {
         return Vec::new();
     }
 
-    let mut tokens: Vec<Token> = split_into_tokens(seq).collect();
-    tokens.sort_by_key(|t| Vec::from_iter(t.chars.iter().cloned()));
+    let mut tokens: Vec<Token<T>> = split_into_tokens(seq).collect();
+    tokens.sort();
 
     TokenSequence::new(tokens).join()
 }
@@ -64,6 +82,7 @@ Examples
 >>> fuzz::ratio(Some("this is a test"), Some("this is a test!"), None, None)
 96.55171966552734
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(name = "ratio", signature = (s1, s2, *, processor=None, score_cutoff=None))]
 pub fn py_ratio(
@@ -82,6 +101,10 @@ pub fn py_ratio(
     Ok(score * 100.0)
 }
 
+pub fn ratio<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2], score_cutoff: f64) -> f64 {
+    indel_normalized_similarity(s1, s2, Some(score_cutoff / 100.0)) * 100.0
+}
+
 /**
 Searches for the optimal alignment of the shorter string in the
 longer string and returns the fuzz.ratio for this alignment.
@@ -118,30 +141,19 @@ short needle (length ≤ 64):
     case performance of ``O(NM)``.
 
 long needle (length > 64):
-    For long needles a similar implementation to FuzzyWuzzy is used.
-    This implementation only considers alignments which start at one
-    of the longest common substrings. This results in a worst case performance
-    of ``O(N[N/64]M)``. However usually most of the alignments can be skipped.
-    The following Python code shows the concept:
-
-    .. code-block:: python
-
-        blocks = SequenceMatcher(None, needle, longer, False).get_matching_blocks()
-        score = 0
-        for block in blocks:
-            long_start = block[1] - block[0] if (block[1] - block[0]) > 0 else 0
-            long_end = long_start + len(shorter)
-            long_substr = longer[long_start:long_end]
-            score = max(score, fuzz.ratio(needle, long_substr))
-
-    This is a lot faster than checking all possible alignments. However it
-    only finds one of the best alignments and not necessarily the optimal one.
+    For long needles the windows of the longer string are bisected. Moving
+    the window by one character changes the Indel distance by at most 2, so
+    the distances of two windows bound the best distance of all windows
+    between them, and these windows are only scored when they could improve
+    the result. It is guaranteed to find the optimal alignment as well, while
+    usually most of the alignments can be skipped.
 
 Examples
 --------
 >>> fuzz.partial_ratio("this is a test", "this is a test!")
 100.0
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "partial_ratio",
@@ -161,7 +173,7 @@ pub fn py_partial_ratio(
     }
 }
 
-pub fn partial_ratio(s1: &[u32], s2: &[u32], score_cutoff: f64) -> f64 {
+pub fn partial_ratio<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2], score_cutoff: f64) -> f64 {
     let alignment = partial_ratio_alignment(s1, s2, score_cutoff);
     match alignment {
         Some(alignment) => alignment.score,
@@ -173,7 +185,11 @@ pub fn partial_ratio(s1: &[u32], s2: &[u32], score_cutoff: f64) -> f64 {
 implementation of partial_ratio for needles <= 64. assumes s1 is already the
 shorter string
 */
-fn partial_ratio_short_needle(s1: &[u32], s2: &[u32], mut score_cutoff: f64) -> ScoreAlignment {
+fn partial_ratio_short_needle<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    mut score_cutoff: f64,
+) -> ScoreAlignment {
     if s1.is_empty() {
         return ScoreAlignment {
             score: 0.0,
@@ -187,7 +203,7 @@ fn partial_ratio_short_needle(s1: &[u32], s2: &[u32], mut score_cutoff: f64) ->
     let len1 = s1.len();
     let len2 = s2.len();
     let mut s1_char_set = HashSet::with_capacity(len1);
-    s1_char_set.extend(s1.iter().cloned());
+    s1_char_set.extend(s1.iter().map(|c| c.value()));
 
     let mut res = ScoreAlignment {
         score: 0.0,
@@ -197,16 +213,10 @@ fn partial_ratio_short_needle(s1: &[u32], s2: &[u32], mut score_cutoff: f64) ->
         dest_end: len1,
     };
 
-    let shift = 128 - len1;
-    let mut block = HashMap::with_capacity(len1);
-    let mut x = 1u128 << shift;
-    for &ch1 in s1 {
-        block.entry(ch1).and_modify(|e| *e |= &x).or_insert(x);
-        x <<= 1;
-    }
+    let block = PatternMatchVector::new(s1);
 
     for i in 1..len1 {
-        let substr_last = s2[i - 1];
+        let substr_last = s2[i - 1].value();
         if !s1_char_set.contains(&substr_last) {
             continue;
         }
@@ -226,7 +236,7 @@ fn partial_ratio_short_needle(s1: &[u32], s2: &[u32], mut score_cutoff: f64) ->
 
     let window_end = len2 - len1;
     for i in 0..window_end {
-        let substr_last = s2[i + len1 - 1];
+        let substr_last = s2[i + len1 - 1].value();
         if !s1_char_set.contains(&substr_last) {
             continue;
         }
@@ -246,7 +256,7 @@ fn partial_ratio_short_needle(s1: &[u32], s2: &[u32], mut score_cutoff: f64) ->
     }
 
     for i in window_end..len2 {
-        let substr_first = s2[i];
+        let substr_first = s2[i].value();
         if !s1_char_set.contains(&substr_first) {
             continue;
         }
@@ -268,6 +278,157 @@ fn partial_ratio_short_needle(s1: &[u32], s2: &[u32], mut score_cutoff: f64) ->
     res
 }
 
+/**
+implementation of partial_ratio for needles > 64. assumes s1 is already the
+shorter string
+
+Instead of scoring every window of s2 with the length of s1, the windows are
+bisected. The indel distance changes by at most 2 when the window is moved by
+one character, so the distances of the outer windows of a range bound the best
+distance inside of it and ranges that can't beat the best window are skipped.
+*/
+fn partial_ratio_long_needle<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    mut score_cutoff: f64,
+) -> ScoreAlignment {
+    let len1 = s1.len();
+    let len2 = s2.len();
+    let mut s1_char_set = HashSet::with_capacity(len1);
+    s1_char_set.extend(s1.iter().map(|c| c.value()));
+
+    let mut res = ScoreAlignment {
+        score: 0.0,
+        src_start: 0,
+        src_end: len1,
+        dest_start: 0,
+        dest_end: len1,
+    };
+
+    let block = PatternMatchVector::new(s1);
+
+    if len2 > len1 {
+        let maximum = 2 * len1;
+        // smallest distance with a score below the cutoff
+        let mut cutoff_dist = ((maximum as f64 * (1.0 - score_cutoff)).floor() as usize)
+            .saturating_sub(1)
+            .min(maximum + 1);
+        while cutoff_dist <= maximum && 1.0 - cutoff_dist as f64 / maximum as f64 >= score_cutoff {
+            cutoff_dist += 1;
+        }
+        let mut best_dist = usize::MAX;
+        let mut scores = vec![usize::MAX; len2 - len1];
+        let mut windows = vec![(0, len2 - len1 - 1)];
+
+        while !windows.is_empty() {
+            let mut new_windows = Vec::new();
+            for (first, last) in windows {
+                for start in [first, last] {
+                    if scores[start] != usize::MAX {
+                        continue;
+                    }
+                    scores[start] =
+                        indel_block_distance(&block, s1, &s2[start..start + len1], None) as usize;
+                    if scores[start] < cutoff_dist {
+                        cutoff_dist = scores[start];
+                        best_dist = scores[start];
+                        res.dest_start = start;
+                        res.dest_end = start + len1;
+                        if best_dist == 0 {
+                            res.score = 100.0;
+                            return res;
+                        }
+                    }
+                }
+
+                let cell_diff = last - first;
+                if cell_diff <= 1 {
+                    continue;
+                }
+
+                // half of the shifts, that are not needed for the known edits,
+                // can improve the distance by 2
+                let known_edits = scores[first].abs_diff(scores[last]);
+                let max_improvement = cell_diff.saturating_sub(known_edits / 2) / 2 * 2;
+                let min_dist = scores[first]
+                    .min(scores[last])
+                    .saturating_sub(max_improvement);
+                if min_dist < cutoff_dist {
+                    let center = first + cell_diff / 2;
+                    new_windows.push((first, center));
+                    new_windows.push((center, last));
+                }
+            }
+            windows = new_windows;
+        }
+
+        if best_dist != usize::MAX {
+            let score = 1.0 - best_dist as f64 / maximum as f64;
+            if score >= score_cutoff {
+                score_cutoff = score;
+                res.score = score;
+            }
+        }
+    }
+
+    for i in 1..len1 {
+        let substr_last = s2[i - 1].value();
+        if !s1_char_set.contains(&substr_last) {
+            continue;
+        }
+
+        let ls_ratio = indel_block_normalized_similarity(&block, s1, &s2[..i], Some(score_cutoff));
+        if ls_ratio > res.score {
+            score_cutoff = ls_ratio;
+            res.score = ls_ratio;
+            res.dest_start = 0;
+            res.dest_end = i;
+            if res.score == 1.0 {
+                res.score = 100.0;
+                return res;
+            }
+        }
+    }
+
+    for i in len2 - len1..len2 {
+        let substr_first = s2[i].value();
+        if !s1_char_set.contains(&substr_first) {
+            continue;
+        }
+
+        let ls_ratio = indel_block_normalized_similarity(&block, s1, &s2[i..], Some(score_cutoff));
+        if ls_ratio > res.score {
+            score_cutoff = ls_ratio;
+            res.score = ls_ratio;
+            res.dest_start = i;
+            res.dest_end = len2;
+            if res.score == 1.0 {
+                res.score = 100.0;
+                return res;
+            }
+        }
+    }
+
+    res.score *= 100.0;
+    res
+}
+
+/**
+implementation of partial_ratio, which picks the algorithm based on the length
+of the needle. assumes s1 is already the shorter string
+*/
+fn partial_ratio_impl<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: f64,
+) -> ScoreAlignment {
+    if s1.len() <= 64 {
+        partial_ratio_short_needle(s1, s2, score_cutoff)
+    } else {
+        partial_ratio_long_needle(s1, s2, score_cutoff)
+    }
+}
+
 /**
 Searches for the optimal alignment of the shorter string in the
 longer string and returns the fuzz.ratio and the corresponding
@@ -305,6 +466,7 @@ Using the alignment information it is possible to calculate the same fuzz.ratio
 >>> fuzz.ratio(s1[res.src_start:res.src_end], s2[res.dest_start:res.dest_end])
 83.33333333333334
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "partial_ratio_alignment",
@@ -337,60 +499,57 @@ pub fn py_partial_ratio_alignment(
         }));
     }
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    Ok(partial_ratio_alignment(&s1, &s2, score_cutoff))
+    Ok(with_views!(&s1, &s2, |s1, s2| partial_ratio_alignment(
+        s1,
+        s2,
+        score_cutoff
+    )))
 }
 
-fn partial_ratio_alignment(s1: &[u32], s2: &[u32], score_cutoff: f64) -> Option<ScoreAlignment> {
-    let mut score_cutoff = score_cutoff;
-    let (len1, len2) = (s1.len(), s2.len());
-
-    let (shorter, longer) = if len1 <= len2 { (&s1, &s2) } else { (&s2, &s1) };
+fn partial_ratio_alignment<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: f64,
+) -> Option<ScoreAlignment> {
+    if s1.len() <= s2.len() {
+        partial_ratio_shorter_first(s1, s2, score_cutoff)
+    } else {
+        partial_ratio_shorter_first(s2, s1, score_cutoff).map(swap_alignment)
+    }
+}
 
-    let mut res = partial_ratio_short_needle(shorter, longer, score_cutoff / 100.0);
-    if (res.score != 100.0) && (len1 == len2) {
+/// `partial_ratio_alignment` for s1 not longer than s2.
+fn partial_ratio_shorter_first<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: f64,
+) -> Option<ScoreAlignment> {
+    let mut score_cutoff = score_cutoff;
+    let mut res = partial_ratio_impl(s1, s2, score_cutoff / 100.0);
+    if (res.score != 100.0) && (s1.len() == s2.len()) {
         score_cutoff = f64::max(score_cutoff, res.score);
-        let res2 = partial_ratio_short_needle(longer, shorter, score_cutoff / 100.0);
+        let res2 = partial_ratio_impl(s2, s1, score_cutoff / 100.0);
         if res2.score > res.score {
-            res = ScoreAlignment {
-                score: res2.score,
-                src_start: res2.dest_start,
-                src_end: res2.dest_end,
-                dest_start: res2.src_start,
-                dest_end: res2.src_end,
-            };
+            res = swap_alignment(res2);
         }
     }
 
     if res.score < score_cutoff {
         return None;
     }
+    Some(res)
+}
 
-    if len1 <= len2 {
-        return Some(res);
-    }
-
-    Some(ScoreAlignment {
+fn swap_alignment(res: ScoreAlignment) -> ScoreAlignment {
+    ScoreAlignment {
         score: res.score,
         src_start: res.dest_start,
         src_end: res.dest_end,
         dest_start: res.src_start,
         dest_end: res.src_end,
-    })
+    }
 }
 
 /**
@@ -420,6 +579,7 @@ Examples
 >>> fuzz.token_sort_ratio("fuzzy wuzzy was a bear", "wuzzy fuzzy was a bear")
 100.0
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "token_sort_ratio",
@@ -440,25 +600,20 @@ pub fn py_token_sort_ratio(
         None => (s1.to_owned(), s2.to_owned()),
     };
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    Ok(token_sort_ratio(&s1, &s2, score_cutoff))
+    Ok(with_views!(&s1, &s2, |s1, s2| token_sort_ratio(
+        s1,
+        s2,
+        score_cutoff
+    )))
 }
 
-pub fn token_sort_ratio(s1: &[u32], s2: &[u32], score_cutoff: Option<f64>) -> f64 {
+pub fn token_sort_ratio<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: Option<f64>,
+) -> f64 {
     let sorted_s1 = sort_tokens(s1);
     let sorted_s2 = sort_tokens(s2);
     let score_cutoff = score_cutoff.map(|c| c / 100.0);
@@ -502,6 +657,7 @@ Examples
 >>> fuzz.token_set_ratio("fuzzy was a bear", "fuzzy fuzzy was a bear")
 100.0
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "token_set_ratio",
@@ -524,27 +680,18 @@ pub fn py_token_set_ratio(
 
     let score_cutoff = score_cutoff.unwrap_or(0.0);
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    Ok(token_set_ratio(&s1, &s2, score_cutoff))
+    Ok(with_views!(&s1, &s2, |s1, s2| token_set_ratio(
+        s1,
+        s2,
+        score_cutoff
+    )))
 }
 
-fn token_set_ratio(s1: &[u32], s2: &[u32], score_cutoff: f64) -> f64 {
-    let tokens_a: HashSet<Vec<u32>> = split_into_tokens(s1).map(|t| t.chars.to_vec()).collect();
-    let tokens_b: HashSet<Vec<u32>> = split_into_tokens(s2).map(|t| t.chars.to_vec()).collect();
+pub fn token_set_ratio<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2], score_cutoff: f64) -> f64 {
+    let tokens_a: HashSet<Vec<u32>> = split_into_tokens(s1).map(|t| t.to_vec()).collect();
+    let tokens_b: HashSet<Vec<u32>> = split_into_tokens(s2).map(|t| t.to_vec()).collect();
 
     if tokens_a.is_empty() || tokens_b.is_empty() {
         return 0.0;
@@ -624,6 +771,7 @@ Returns
 similarity : float
     similarity between s1 and s2 as a float between 0 and 100
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "token_ratio",
@@ -644,9 +792,19 @@ pub fn py_token_ratio(
         None => (s1.to_owned(), s2.to_owned()),
     };
 
-    let token_set_ration = py_token_set_ratio(&s1, &s2, None, score_cutoff)?;
-    let token_sort_ratio = py_token_sort_ratio(&s1, &s2, None, score_cutoff)?;
-    Ok(token_set_ration.max(token_sort_ratio))
+    let (s1, s2) = conv_views(&s1, &s2)?;
+
+    Ok(with_views!(&s1, &s2, |s1, s2| token_ratio(
+        s1,
+        s2,
+        score_cutoff.unwrap_or(0.0)
+    )))
+}
+
+pub fn token_ratio<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2], score_cutoff: f64) -> f64 {
+    let token_set_ration = token_set_ratio(s1, s2, score_cutoff);
+    let token_sort_ratio = token_sort_ratio(s1, s2, Some(score_cutoff));
+    token_set_ration.max(token_sort_ratio)
 }
 
 /**
@@ -672,6 +830,7 @@ Returns
 similarity : float
     similarity between s1 and s2 as a float between 0 and 100
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "partial_token_sort_ratio",
@@ -698,25 +857,20 @@ pub fn py_partial_token_sort_ratio(
         return Ok(100.0);
     }
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    Ok(partial_token_sort_ratio(&s1, &s2, score_cutoff))
+    Ok(with_views!(&s1, &s2, |s1, s2| partial_token_sort_ratio(
+        s1,
+        s2,
+        score_cutoff
+    )))
 }
 
-fn partial_token_sort_ratio(s1: &[u32], s2: &[u32], score_cutoff: f64) -> f64 {
+pub fn partial_token_sort_ratio<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: f64,
+) -> f64 {
     let sorted_s1 = sort_tokens(s1);
     let sorted_s2 = sort_tokens(s2);
 
@@ -746,6 +900,7 @@ Returns
 similarity : float
     similarity between s1 and s2 as a float between 0 and 100
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "partial_token_set_ratio",
@@ -772,28 +927,23 @@ pub fn py_partial_token_set_ratio(
         return Ok(0.0);
     }
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    Ok(partial_token_set_ratio(&s1, &s2, score_cutoff))
+    Ok(with_views!(&s1, &s2, |s1, s2| partial_token_set_ratio(
+        s1,
+        s2,
+        score_cutoff
+    )))
 }
 
-fn partial_token_set_ratio(s1: &[u32], s2: &[u32], score_cutoff: f64) -> f64 {
+pub fn partial_token_set_ratio<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: f64,
+) -> f64 {
     // Convert to tokens and collect into sets
-    let tokens_a: HashSet<Vec<u32>> = split_into_tokens(s1).map(|t| t.chars.to_vec()).collect();
-    let tokens_b: HashSet<Vec<u32>> = split_into_tokens(s2).map(|t| t.chars.to_vec()).collect();
+    let tokens_a: HashSet<Vec<u32>> = split_into_tokens(s1).map(|t| t.to_vec()).collect();
+    let tokens_b: HashSet<Vec<u32>> = split_into_tokens(s2).map(|t| t.to_vec()).collect();
 
     if tokens_a.is_empty() || tokens_b.is_empty() {
         return 0.0;
@@ -841,6 +991,7 @@ Returns
 similarity : float
     similarity between s1 and s2 as a float between 0 and 100
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "partial_token_ratio",
@@ -867,32 +1018,27 @@ pub fn py_partial_token_ratio(
 
     let score_cutoff = score_cutoff.unwrap_or(0.0);
 
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    Ok(partial_token_ratio(&s1, &s2, score_cutoff))
+    Ok(with_views!(&s1, &s2, |s1, s2| partial_token_ratio(
+        s1,
+        s2,
+        score_cutoff
+    )))
 }
 
-fn partial_token_ratio(s1: &[u32], s2: &[u32], score_cutoff: f64) -> f64 {
+pub fn partial_token_ratio<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    score_cutoff: f64,
+) -> f64 {
     // Get tokens using TokenIterator
-    let tokens_a: Vec<Token> = split_into_tokens(s1).collect();
-    let tokens_b: Vec<Token> = split_into_tokens(s2).collect();
+    let tokens_a: Vec<Token<T1>> = split_into_tokens(s1).collect();
+    let tokens_b: Vec<Token<T2>> = split_into_tokens(s2).collect();
 
     // Create sets of token chars for intersection check
-    let tokens_a_set: HashSet<Vec<u32>> = tokens_a.iter().map(|t| t.chars.to_vec()).collect();
-    let tokens_b_set: HashSet<Vec<u32>> = tokens_b.iter().map(|t| t.chars.to_vec()).collect();
+    let tokens_a_set: HashSet<Vec<u32>> = tokens_a.iter().map(|t| t.to_vec()).collect();
+    let tokens_b_set: HashSet<Vec<u32>> = tokens_b.iter().map(|t| t.to_vec()).collect();
 
     // Quick return if there's an intersection
     if !tokens_a_set.is_disjoint(&tokens_b_set) {
@@ -900,14 +1046,14 @@ fn partial_token_ratio(s1: &[u32], s2: &[u32], score_cutoff: f64) -> f64 {
     }
 
     // Get differences between token sets
-    let diff_ab: Vec<Token> = tokens_a
+    let diff_ab: Vec<Token<T1>> = tokens_a
         .iter()
-        .filter(|t| !tokens_b_set.contains(t.chars))
+        .filter(|t| !tokens_b_set.contains(&t.to_vec()))
         .cloned()
         .collect();
-    let diff_ba: Vec<Token> = tokens_b
+    let diff_ba: Vec<Token<T2>> = tokens_b
         .iter()
-        .filter(|t| !tokens_a_set.contains(t.chars))
+        .filter(|t| !tokens_a_set.contains(&t.to_vec()))
         .cloned()
         .collect();
 
@@ -957,6 +1103,7 @@ Returns
 similarity : float
     similarity between s1 and s2 as a float between 0 and 100
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "WRatio",  // name is chosen in line with Rapifuzz
@@ -985,25 +1132,16 @@ pub fn py_weighted_ratio(
 
     // NOTE: this is not done in RapidFuzz, but otherwise we cannot get len
     // which is required in weighted ratio before using the other scorers
-    let (s1, s2) = match conv_sequences(&s1, &s2) {
-        Ok((Some(s1), Some(s2))) => (s1, s2),
-        Ok((_, _)) => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Cannot handle None",
-            ))
-        }
-        Err(e) => {
-            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
-                "Failed to convert sequences to u32. {}",
-                e
-            )))
-        }
-    };
+    let (s1, s2) = conv_views(&s1, &s2)?;
 
-    Ok(weighted_ratio(&s1, &s2, score_cutoff))
+    Ok(with_views!(&s1, &s2, |s1, s2| weighted_ratio(
+        s1,
+        s2,
+        score_cutoff
+    )))
 }
 
-fn weighted_ratio(s1: &[u32], s2: &[u32], score_cutoff: f64) -> f64 {
+pub fn weighted_ratio<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2], score_cutoff: f64) -> f64 {
     let len1 = s1.len();
     let len2 = s2.len();
     let len_ratio = if len1 > len2 {
@@ -1075,6 +1213,7 @@ Examples
 >>> fuzz.QRatio("this is a test", "this is a test!")
 96.55171966552734
 */
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(
     name = "QRatio",  // name is chosen in line with Rapifuzz
@@ -1102,18 +1241,287 @@ pub fn py_quick_ratio(
     py_ratio(&s1, &s2, None, score_cutoff)
 }
 
+pub fn quick_ratio<T1: SeqElem, T2: SeqElem>(s1: &[T1], s2: &[T2], score_cutoff: f64) -> f64 {
+    if s1.is_empty() && s2.is_empty() {
+        return 0.0;
+    }
+
+    ratio(s1, s2, score_cutoff)
+}
+
+/// Inner metrics `monge_elkan` computes natively. All of them are symmetric
+/// and return a similarity between 0 and 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InnerMetric {
+    Indel,
+    #[cfg(feature = "levenshtein")]
+    Levenshtein,
+    #[cfg(feature = "jaro")]
+    Jaro,
+    #[cfg(feature = "jaro")]
+    JaroWinkler,
+}
+
+impl InnerMetric {
+    fn similarity<T1: SeqElem, T2: SeqElem>(self, s1: &[T1], s2: &[T2]) -> f64 {
+        match self {
+            Self::Indel => indel_normalized_similarity(s1, s2, None),
+            #[cfg(feature = "levenshtein")]
+            Self::Levenshtein => {
+                levenshtein_normalized_similarity(s1, s2, LevenshteinWeights(1, 1, 1), None)
+            }
+            #[cfg(feature = "jaro")]
+            Self::Jaro => jaro_similarity(s1, s2, 0.0),
+            #[cfg(feature = "jaro")]
+            Self::JaroWinkler => jaro_winkler_similarity(s1, s2, 0.1, 0.0),
+        }
+    }
+
+    /// The native metric for the Python scorer `inner`. Distances are
+    /// supported as well, since they are turned into similarities anyway.
+    #[cfg(feature = "python")]
+    fn from_scorer(inner: &Bound<'_, PyAny>) -> Option<Self> {
+        let module: String = inner.getattr("__module__").ok()?.extract().ok()?;
+        let name: String = inner.getattr("__name__").ok()?.extract().ok()?;
+
+        match (module.as_str(), name.as_str()) {
+            ("fuzz", "ratio") | ("indel", "normalized_similarity" | "normalized_distance") => {
+                Some(Self::Indel)
+            }
+            #[cfg(feature = "levenshtein")]
+            ("levenshtein", "normalized_similarity" | "normalized_distance") => {
+                Some(Self::Levenshtein)
+            }
+            #[cfg(feature = "jaro")]
+            ("jaro", _) => Some(Self::Jaro),
+            #[cfg(feature = "jaro")]
+            ("jaro_winkler", _) => Some(Self::JaroWinkler),
+            _ => None,
+        }
+    }
+}
+
+/// Monge-Elkan similarity between 0 and 100 using `sim` for pairs of tokens.
+/// When the result is symmetrised and `sim` is not symmetric, the tokens of s2
+/// are compared to the tokens of s1 using `reverse`.
+fn monge_elkan_by<T1: SeqElem, T2: SeqElem, E>(
+    s1: &[T1],
+    s2: &[T2],
+    symmetric: bool,
+    score_cutoff: f64,
+    mut sim: impl FnMut(&[T1], &[T2]) -> Result<f64, E>,
+    mut reverse: Option<impl FnMut(&[T2], &[T1]) -> Result<f64, E>>,
+) -> Result<f64, E> {
+    let tokens1: Vec<Token<T1>> = split_into_tokens(s1).collect();
+    let tokens2: Vec<Token<T2>> = split_into_tokens(s2).collect();
+    if tokens1.is_empty() || tokens2.is_empty() {
+        return Ok(0.0);
+    }
+
+    let mut forward = Vec::with_capacity(tokens1.len() * tokens2.len());
+    for t1 in &tokens1 {
+        for t2 in &tokens2 {
+            forward.push(sim(t1.chars, t2.chars)?);
+        }
+    }
+
+    // mean of the best match of every token of s1 in s2
+    let best_rows = forward
+        .chunks(tokens2.len())
+        .map(|row| row.iter().copied().fold(0.0, f64::max))
+        .sum::<f64>();
+    let mut score = best_rows / tokens1.len() as f64;
+
+    if symmetric {
+        let mut best_cols = vec![0.0_f64; tokens2.len()];
+        for (i, t1) in tokens1.iter().enumerate() {
+            for (j, t2) in tokens2.iter().enumerate() {
+                let value = match &mut reverse {
+                    Some(reverse) => reverse(t2.chars, t1.chars)?,
+                    None => forward[i * tokens2.len() + j],
+                };
+                best_cols[j] = best_cols[j].max(value);
+            }
+        }
+        let backward = best_cols.iter().sum::<f64>() / tokens2.len() as f64;
+        score = (score + backward) / 2.0;
+    }
+
+    let score = score * 100.0;
+    Ok(if score < score_cutoff { 0.0 } else { score })
+}
+
+/**
+Calculates the Monge-Elkan similarity of the tokens of two strings.
+
+Every token of s1 is compared to every token of s2 using the inner metric,
+and the scores of the best match of every token of s1 are averaged. This
+is more forgiving for misspelled tokens than token_sort_ratio and
+token_set_ratio.
+
+Parameters
+----------
+s1 : str
+    First string to compare.
+s2 : str
+    Second string to compare.
+inner : callable, optional
+    Metric used to compare two tokens, e.g.
+    crustyfuzz.distance.jaro_winkler.similarity or
+    crustyfuzz.distance.levenshtein.normalized_similarity. The normalized
+    metrics of indel, levenshtein, jaro and jaro_winkler as well as
+    fuzz.ratio are calculated natively. Other callables have to return a
+    normalized score. Default is None, which uses the normalized Indel
+    similarity.
+symmetric : bool, optional
+    Whether the mean of the scores in both directions is returned, which
+    makes the result independent of the argument order. Default is True.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    comparing them. Default is None, which deactivates this behaviour.
+score_cutoff : float, optional
+    Optional argument for a score threshold as a float between 0 and 100.
+    For ratio < score_cutoff 0 is returned instead. Default is 0,
+    which deactivates this behaviour.
+
+Returns
+-------
+similarity : float
+    similarity between s1 and s2 as a float between 0 and 100
+
+Raises
+------
+ValueError
+    If inner is a known metric that does not return a normalized score, or
+    if a callable inner is passed a token with elements that are not valid
+    characters.
+
+Examples
+--------
+>>> from crustyfuzz.distance import jaro_winkler
+>>> fuzz.monge_elkan("jon smiht", "john smith", inner=jaro_winkler.similarity)
+94.33333333333334
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "monge_elkan",
+    signature = (s1, s2, *, inner=None, symmetric=true, processor=None, score_cutoff=None)
+)]
+pub fn py_monge_elkan(
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    inner: Option<&Bound<'_, PyAny>>,
+    symmetric: bool,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<f64>,
+) -> PyResult<f64> {
+    if s1.is_none() || s2.is_none() {
+        return Ok(0.0);
+    }
+
+    let (s1, s2) = match processor {
+        Some(proc) => (proc.call1((s1,))?, proc.call1((s2,))?),
+        None => (s1.to_owned(), s2.to_owned()),
+    };
+
+    let (s1, s2) = conv_views(&s1, &s2)?;
+
+    let score_cutoff = score_cutoff.unwrap_or(0.0);
+    let Some(inner) = inner else {
+        return Ok(with_views!(&s1, &s2, |s1, s2| monge_elkan(
+            s1,
+            s2,
+            InnerMetric::Indel,
+            symmetric,
+            score_cutoff
+        )));
+    };
+    if let Some(metric) = InnerMetric::from_scorer(inner) {
+        return Ok(with_views!(&s1, &s2, |s1, s2| monge_elkan(
+            s1,
+            s2,
+            metric,
+            symmetric,
+            score_cutoff
+        )));
+    }
+
+    // scores of registered scorers are mapped to similarities between 0 and
+    // 1, anything else is expected to return one already
+    let (normalize, inner_symmetric) = match get_scorer_flags(inner, &HashMap::new()) {
+        Some(flags) if flags.flags & ScorerFlag::ResultF64 as u64 != 0 => {
+            let (optimal, worst) = (flags.optimal_score as f64, flags.worst_score as f64);
+            let normalize: Box<dyn Fn(f64) -> f64> =
+                Box::new(move |score| (score - worst) / (optimal - worst));
+            (normalize, flags.flags & ScorerFlag::Symmetric as u64 != 0)
+        }
+        Some(_) => {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "inner has to return a normalized score",
+            ))
+        }
+        None => (Box::new(|score| score) as Box<dyn Fn(f64) -> f64>, false),
+    };
+
+    let call = |t1: String, t2: String| -> PyResult<f64> {
+        let score: f64 = inner.call1((t1, t2))?.extract()?;
+        Ok(normalize(score))
+    };
+    with_views!(&s1, &s2, |s1, s2| monge_elkan_by(
+        s1,
+        s2,
+        symmetric,
+        score_cutoff,
+        |t1, t2| call(token_to_str(t1)?, token_to_str(t2)?),
+        (!inner_symmetric)
+            .then_some(|t2: &[_], t1: &[_]| { call(token_to_str(t2)?, token_to_str(t1)?) }),
+    ))
+}
+
+/// Tokens of sequences other than strings can hold elements that are not
+/// valid characters, which cannot be passed on as a string.
+#[cfg(feature = "python")]
+fn token_to_str<T: SeqElem>(token: &[T]) -> PyResult<String> {
+    token
+        .iter()
+        .map(|c| {
+            char::from_u32(c.value()).ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "inner can only compare tokens of valid characters, got {:#x}",
+                    c.value()
+                ))
+            })
+        })
+        .collect()
+}
+
+pub fn monge_elkan<T1: SeqElem, T2: SeqElem>(
+    s1: &[T1],
+    s2: &[T2],
+    inner: InnerMetric,
+    symmetric: bool,
+    score_cutoff: f64,
+) -> f64 {
+    let similarity = |t1: &[T1], t2: &[T2]| Ok(inner.similarity(t1, t2));
+    // all native metrics are symmetric
+    let reverse = None::<fn(&[T2], &[T1]) -> Result<f64, std::convert::Infallible>>;
+    let result = monge_elkan_by(s1, s2, symmetric, score_cutoff, similarity, reverse);
+    match result {
+        Ok(score) => score,
+        Err(never) => match never {},
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn str_to_vec(s: &str) -> Vec<u32> {
-        s.chars().map(|c| c as u32).collect()
-    }
+    use crate::common::test_utils::to_seq;
 
     #[test]
     fn test_ratio() {
-        let s1 = str_to_vec("this is a test");
-        let s2 = str_to_vec("this is a test!");
+        let s1 = to_seq("this is a test");
+        let s2 = to_seq("this is a test!");
         let result = indel_normalized_similarity(&s1, &s2, None) * 100.0;
         assert!(
             (result - 96.55171966552734).abs() < 1e-5,
@@ -1123,8 +1531,8 @@ mod tests {
 
     #[test]
     fn test_ratio_with_cutoff() {
-        let s1 = str_to_vec("this is a test");
-        let s2 = str_to_vec("this is a test!");
+        let s1 = to_seq("this is a test");
+        let s2 = to_seq("this is a test!");
         let result = indel_normalized_similarity(&s1, &s2, Some(0.0)) * 100.0;
         assert!(
             (result - 96.55171966552734).abs() < 1e-5,
@@ -1135,8 +1543,8 @@ mod tests {
 
     #[test]
     fn test_ratio_unordered() {
-        let s1 = str_to_vec("new york mets vs atlanta braves");
-        let s2 = str_to_vec("atlanta braves vs new york mets");
+        let s1 = to_seq("new york mets vs atlanta braves");
+        let s2 = to_seq("atlanta braves vs new york mets");
         let result = indel_normalized_similarity(&s1, &s2, None) * 100.0;
         assert!(
             (result - 45.16129032258065).abs() < 1e-5,
@@ -1147,16 +1555,16 @@ mod tests {
 
     #[test]
     fn test_partial_ratio() {
-        let s1 = str_to_vec("this is a test");
-        let s2 = str_to_vec("this is a test!");
+        let s1 = to_seq("this is a test");
+        let s2 = to_seq("this is a test!");
         let result = partial_ratio(&s1, &s2, 0.0);
         assert_eq!(result, 100.0, "Expected 100.0");
     }
 
     #[test]
     fn test_partial_ratio_issue138() {
-        let s1 = str_to_vec(&"a".repeat(65));
-        let s2 = str_to_vec(&format!(
+        let s1 = to_seq(&"a".repeat(65));
+        let s2 = to_seq(&format!(
             "a{}{}",
             char::from_u32(256).unwrap(),
             "a".repeat(63)
@@ -1174,7 +1582,7 @@ mod tests {
         let str1 = "er merkantilismus förderte handle und verkehr mit teils marktkonformen, teils dirigistischen maßnahmen.";
         let str2 = "ils marktkonformen, teils dirigistischen maßnahmen. an der schwelle zum 19. jahrhundert entstand ein neu";
 
-        let alignment = partial_ratio_alignment(&str_to_vec(str1), &str_to_vec(str2), 0.0);
+        let alignment = partial_ratio_alignment(&to_seq(str1), &to_seq(str2), 0.0);
 
         dbg!(&alignment);
 
@@ -1191,8 +1599,8 @@ mod tests {
 
     #[test]
     fn test_partial_ratio_short_needle_identical() {
-        let s1 = str_to_vec("abcd");
-        let s2 = str_to_vec("abcd");
+        let s1 = to_seq("abcd");
+        let s2 = to_seq("abcd");
         let result = partial_ratio_short_needle(&s1, &s2, 0.0);
         assert_eq!(result.score, 100.0);
         assert_eq!(result.src_start, 0);
@@ -1203,8 +1611,8 @@ mod tests {
 
     #[test]
     fn test_partial_ratio_short_needle_substring() {
-        let s1 = str_to_vec("bcd");
-        let s2 = str_to_vec("abcde");
+        let s1 = to_seq("bcd");
+        let s2 = to_seq("abcde");
         let result = partial_ratio_short_needle(&s1, &s2, 0.0);
         assert_eq!(result.score, 100.0);
         assert_eq!(result.src_start, 0);
@@ -1215,8 +1623,8 @@ mod tests {
 
     #[test]
     fn test_partial_ratio_short_needle_partial_match() {
-        let s1 = str_to_vec("abc");
-        let s2 = str_to_vec("bcde");
+        let s1 = to_seq("abc");
+        let s2 = to_seq("bcde");
         let result = partial_ratio_short_needle(&s1, &s2, 0.0);
         assert!((result.score - 80.0).abs() < 1e-10);
         assert_eq!(result.src_start, 0);
@@ -1227,8 +1635,8 @@ mod tests {
 
     #[test]
     fn test_partial_ratio_short_needle_partial_match_score_cutoff() {
-        let s1 = str_to_vec("abc");
-        let s2 = str_to_vec("bcde");
+        let s1 = to_seq("abc");
+        let s2 = to_seq("bcde");
         let result = partial_ratio_short_needle(&s1, &s2, 0.9);
         assert_eq!(result.score, 0.0);
         assert_eq!(result.src_start, 0);
@@ -1239,32 +1647,32 @@ mod tests {
 
     #[test]
     fn test_partial_ratio_short_needle_no_match() {
-        let s1 = str_to_vec("abc");
-        let s2 = str_to_vec("def");
+        let s1 = to_seq("abc");
+        let s2 = to_seq("def");
         let result = partial_ratio_short_needle(&s1, &s2, 0.0);
         assert_eq!(result.score, 0.0);
     }
 
     #[test]
     fn test_partial_ratio_short_needle_score_cutoff() {
-        let s1 = str_to_vec("abc");
-        let s2 = str_to_vec("abcde");
+        let s1 = to_seq("abc");
+        let s2 = to_seq("abcde");
         let result = partial_ratio_short_needle(&s1, &s2, 0.9);
         assert_eq!(result.score, 100.0);
     }
 
     #[test]
     fn test_partial_ratio_short_needle_empty_s1() {
-        let s1 = str_to_vec("");
-        let s2 = str_to_vec("abc");
+        let s1 = to_seq("");
+        let s2 = to_seq("abc");
         let result = partial_ratio_short_needle(&s1, &s2, 0.0);
         assert_eq!(result.score, 0.0);
     }
 
     #[test]
     fn test_partial_ratio_short_needle_certain_string() {
-        let s1 = str_to_vec("cetain");
-        let s2 = str_to_vec("a certain string");
+        let s1 = to_seq("cetain");
+        let s2 = to_seq("a certain string");
         let result = partial_ratio_short_needle(&s1, &s2, 0.0);
         dbg!(&result);
         assert!((result.score - 83.33333333333334).abs() < 1e-10);
@@ -1274,30 +1682,165 @@ mod tests {
         assert_eq!(result.dest_end, 8);
     }
 
+    #[test]
+    fn test_partial_ratio_long_needle() {
+        let needle = "stainless steel kitchen sink with drainboard and faucet ".repeat(3);
+        let s1 = to_seq(&needle);
+        let s2 = to_seq(&format!("product: {}(discontinued)", needle));
+        let result = partial_ratio(&s1, &s2, 0.0);
+        assert_eq!(result, 100.0);
+    }
+
+    #[test]
+    fn test_partial_ratio_long_needle_matches_all_windows() {
+        let mut state = 42_u64;
+        let mut next = |modulo: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % modulo
+        };
+
+        for _ in 0..50 {
+            let len1 = 65 + next(100) as usize;
+            let len2 = len1 + next(400) as usize;
+            let alphabet = 2 + next(6) as u32;
+            let s1 = (0..len1)
+                .map(|_| next(alphabet as u64) as u32)
+                .collect::<Vec<_>>();
+            let s2 = (0..len2)
+                .map(|_| next(alphabet as u64) as u32)
+                .collect::<Vec<_>>();
+
+            // the short needle implementation scores every window
+            let expected = partial_ratio_short_needle(&s1, &s2, 0.0);
+            let result = partial_ratio_long_needle(&s1, &s2, 0.0);
+            assert_eq!(result.score, expected.score);
+            assert_eq!(
+                result.score,
+                ratio(&s1, &s2[result.dest_start..result.dest_end], 0.0)
+            );
+
+            for cutoff in [expected.score / 100.0, expected.score / 100.0 - 1e-9, 0.9] {
+                assert_eq!(
+                    partial_ratio_long_needle(&s1, &s2, cutoff).score,
+                    partial_ratio_short_needle(&s1, &s2, cutoff).score
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_token_sort_ratio_empty() {
-        let s1 = str_to_vec("");
-        let s2 = str_to_vec("");
+        let s1 = to_seq("");
+        let s2 = to_seq("");
         let result = token_sort_ratio(&s1, &s2, None);
         assert_eq!(result, 100.0);
     }
 
     #[test]
     fn test_partial_token_set_ratio_empty() {
-        let s1 = str_to_vec("");
-        let s2 = str_to_vec("");
+        let s1 = to_seq("");
+        let s2 = to_seq("");
         let result = token_set_ratio(&s1, &s2, 0.0);
         assert_eq!(result, 0.0);
     }
 
+    #[test]
+    fn test_mixed_element_widths() {
+        let strings = [
+            "new york mets",
+            "new yörk mets vs atlanta braves",
+            "atlanta braves vs new york mets",
+            "mets",
+        ];
+        for a in strings {
+            for b in strings {
+                let (s1, s2) = (to_seq(a), to_seq(b));
+                // the same characters stored with one and two bytes each
+                let s1_ucs1 = s1.iter().map(|&c| c as u8).collect::<Vec<_>>();
+                let s2_ucs2 = s2.iter().map(|&c| c as u16).collect::<Vec<_>>();
+                assert_eq!(ratio(&s1_ucs1, &s2_ucs2, 0.0), ratio(&s1, &s2, 0.0));
+                assert_eq!(
+                    partial_ratio(&s1_ucs1, &s2_ucs2, 0.0),
+                    partial_ratio(&s1, &s2, 0.0)
+                );
+                assert_eq!(
+                    weighted_ratio(&s1_ucs1, &s2_ucs2, 0.0),
+                    weighted_ratio(&s1, &s2, 0.0)
+                );
+                assert_eq!(
+                    token_ratio(&s1_ucs1, &s2_ucs2, 0.0),
+                    token_ratio(&s1, &s2, 0.0)
+                );
+                assert_eq!(
+                    partial_token_ratio(&s1_ucs1, &s2_ucs2, 0.0),
+                    partial_token_ratio(&s1, &s2, 0.0)
+                );
+                assert_eq!(
+                    monge_elkan(&s1_ucs1, &s2_ucs2, InnerMetric::Indel, true, 0.0),
+                    monge_elkan(&s1, &s2, InnerMetric::Indel, true, 0.0)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_token_ratios_invalid_chars() {
+        // hashed elements of non-string sequences are not valid characters
+        let s1 = [1_u32, 0xDEAD_BEEF];
+        let s2 = [1_u32, 3];
+        assert_eq!(token_sort_ratio(&s1, &s1, None), 100.0);
+        assert!(token_sort_ratio(&s1, &s2, None) < 100.0);
+        assert!(token_set_ratio(&s1, &s2, 0.0) < 100.0);
+        assert!(weighted_ratio(&s1, &s2, 0.0) < 100.0);
+    }
+
     #[test]
     fn test_weighted_ratio() {
-        let s1 = str_to_vec("South Korea");
-        let s2 = str_to_vec("North Korea");
+        let s1 = to_seq("South Korea");
+        let s2 = to_seq("North Korea");
         let expected = 81.81818181818181;
         let score1 = weighted_ratio(&s1, &s2, 0.0);
         let score2 = weighted_ratio(&s1, &s2, score1 - 0.0001);
         assert_eq!(score1, score2);
         assert_eq!(score1, expected);
     }
+
+    #[test]
+    fn test_monge_elkan() {
+        let s1 = to_seq("jon smiht");
+        let s2 = to_seq("john smith");
+        let score = monge_elkan(&s1, &s2, InnerMetric::Indel, false, 0.0);
+        let expected = (ratio(&to_seq("jon"), &to_seq("john"), 0.0)
+            + ratio(&to_seq("smiht"), &to_seq("smith"), 0.0))
+            / 2.0;
+        assert!((score - expected).abs() < 1e-9);
+        assert_eq!(monge_elkan(&s1, &s1, InnerMetric::Indel, true, 0.0), 100.0);
+        assert_eq!(monge_elkan(&s1, &s2, InnerMetric::Indel, false, 90.0), 0.0);
+    }
+
+    #[test]
+    fn test_monge_elkan_symmetric() {
+        let s1 = to_seq("new york");
+        let s2 = to_seq("york new jersey");
+        let forward = monge_elkan(&s1, &s2, InnerMetric::Indel, false, 0.0);
+        let backward = monge_elkan(&s2, &s1, InnerMetric::Indel, false, 0.0);
+        assert_eq!(forward, 100.0);
+        assert!(backward < 100.0);
+        let symmetric = monge_elkan(&s1, &s2, InnerMetric::Indel, true, 0.0);
+        assert!((symmetric - (forward + backward) / 2.0).abs() < 1e-9);
+        assert_eq!(
+            symmetric,
+            monge_elkan(&s2, &s1, InnerMetric::Indel, true, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_monge_elkan_empty() {
+        let s1 = to_seq("");
+        let s2 = to_seq("  new york ");
+        assert_eq!(monge_elkan(&s1, &s2, InnerMetric::Indel, true, 0.0), 0.0);
+        assert_eq!(monge_elkan(&s1, &s1, InnerMetric::Indel, true, 0.0), 0.0);
+    }
 }
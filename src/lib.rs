@@ -1,19 +1,36 @@
 mod common;
-mod distance;
-mod fuzz;
+// the pyo3 0.22 function wrappers emit `PyErr` -> `PyErr` conversions, so
+// the lint is silenced for the modules with bindings only
+#[cfg_attr(feature = "python", allow(clippy::useless_conversion))]
+pub mod distance;
+#[cfg(feature = "fuzz")]
+#[cfg_attr(feature = "python", allow(clippy::useless_conversion))]
+pub mod fuzz;
+#[cfg(feature = "phonetic")]
+#[cfg_attr(feature = "python", allow(clippy::useless_conversion))]
+pub mod phonetic;
+#[cfg(feature = "process")]
+#[allow(clippy::useless_conversion)]
 mod process;
 
+#[cfg(feature = "python")]
 use crate::distance::setup_scorer_metadata;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 
 // A rusty string matching library
+#[cfg(feature = "python")]
 #[pymodule]
 mod crustyfuzz {
     use super::*;
 
     #[pymodule_init]
-    fn init(_m: &Bound<'_, PyModule>) -> PyResult<()> {
+    fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
         setup_scorer_metadata();
+        m.add(
+            "_exact_comparison",
+            crate::common::exact_comparison_var(m.py())?,
+        )?;
         Ok(())
     }
 
@@ -35,6 +52,15 @@ mod crustyfuzz {
             };
         }
 
+        #[pymodule(submodule)]
+        mod lcs_str {
+            #[pymodule_export]
+            use crate::distance::lcs_str::{
+                py_alignment, py_distance, py_normalized_distance, py_normalized_similarity,
+                py_similarity,
+            };
+        }
+
         #[pymodule(submodule)]
         mod indel {
             #[pymodule_export]
@@ -44,6 +70,15 @@ mod crustyfuzz {
             };
         }
 
+        #[pymodule(submodule)]
+        mod gotoh {
+            #[pymodule_export]
+            use crate::distance::gotoh::{
+                py_distance, py_editops, py_normalized_distance, py_normalized_similarity,
+                py_opcodes, py_similarity,
+            };
+        }
+
         #[pymodule(submodule)]
         mod hamming {
             #[pymodule_export]
@@ -53,15 +88,17 @@ mod crustyfuzz {
             };
         }
 
+        #[cfg(feature = "levenshtein")]
         #[pymodule(submodule)]
         mod levenshtein {
             #[pymodule_export]
             use crate::distance::levenshtein::{
                 py_distance, py_editops, py_normalized_distance, py_normalized_similarity,
-                py_opcodes, py_similarity,
+                py_opcodes, py_similarity, CostTable,
             };
         }
 
+        #[cfg(feature = "damerau_levenshtein")]
         #[pymodule(submodule)]
         mod damerau_levenshtein {
             #[pymodule_export]
@@ -70,6 +107,25 @@ mod crustyfuzz {
             };
         }
 
+        #[cfg(feature = "phonetic")]
+        #[pymodule(submodule)]
+        mod editex {
+            #[pymodule_export]
+            use crate::distance::editex::{
+                py_distance, py_normalized_distance, py_normalized_similarity, py_similarity,
+            };
+        }
+
+        #[cfg(feature = "phonetic")]
+        #[pymodule(submodule)]
+        mod mra {
+            #[pymodule_export]
+            use crate::distance::mra::{
+                py_distance, py_normalized_distance, py_normalized_similarity, py_similarity,
+            };
+        }
+
+        #[cfg(feature = "jaro")]
         #[pymodule(submodule)]
         mod jaro {
             #[pymodule_export]
@@ -78,6 +134,7 @@ mod crustyfuzz {
             };
         }
 
+        #[cfg(feature = "jaro")]
         #[pymodule(submodule)]
         mod jaro_winkler {
             #[pymodule_export]
@@ -86,6 +143,7 @@ mod crustyfuzz {
             };
         }
 
+        #[cfg(feature = "osa")]
         #[pymodule(submodule)]
         mod osa {
             #[pymodule_export]
@@ -94,6 +152,42 @@ mod crustyfuzz {
             };
         }
 
+        #[cfg(feature = "qgram")]
+        #[pymodule(submodule)]
+        mod jaccard {
+            #[pymodule_export]
+            use crate::distance::jaccard::{
+                py_distance, py_normalized_distance, py_normalized_similarity, py_similarity,
+            };
+        }
+
+        #[cfg(feature = "qgram")]
+        #[pymodule(submodule)]
+        mod sorensen_dice {
+            #[pymodule_export]
+            use crate::distance::sorensen_dice::{
+                py_distance, py_normalized_distance, py_normalized_similarity, py_similarity,
+            };
+        }
+
+        #[cfg(feature = "qgram")]
+        #[pymodule(submodule)]
+        mod cosine {
+            #[pymodule_export]
+            use crate::distance::cosine::{
+                py_distance, py_normalized_distance, py_normalized_similarity, py_similarity,
+            };
+        }
+
+        #[cfg(feature = "qgram")]
+        #[pymodule(submodule)]
+        mod overlap {
+            #[pymodule_export]
+            use crate::distance::overlap::{
+                py_distance, py_normalized_distance, py_normalized_similarity, py_similarity,
+            };
+        }
+
         #[pymodule(submodule)]
         mod postfix {
             #[pymodule_export]
@@ -109,18 +203,45 @@ mod crustyfuzz {
                 py_distance, py_normalized_distance, py_normalized_similarity, py_similarity,
             };
         }
+
+        #[pymodule(submodule)]
+        mod ratcliff_obershelp {
+            #[pymodule_export]
+            use crate::distance::ratcliff_obershelp::{
+                py_distance, py_matching_blocks, py_normalized_distance, py_normalized_similarity,
+                py_opcodes, py_similarity,
+            };
+        }
+
+        #[pymodule(submodule)]
+        mod smith_waterman {
+            #[pymodule_export]
+            use crate::distance::smith_waterman::{
+                py_alignment, py_distance, py_editops, py_normalized_distance,
+                py_normalized_similarity, py_opcodes, py_similarity,
+            };
+        }
     }
 
+    #[cfg(feature = "fuzz")]
     #[pymodule(submodule)]
     mod fuzz {
         #[pymodule_export]
         use crate::fuzz::{
-            py_partial_ratio, py_partial_ratio_alignment, py_partial_token_ratio,
+            py_monge_elkan, py_partial_ratio, py_partial_ratio_alignment, py_partial_token_ratio,
             py_partial_token_set_ratio, py_partial_token_sort_ratio, py_quick_ratio, py_ratio,
             py_token_ratio, py_token_set_ratio, py_token_sort_ratio, py_weighted_ratio,
         };
     }
 
+    #[cfg(feature = "phonetic")]
+    #[pymodule(submodule)]
+    mod phonetic {
+        #[pymodule_export]
+        use crate::phonetic::{py_double_metaphone, py_metaphone, py_nysiis, py_score, py_soundex};
+    }
+
+    #[cfg(feature = "process")]
     #[pymodule(submodule)]
     mod process {
         #[pymodule_export]
@@ -0,0 +1,370 @@
+mod double_metaphone;
+mod metaphone;
+mod nysiis;
+mod soundex;
+
+pub use double_metaphone::double_metaphone;
+pub use metaphone::metaphone;
+pub use nysiis::nysiis;
+pub use soundex::soundex;
+
+#[cfg(feature = "python")]
+use crate::distance::{get_scorer_flags, ScorerFlag, ScorerFlags};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyDict;
+#[cfg(feature = "python")]
+use std::collections::HashMap;
+
+/// The phonetic algorithms `score` can encode strings with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoder {
+    Soundex,
+    Metaphone,
+    DoubleMetaphone,
+    Nysiis,
+}
+
+impl Encoder {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "soundex" => Some(Self::Soundex),
+            "metaphone" => Some(Self::Metaphone),
+            "double_metaphone" => Some(Self::DoubleMetaphone),
+            "nysiis" => Some(Self::Nysiis),
+            _ => None,
+        }
+    }
+
+    /// Encodes `s`. Double Metaphone returns the alternate code as well, if
+    /// there is one.
+    pub fn encode(self, s: &str) -> Vec<String> {
+        match self {
+            Self::Soundex => vec![soundex(s)],
+            Self::Metaphone => vec![metaphone(s)],
+            Self::DoubleMetaphone => {
+                let (primary, secondary) = double_metaphone(s);
+                if secondary.is_empty() {
+                    vec![primary]
+                } else {
+                    vec![primary, secondary]
+                }
+            }
+            Self::Nysiis => vec![nysiis(s)],
+        }
+    }
+}
+
+/// Whether `s1` and `s2` share a phonetic code.
+pub fn codes_match(s1: &str, s2: &str, encoder: Encoder) -> bool {
+    let codes2 = encoder.encode(s2);
+    encoder.encode(s1).iter().any(|code| codes2.contains(code))
+}
+
+/**
+Encodes a string with American Soundex.
+
+Parameters
+----------
+s : str
+    String to encode. Everything but ASCII letters is ignored.
+
+Returns
+-------
+code : str
+    the first letter followed by three digits, or an empty string when
+    s contains no letters
+
+Examples
+--------
+>>> phonetic.soundex("Robert")
+'R163'
+>>> phonetic.soundex("Rupert")
+'R163'
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "soundex", signature = (s))]
+pub fn py_soundex(s: &str) -> String {
+    soundex(s)
+}
+
+/**
+Encodes a string with the original Metaphone algorithm.
+
+Parameters
+----------
+s : str
+    String to encode. Everything but ASCII letters is ignored.
+
+Returns
+-------
+code : str
+    Metaphone code of s. "0" stands for the "th" sound and "X" for "sh".
+
+Examples
+--------
+>>> phonetic.metaphone("Smith")
+'SM0'
+>>> phonetic.metaphone("Smyth")
+'SM0'
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "metaphone", signature = (s))]
+pub fn py_metaphone(s: &str) -> String {
+    metaphone(s)
+}
+
+/**
+Encodes a string with the Double Metaphone algorithm.
+
+Parameters
+----------
+s : str
+    String to encode.
+
+Returns
+-------
+codes : tuple[str, str]
+    the primary and the alternate code, both at most 4 characters long.
+    The alternate code is empty when s has no alternate pronunciation.
+
+Examples
+--------
+>>> phonetic.double_metaphone("Schmidt")
+('XMT', 'SMT')
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "double_metaphone", signature = (s))]
+pub fn py_double_metaphone(s: &str) -> (String, String) {
+    double_metaphone(s)
+}
+
+/**
+Encodes a string with the New York State Identification and Intelligence
+System (NYSIIS) algorithm.
+
+Parameters
+----------
+s : str
+    String to encode. Everything but ASCII letters is ignored.
+
+Returns
+-------
+code : str
+    NYSIIS code of s. It is not truncated to six characters.
+
+Examples
+--------
+>>> phonetic.nysiis("Mitchell")
+'MATCAL'
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "nysiis", signature = (s))]
+pub fn py_nysiis(s: &str) -> String {
+    nysiis(s)
+}
+
+#[cfg(feature = "python")]
+fn py_encoder(name: &str) -> PyResult<Encoder> {
+    Encoder::from_name(name).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!(
+            "unknown encoder {name:?}, expected one of 'soundex', 'metaphone', \
+             'double_metaphone' or 'nysiis'"
+        ))
+    })
+}
+
+/// The keyword arguments of `score` that are not passed on to the metric.
+#[cfg(feature = "python")]
+const SCORE_KWARGS: [&str; 2] = ["encoder", "metric"];
+
+/// Flags of `score` for the given `scorer_kwargs`. Without a metric the
+/// result is a normalized similarity, otherwise it is the result of the
+/// metric.
+#[cfg(feature = "python")]
+pub(crate) fn get_scorer_flags_score(scorer_kwargs: &HashMap<String, PyObject>) -> ScorerFlags {
+    Python::with_gil(|py| {
+        let metric = scorer_kwargs.get("metric").filter(|m| !m.is_none(py));
+        let Some(metric) = metric else {
+            return ScorerFlags {
+                optimal_score: 1,
+                worst_score: 0,
+                flags: ScorerFlag::ResultF64 | ScorerFlag::Symmetric,
+            };
+        };
+
+        let metric_kwargs: HashMap<String, PyObject> = scorer_kwargs
+            .iter()
+            .filter(|(key, _)| !SCORE_KWARGS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone_ref(py)))
+            .collect();
+        // the same fallback process uses for unknown scorers
+        get_scorer_flags(metric.bind(py), &metric_kwargs).unwrap_or(ScorerFlags {
+            optimal_score: 100,
+            worst_score: 0,
+            flags: ScorerFlag::ResultF64 | ScorerFlag::Symmetric,
+        })
+    })
+}
+
+/**
+Compares the phonetic codes of two strings.
+
+Both strings are encoded with the selected phonetic algorithm. Without a
+metric the result is 1.0 when the codes match and 0.0 otherwise. With a
+metric the codes are compared using that metric instead. Double Metaphone
+compares all combinations of the primary and alternate codes and returns
+the best result.
+
+Parameters
+----------
+s1 : str
+    First string to compare.
+s2 : str
+    Second string to compare.
+encoder : str, optional
+    Phonetic algorithm used to encode the strings. One of "soundex",
+    "metaphone", "double_metaphone" and "nysiis". Default is "metaphone".
+metric : callable, optional
+    Scorer used to compare the codes, e.g.
+    crustyfuzz.distance.levenshtein.normalized_similarity or
+    crustyfuzz.distance.jaro_winkler.similarity. Default is None, which
+    compares the codes for equality.
+processor: callable, optional
+    Optional callable that is used to preprocess the strings before
+    encoding them. Default is None, which deactivates this behaviour.
+score_cutoff : Any, optional
+    Score threshold that is passed on to the metric. Without a metric
+    it is a float between 0 and 1, and 0 is returned for results below
+    it. Default is None, which deactivates this behaviour.
+**kwargs : Any, optional
+    Additional keyword arguments passed on to the metric.
+
+Returns
+-------
+score : Any
+    1.0 or 0.0 without a metric, otherwise the best result of the metric
+
+Raises
+------
+ValueError
+    If encoder is not a known phonetic algorithm.
+
+Examples
+--------
+>>> phonetic.score("Smith", "Smyth")
+1.0
+>>> phonetic.score("Robert", "Rupert", encoder="soundex")
+1.0
+>>> from crustyfuzz.distance import levenshtein
+>>> phonetic.score("Knight", "Night", metric=levenshtein.distance)
+0
+*/
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(
+    name = "score",
+    signature = (s1, s2, *, encoder="metaphone", metric=None, processor=None, score_cutoff=None, **kwargs)
+)]
+#[allow(clippy::too_many_arguments)]
+pub fn py_score(
+    py: Python<'_>,
+    s1: &Bound<'_, PyAny>,
+    s2: &Bound<'_, PyAny>,
+    encoder: &str,
+    metric: Option<&Bound<'_, PyAny>>,
+    processor: Option<&Bound<'_, PyAny>>,
+    score_cutoff: Option<&Bound<'_, PyAny>>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyObject> {
+    let encoder = py_encoder(encoder)?;
+
+    if s1.is_none() || s2.is_none() {
+        return match metric {
+            // the metric knows its worst score
+            Some(metric) => {
+                let metric_kwargs = PyDict::new_bound(py);
+                if let Some(kwargs) = kwargs {
+                    metric_kwargs.update(kwargs.as_mapping())?;
+                }
+                Ok(metric.call((s1, s2), Some(&metric_kwargs))?.unbind())
+            }
+            None => Ok(0.0.into_py(py)),
+        };
+    }
+
+    let (s1, s2) = match processor {
+        Some(proc) => (proc.call1((s1,))?, proc.call1((s2,))?),
+        None => (s1.to_owned(), s2.to_owned()),
+    };
+    let codes1 = encoder.encode(&s1.extract::<String>()?);
+    let codes2 = encoder.encode(&s2.extract::<String>()?);
+
+    let Some(metric) = metric else {
+        let score_cutoff = score_cutoff.map(|c| c.extract::<f64>()).transpose()?;
+        let matched = codes1.iter().any(|code| codes2.contains(code));
+        let score = if matched { 1.0 } else { 0.0 };
+        return Ok(match score_cutoff {
+            Some(cutoff) if score < cutoff => 0.0,
+            _ => score,
+        }
+        .into_py(py));
+    };
+
+    let metric_kwargs = PyDict::new_bound(py);
+    if let Some(kwargs) = kwargs {
+        metric_kwargs.update(kwargs.as_mapping())?;
+    }
+    if let Some(score_cutoff) = score_cutoff {
+        metric_kwargs.set_item("score_cutoff", score_cutoff)?;
+    }
+
+    let flags_kwargs: HashMap<String, PyObject> = metric_kwargs.extract()?;
+    let lowest_score_worst = get_scorer_flags(metric, &flags_kwargs)
+        .is_none_or(|flags| flags.optimal_score > flags.worst_score);
+
+    let mut best: Option<(f64, Bound<'_, PyAny>)> = None;
+    for code1 in &codes1 {
+        for code2 in &codes2 {
+            let result = metric.call((code1, code2), Some(&metric_kwargs))?;
+            let value: f64 = result.extract()?;
+            let is_better = best.as_ref().is_none_or(|(best_value, _)| {
+                if lowest_score_worst {
+                    value > *best_value
+                } else {
+                    value < *best_value
+                }
+            });
+            if is_better {
+                best = Some((value, result));
+            }
+        }
+    }
+
+    Ok(best.expect("every encoder returns a code").1.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoder_from_name() {
+        assert_eq!(Encoder::from_name("nysiis"), Some(Encoder::Nysiis));
+        assert_eq!(Encoder::from_name("Soundex"), None);
+    }
+
+    #[test]
+    fn test_codes_match() {
+        assert!(codes_match("Smith", "Smyth", Encoder::Metaphone));
+        assert!(codes_match("Robert", "Rupert", Encoder::Soundex));
+        assert!(!codes_match("Robert", "Rubin", Encoder::Soundex));
+        // only the alternate codes match
+        assert!(codes_match("Smith", "Schmidt", Encoder::DoubleMetaphone));
+    }
+}
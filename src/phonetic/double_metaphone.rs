@@ -0,0 +1,710 @@
+/// Codes are cut off once both of them reached this length.
+const MAX_LEN: usize = 4;
+
+struct Encoder {
+    word: Vec<char>,
+    primary: String,
+    secondary: String,
+    has_alternate: bool,
+    slavo_germanic: bool,
+}
+
+impl Encoder {
+    fn new(s: &str) -> Self {
+        let word: Vec<char> = s.chars().flat_map(char::to_uppercase).collect();
+        let text: String = word.iter().collect();
+        let slavo_germanic = ["W", "K", "CZ", "WITZ"].iter().any(|p| text.contains(p));
+
+        Self {
+            word,
+            primary: String::new(),
+            secondary: String::new(),
+            has_alternate: false,
+            slavo_germanic,
+        }
+    }
+
+    /// Character at `pos`, positions outside of the word are blanks.
+    fn at(&self, pos: isize) -> char {
+        usize::try_from(pos)
+            .ok()
+            .and_then(|pos| self.word.get(pos))
+            .copied()
+            .unwrap_or(' ')
+    }
+
+    fn is_vowel(&self, pos: isize) -> bool {
+        matches!(self.at(pos), 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+    }
+
+    /// Checks whether one of `candidates` starts at `pos`. All candidates
+    /// have to be of the same length.
+    fn string_at(&self, pos: isize, candidates: &[&str]) -> bool {
+        if pos < 0 {
+            return false;
+        }
+        candidates.iter().any(|candidate| {
+            candidate
+                .chars()
+                .zip(pos..)
+                .all(|(c, pos)| self.at(pos) == c)
+        })
+    }
+
+    fn add(&mut self, code: &str) {
+        self.primary.push_str(code);
+        self.secondary.push_str(code);
+    }
+
+    /// Adds different codes for the primary and the alternate encoding.
+    fn add_alternate(&mut self, primary: &str, secondary: &str) {
+        self.has_alternate = true;
+        self.primary.push_str(primary);
+        self.secondary.push_str(secondary);
+    }
+
+    fn encode(mut self) -> (String, String) {
+        let len = self.word.len() as isize;
+        let last = len - 1;
+        let mut current: isize = 0;
+
+        // silent first letters
+        if self.string_at(0, &["GN", "KN", "PN", "WR", "PS"]) {
+            current += 1;
+        }
+        // an initial 'X' is pronounced 'Z', e.g. 'Xavier'
+        if self.at(0) == 'X' {
+            self.add("S");
+            current += 1;
+        }
+
+        while (self.primary.len() < MAX_LEN || self.secondary.len() < MAX_LEN) && current < len {
+            current += match self.at(current) {
+                'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                    if current == 0 {
+                        self.add("A");
+                    }
+                    1
+                }
+                'B' => {
+                    self.add("P");
+                    if self.at(current + 1) == 'B' {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'Ç' => {
+                    self.add("S");
+                    1
+                }
+                'C' => self.encode_c(current),
+                'D' => {
+                    if self.string_at(current, &["DG"]) {
+                        if self.string_at(current + 2, &["I", "E", "Y"]) {
+                            // e.g. 'edge'
+                            self.add("J");
+                            3
+                        } else {
+                            // e.g. 'edgar'
+                            self.add("TK");
+                            2
+                        }
+                    } else if self.string_at(current, &["DT", "DD"]) {
+                        self.add("T");
+                        2
+                    } else {
+                        self.add("T");
+                        1
+                    }
+                }
+                'F' => self.encode_doubled(current, 'F', "F"),
+                'G' => self.encode_g(current),
+                // only kept as the first letter or between two vowels
+                'H' if (current == 0 || self.is_vowel(current - 1))
+                    && self.is_vowel(current + 1) =>
+                {
+                    self.add("H");
+                    2
+                }
+                'J' => self.encode_j(current, last),
+                'K' => self.encode_doubled(current, 'K', "K"),
+                'L' => {
+                    if self.at(current + 1) == 'L' {
+                        // spanish, e.g. 'cabrillo', 'gallegos'
+                        if (current == len - 3
+                            && self.string_at(current - 1, &["ILLO", "ILLA", "ALLE"]))
+                            || ((self.string_at(last - 1, &["AS", "OS"])
+                                || self.string_at(last, &["A", "O"]))
+                                && self.string_at(current - 1, &["ALLE"]))
+                        {
+                            self.add_alternate("L", "");
+                        } else {
+                            self.add("L");
+                        }
+                        2
+                    } else {
+                        self.add("L");
+                        1
+                    }
+                }
+                'M' => {
+                    self.add("M");
+                    // e.g. 'dumb', 'thumb'
+                    if (self.string_at(current - 1, &["UMB"])
+                        && (current + 1 == last || self.string_at(current + 2, &["ER"])))
+                        || self.at(current + 1) == 'M'
+                    {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'N' => self.encode_doubled(current, 'N', "N"),
+                'Ñ' => {
+                    self.add("N");
+                    1
+                }
+                'P' => {
+                    if self.at(current + 1) == 'H' {
+                        self.add("F");
+                        2
+                    } else {
+                        self.add("P");
+                        // e.g. 'campbell', 'raspberry'
+                        if self.string_at(current + 1, &["P", "B"]) {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                'Q' => self.encode_doubled(current, 'Q', "K"),
+                'R' => {
+                    // french, e.g. 'rogier', but not 'hochmeier'
+                    if current == last
+                        && !self.slavo_germanic
+                        && self.string_at(current - 2, &["IE"])
+                        && !self.string_at(current - 4, &["ME", "MA"])
+                    {
+                        self.add_alternate("", "R");
+                    } else {
+                        self.add("R");
+                    }
+                    if self.at(current + 1) == 'R' {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'S' => self.encode_s(current, last),
+                'T' => {
+                    if self.string_at(current, &["TION"])
+                        || self.string_at(current, &["TIA", "TCH"])
+                    {
+                        self.add("X");
+                        3
+                    } else if self.string_at(current, &["TH"]) || self.string_at(current, &["TTH"])
+                    {
+                        // 'thomas', 'thames' or germanic
+                        if self.string_at(current + 2, &["OM", "AM"])
+                            || self.string_at(0, &["VAN ", "VON "])
+                            || self.string_at(0, &["SCH"])
+                        {
+                            self.add("T");
+                        } else {
+                            self.add_alternate("0", "T");
+                        }
+                        2
+                    } else {
+                        self.add("T");
+                        if self.string_at(current + 1, &["T", "D"]) {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                'V' => self.encode_doubled(current, 'V', "F"),
+                'W' => self.encode_w(current, last),
+                'X' => {
+                    // french, e.g. 'breaux'
+                    if !(current == last
+                        && (self.string_at(current - 3, &["IAU", "EAU"])
+                            || self.string_at(current - 2, &["AU", "OU"])))
+                    {
+                        self.add("KS");
+                    }
+                    if self.string_at(current + 1, &["C", "X"]) {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                'Z' => {
+                    if self.at(current + 1) == 'H' {
+                        // chinese pinyin, e.g. 'zhao'
+                        self.add("J");
+                        2
+                    } else {
+                        if self.string_at(current + 1, &["ZO", "ZI", "ZA"])
+                            || (self.slavo_germanic && current > 0 && self.at(current - 1) != 'T')
+                        {
+                            self.add_alternate("S", "TS");
+                        } else {
+                            self.add("S");
+                        }
+                        if self.at(current + 1) == 'Z' {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                }
+                _ => 1,
+            };
+        }
+
+        self.primary.truncate(MAX_LEN);
+        self.secondary.truncate(MAX_LEN);
+        if !self.has_alternate {
+            self.secondary.clear();
+        }
+        (self.primary, self.secondary)
+    }
+
+    /// Letters that are encoded the same way when they are doubled.
+    fn encode_doubled(&mut self, current: isize, letter: char, code: &str) -> isize {
+        self.add(code);
+        if self.at(current + 1) == letter {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_c(&mut self, current: isize) -> isize {
+        // various germanic
+        if current > 1
+            && !self.is_vowel(current - 2)
+            && self.string_at(current - 1, &["ACH"])
+            && self.at(current + 2) != 'I'
+            && (self.at(current + 2) != 'E' || self.string_at(current - 2, &["BACHER", "MACHER"]))
+        {
+            self.add("K");
+            return 2;
+        }
+
+        // special case 'caesar'
+        if current == 0 && self.string_at(current, &["CAESAR"]) {
+            self.add("S");
+            return 2;
+        }
+
+        // italian 'chianti'
+        if self.string_at(current, &["CHIA"]) {
+            self.add("K");
+            return 2;
+        }
+
+        if self.string_at(current, &["CH"]) {
+            // 'michael'
+            if current > 0 && self.string_at(current, &["CHAE"]) {
+                self.add_alternate("K", "X");
+                return 2;
+            }
+
+            // greek roots, e.g. 'chemistry', 'chorus'
+            if current == 0
+                && (self.string_at(current + 1, &["HARAC", "HARIS"])
+                    || self.string_at(current + 1, &["HOR", "HYM", "HIA", "HEM"]))
+                && !self.string_at(0, &["CHORE"])
+            {
+                self.add("K");
+                return 2;
+            }
+
+            // germanic, greek, or otherwise 'ch' for the 'kh' sound
+            if self.string_at(0, &["VAN ", "VON "])
+                || self.string_at(0, &["SCH"])
+                // 'architect' but not 'arch', 'orchestra', 'orchid'
+                || self.string_at(current - 2, &["ORCHES", "ARCHIT", "ORCHID"])
+                || self.string_at(current + 2, &["T", "S"])
+                || ((self.string_at(current - 1, &["A", "O", "U", "E"]) || current == 0)
+                    // e.g. 'wachtler', 'wechsler', but not 'tichner'
+                    && self.string_at(
+                        current + 2,
+                        &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "],
+                    ))
+            {
+                self.add("K");
+            } else if current == 0 {
+                self.add("X");
+            } else if self.string_at(0, &["MC"]) {
+                // e.g. 'McHugh'
+                self.add("K");
+            } else {
+                self.add_alternate("X", "K");
+            }
+            return 2;
+        }
+
+        // e.g. 'czerny'
+        if self.string_at(current, &["CZ"]) && !self.string_at(current - 2, &["WICZ"]) {
+            self.add_alternate("S", "X");
+            return 2;
+        }
+
+        // e.g. 'focaccia'
+        if self.string_at(current + 1, &["CIA"]) {
+            self.add("X");
+            return 3;
+        }
+
+        // double 'C', but not if e.g. 'McClellan'
+        if self.string_at(current, &["CC"]) && !(current == 1 && self.at(0) == 'M') {
+            // 'bellocchio' but not 'bacchus'
+            if self.string_at(current + 2, &["I", "E", "H"])
+                && !self.string_at(current + 2, &["HU"])
+            {
+                // 'accident', 'accede', 'succeed'
+                if (current == 1 && self.at(current - 1) == 'A')
+                    || self.string_at(current - 1, &["UCCEE", "UCCES"])
+                {
+                    self.add("KS");
+                } else {
+                    // 'bacci', 'bertucci', other italian
+                    self.add("X");
+                }
+                return 3;
+            }
+            // Pierce's rule
+            self.add("K");
+            return 2;
+        }
+
+        if self.string_at(current, &["CK", "CG", "CQ"]) {
+            self.add("K");
+            return 2;
+        }
+
+        if self.string_at(current, &["CI", "CE", "CY"]) {
+            // italian vs. english
+            if self.string_at(current, &["CIO", "CIE", "CIA"]) {
+                self.add_alternate("S", "X");
+            } else {
+                self.add("S");
+            }
+            return 2;
+        }
+
+        self.add("K");
+        // names like 'mac caffrey', 'mac gregor'
+        if self.string_at(current + 1, &[" C", " Q", " G"]) {
+            3
+        } else if self.string_at(current + 1, &["C", "K", "Q"])
+            && !self.string_at(current + 1, &["CE", "CI"])
+        {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_g(&mut self, current: isize) -> isize {
+        if self.at(current + 1) == 'H' {
+            if current > 0 && !self.is_vowel(current - 1) {
+                self.add("K");
+                return 2;
+            }
+
+            // 'ghislane', 'ghiradelli'
+            if current == 0 {
+                if self.at(current + 2) == 'I' {
+                    self.add("J");
+                } else {
+                    self.add("K");
+                }
+                return 2;
+            }
+
+            // Parker's rule (with some further refinements), e.g. 'hugh'
+            if (current > 1 && self.string_at(current - 2, &["B", "H", "D"]))
+                // e.g. 'bough'
+                || (current > 2 && self.string_at(current - 3, &["B", "H", "D"]))
+                // e.g. 'broughton'
+                || (current > 3 && self.string_at(current - 4, &["B", "H"]))
+            {
+                return 2;
+            }
+
+            // e.g. 'laugh', 'McLaughlin', 'cough', 'gough', 'rough', 'tough'
+            if current > 2
+                && self.at(current - 1) == 'U'
+                && self.string_at(current - 3, &["C", "G", "L", "R", "T"])
+            {
+                self.add("F");
+            } else if current > 0 && self.at(current - 1) != 'I' {
+                self.add("K");
+            }
+            return 2;
+        }
+
+        if self.at(current + 1) == 'N' {
+            if current == 1 && self.is_vowel(0) && !self.slavo_germanic {
+                self.add_alternate("KN", "N");
+            } else if !self.string_at(current + 2, &["EY"])
+                && self.at(current + 1) != 'Y'
+                && !self.slavo_germanic
+            {
+                // not e.g. 'cagney'
+                self.add_alternate("N", "KN");
+            } else {
+                self.add("KN");
+            }
+            return 2;
+        }
+
+        // 'tagliaro'
+        if self.string_at(current + 1, &["LI"]) && !self.slavo_germanic {
+            self.add_alternate("KL", "L");
+            return 2;
+        }
+
+        // -ges-, -gep-, -gel-, -gie- at the beginning
+        if current == 0
+            && (self.at(current + 1) == 'Y'
+                || self.string_at(
+                    current + 1,
+                    &[
+                        "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+                    ],
+                ))
+        {
+            self.add_alternate("K", "J");
+            return 2;
+        }
+
+        // -ger-, -gy-
+        if (self.string_at(current + 1, &["ER"]) || self.at(current + 1) == 'Y')
+            && !self.string_at(0, &["DANGER", "RANGER", "MANGER"])
+            && !self.string_at(current - 1, &["E", "I"])
+            && !self.string_at(current - 1, &["RGY", "OGY"])
+        {
+            self.add_alternate("K", "J");
+            return 2;
+        }
+
+        // italian, e.g. 'biaggi'
+        if self.string_at(current + 1, &["E", "I", "Y"])
+            || self.string_at(current - 1, &["AGGI", "OGGI"])
+        {
+            if self.string_at(0, &["VAN ", "VON "])
+                || self.string_at(0, &["SCH"])
+                || self.string_at(current + 1, &["ET"])
+            {
+                // obvious germanic
+                self.add("K");
+            } else if self.string_at(current + 1, &["IER "]) {
+                // always soft if french ending
+                self.add("J");
+            } else {
+                self.add_alternate("J", "K");
+            }
+            return 2;
+        }
+
+        self.encode_doubled(current, 'G', "K")
+    }
+
+    fn encode_j(&mut self, current: isize, last: isize) -> isize {
+        // obvious spanish, 'jose', 'san jacinto'
+        if self.string_at(current, &["JOSE"]) || self.string_at(0, &["SAN "]) {
+            if (current == 0 && self.at(current + 4) == ' ') || self.string_at(0, &["SAN "]) {
+                self.add("H");
+            } else {
+                self.add_alternate("J", "H");
+            }
+            return 1;
+        }
+
+        if current == 0 {
+            // 'Yankelovich' / 'Jankelowicz'
+            self.add_alternate("J", "A");
+        } else if self.is_vowel(current - 1)
+            && !self.slavo_germanic
+            && matches!(self.at(current + 1), 'A' | 'O')
+        {
+            // spanish pronunciation of e.g. 'bajador'
+            self.add_alternate("J", "H");
+        } else if current == last {
+            self.add_alternate("J", "");
+        } else if !self.string_at(current + 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+            && !self.string_at(current - 1, &["S", "K", "L"])
+        {
+            self.add("J");
+        }
+
+        if self.at(current + 1) == 'J' {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_s(&mut self, current: isize, last: isize) -> isize {
+        // 'island', 'isle', 'carlisle', 'carlysle'
+        if self.string_at(current - 1, &["ISL", "YSL"]) {
+            return 1;
+        }
+
+        // 'sugar-'
+        if current == 0 && self.string_at(current, &["SUGAR"]) {
+            self.add_alternate("X", "S");
+            return 1;
+        }
+
+        if self.string_at(current, &["SH"]) {
+            // germanic
+            if self.string_at(current + 1, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                self.add("S");
+            } else {
+                self.add("X");
+            }
+            return 2;
+        }
+
+        // italian & armenian
+        if self.string_at(current, &["SIO", "SIA"]) || self.string_at(current, &["SIAN"]) {
+            if self.slavo_germanic {
+                self.add("S");
+            } else {
+                self.add_alternate("S", "X");
+            }
+            return 3;
+        }
+
+        // german & anglicisations, e.g. 'smith' matches 'schmidt', 'snider'
+        // matches 'schneider', and -sz- in slavic languages
+        if (current == 0 && self.string_at(current + 1, &["M", "N", "L", "W"]))
+            || self.string_at(current + 1, &["Z"])
+        {
+            self.add_alternate("S", "X");
+            return if self.string_at(current + 1, &["Z"]) {
+                2
+            } else {
+                1
+            };
+        }
+
+        if self.string_at(current, &["SC"]) {
+            // Schlesinger's rule
+            if self.at(current + 2) == 'H' {
+                // dutch origin, e.g. 'school', 'schooner'
+                if self.string_at(current + 3, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                    // 'schermerhorn', 'schenker'
+                    if self.string_at(current + 3, &["ER", "EN"]) {
+                        self.add_alternate("X", "SK");
+                    } else {
+                        self.add("SK");
+                    }
+                } else if current == 0 && !self.is_vowel(3) && self.at(3) != 'W' {
+                    self.add_alternate("X", "S");
+                } else {
+                    self.add("X");
+                }
+            } else if self.string_at(current + 2, &["I", "E", "Y"]) {
+                self.add("S");
+            } else {
+                self.add("SK");
+            }
+            return 3;
+        }
+
+        // french, e.g. 'resnais', 'artois'
+        if current == last && self.string_at(current - 2, &["AI", "OI"]) {
+            self.add_alternate("", "S");
+        } else {
+            self.add("S");
+        }
+
+        if self.string_at(current + 1, &["S", "Z"]) {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn encode_w(&mut self, current: isize, last: isize) -> isize {
+        // can also be in the middle of the word
+        if self.string_at(current, &["WR"]) {
+            self.add("R");
+            return 2;
+        }
+
+        if current == 0 && (self.is_vowel(current + 1) || self.string_at(current, &["WH"])) {
+            if self.is_vowel(current + 1) {
+                // 'Wasserman' should match 'Vasserman'
+                self.add_alternate("A", "F");
+            } else {
+                // 'Uomo' should match 'Womo'
+                self.add("A");
+            }
+        }
+
+        // 'Arnow' should match 'Arnoff'
+        if (current == last && self.is_vowel(current - 1))
+            || self.string_at(current - 1, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+            || self.string_at(0, &["SCH"])
+        {
+            self.add_alternate("", "F");
+            return 1;
+        }
+
+        // polish, e.g. 'filipowicz'
+        if self.string_at(current, &["WICZ", "WITZ"]) {
+            self.add_alternate("TS", "FX");
+            return 4;
+        }
+
+        1
+    }
+}
+
+/// Encodes `s` with the Double Metaphone algorithm by Lawrence Philips and
+/// returns the primary and the alternate code, both at most 4 characters
+/// long. The alternate code is empty when the word has no alternate
+/// pronunciation.
+pub fn double_metaphone(s: &str) -> (String, String) {
+    Encoder::new(s).encode()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(primary: &str, secondary: &str) -> (String, String) {
+        (primary.to_string(), secondary.to_string())
+    }
+
+    #[test]
+    fn test_double_metaphone() {
+        assert_eq!(double_metaphone("Smith"), codes("SM0", "XMT"));
+        assert_eq!(double_metaphone("Schmidt"), codes("XMT", "SMT"));
+        assert_eq!(double_metaphone("Thompson"), codes("TMPS", ""));
+        assert_eq!(double_metaphone("Jose"), codes("HS", ""));
+        assert_eq!(double_metaphone("Arnow"), codes("ARN", "ARNF"));
+        assert_eq!(double_metaphone("Xavier"), codes("SF", "SFR"));
+        assert_eq!(double_metaphone("Michael"), codes("MKL", "MXL"));
+        assert_eq!(double_metaphone("Edge"), codes("AJ", ""));
+        assert_eq!(double_metaphone("Caesar"), codes("SSR", ""));
+        assert_eq!(double_metaphone("Laugh"), codes("LF", ""));
+        assert_eq!(double_metaphone("Wasserman"), codes("ASRM", "FSRM"));
+    }
+
+    #[test]
+    fn test_double_metaphone_truncates() {
+        assert_eq!(double_metaphone("Maclaverty"), codes("MKLF", ""));
+        assert_eq!(double_metaphone(""), codes("", ""));
+    }
+}
@@ -0,0 +1,191 @@
+fn is_vowel(c: Option<char>) -> bool {
+    matches!(c, Some('A' | 'E' | 'I' | 'O' | 'U'))
+}
+
+/// Encodes `s` with the original Metaphone algorithm by Lawrence Philips.
+/// Everything but ASCII letters is ignored and the code is not truncated.
+/// `0` stands for the "th" sound and `X` for "sh".
+pub fn metaphone(s: &str) -> String {
+    let mut word: Vec<char> = s
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    // silent first letters
+    if matches!(
+        word.as_slice(),
+        ['K', 'N', ..] | ['G', 'N', ..] | ['P', 'N', ..] | ['A', 'E', ..] | ['W', 'R', ..]
+    ) {
+        word.remove(0);
+    }
+
+    let at = |i: usize| word.get(i).copied();
+    let mut result = String::with_capacity(word.len());
+    let mut i = 0;
+    while i < word.len() {
+        let c = word[i];
+        let prev = i.checked_sub(1).and_then(at);
+        let next = at(i + 1);
+        let after_next = at(i + 2);
+
+        // duplicate letters are encoded once, except for `C`
+        if prev == Some(c) && c != 'C' {
+            i += 1;
+            continue;
+        }
+
+        match c {
+            'A' | 'E' | 'I' | 'O' | 'U' => {
+                if i == 0 {
+                    result.push(c);
+                }
+            }
+            'B' => {
+                // silent in a trailing "MB"
+                if !(prev == Some('M') && next.is_none()) {
+                    result.push('B');
+                }
+            }
+            'C' => {
+                if next == Some('I') && after_next == Some('A') {
+                    result.push('X');
+                } else if next == Some('H') {
+                    result.push(if prev == Some('S') { 'K' } else { 'X' });
+                    i += 1;
+                } else if matches!(next, Some('I' | 'E' | 'Y')) {
+                    // silent in "SCI", "SCE" and "SCY"
+                    if prev != Some('S') {
+                        result.push('S');
+                    }
+                } else {
+                    result.push('K');
+                }
+            }
+            'D' => {
+                if next == Some('G') && matches!(after_next, Some('E' | 'I' | 'Y')) {
+                    result.push('J');
+                    i += 2;
+                } else {
+                    result.push('T');
+                }
+            }
+            'G' => {
+                let silent = if next == Some('H') {
+                    // "GH" is silent unless it ends the word or precedes a vowel
+                    !(after_next.is_none() || is_vowel(after_next))
+                } else if next == Some('N') {
+                    // "GN" and "GNED" at the end of the word
+                    after_next.is_none()
+                        || (after_next == Some('E')
+                            && at(i + 3) == Some('D')
+                            && i + 4 == word.len())
+                } else {
+                    false
+                };
+
+                if !silent {
+                    if matches!(next, Some('I' | 'E' | 'Y')) && prev != Some('G') {
+                        result.push('J');
+                    } else {
+                        result.push('K');
+                    }
+                }
+            }
+            'H' => {
+                // silent after a vowel, unless a vowel follows as well, and
+                // in the digraphs handled by the preceding letter
+                if (!is_vowel(prev) || is_vowel(next))
+                    && !matches!(prev, Some('C' | 'G' | 'P' | 'S' | 'T'))
+                {
+                    result.push('H');
+                }
+            }
+            'K' => {
+                if prev != Some('C') {
+                    result.push('K');
+                }
+            }
+            'P' => {
+                if next == Some('H') {
+                    result.push('F');
+                } else {
+                    result.push('P');
+                }
+            }
+            'Q' => result.push('K'),
+            'S' => {
+                if next == Some('H') || (next == Some('I') && matches!(after_next, Some('O' | 'A')))
+                {
+                    result.push('X');
+                } else {
+                    result.push('S');
+                }
+            }
+            'T' => {
+                if next == Some('I') && matches!(after_next, Some('O' | 'A')) {
+                    result.push('X');
+                } else if next == Some('H') {
+                    result.push('0');
+                } else if !(next == Some('C') && after_next == Some('H')) {
+                    result.push('T');
+                }
+            }
+            'V' => result.push('F'),
+            'W' => {
+                if i == 0 && next == Some('H') {
+                    result.push('W');
+                    i += 1;
+                } else if is_vowel(next) {
+                    result.push('W');
+                }
+            }
+            'X' => {
+                if i == 0 {
+                    result.push('S');
+                } else {
+                    result.push_str("KS");
+                }
+            }
+            'Y' => {
+                if is_vowel(next) {
+                    result.push('Y');
+                }
+            }
+            'Z' => result.push('S'),
+            // F, J, L, M, N and R are kept as they are
+            _ => result.push(c),
+        }
+        i += 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metaphone() {
+        assert_eq!(metaphone("Smith"), "SM0");
+        assert_eq!(metaphone("Smyth"), "SM0");
+        assert_eq!(metaphone("Thumb"), "0M");
+        assert_eq!(metaphone("Knight"), "NT");
+        assert_eq!(metaphone("Phillip"), "FLP");
+        assert_eq!(metaphone("Xavier"), "SFR");
+        assert_eq!(metaphone("Wright"), "RT");
+        assert_eq!(metaphone("White"), "WT");
+        assert_eq!(metaphone("Judge"), "JJ");
+        assert_eq!(metaphone("Science"), "SNS");
+        assert_eq!(metaphone("Nation"), "NXN");
+        assert_eq!(metaphone("Accident"), "AKSTNT");
+    }
+
+    #[test]
+    fn test_metaphone_ignores_non_letters() {
+        assert_eq!(metaphone("Mc-Donald"), metaphone("mcdonald"));
+        assert_eq!(metaphone(""), "");
+        assert_eq!(metaphone("42"), "");
+    }
+}
@@ -0,0 +1,110 @@
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'A' | 'E' | 'I' | 'O' | 'U')
+}
+
+/// Encodes `s` with the New York State Identification and Intelligence
+/// System algorithm. Everything but ASCII letters is ignored and the code is
+/// not truncated to the traditional six characters.
+pub fn nysiis(s: &str) -> String {
+    let mut word: Vec<char> = s
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    if word.is_empty() {
+        return String::new();
+    }
+
+    // translate the first characters
+    match word.as_slice() {
+        ['M', 'A', 'C', ..] => word[1] = 'C',
+        ['K', 'N', ..] => {
+            word.remove(0);
+        }
+        ['K', ..] => word[0] = 'C',
+        ['P', 'H' | 'F', ..] => word[..2].copy_from_slice(&['F', 'F']),
+        ['S', 'C', 'H', ..] => word[..3].copy_from_slice(&['S', 'S', 'S']),
+        _ => {}
+    }
+
+    // translate the last characters
+    match word.as_slice() {
+        [.., 'E' | 'I', 'E'] => {
+            word.truncate(word.len() - 2);
+            word.push('Y');
+        }
+        [.., 'D' | 'R' | 'N', 'T'] | [.., 'R' | 'N', 'D'] => {
+            word.truncate(word.len() - 2);
+            word.push('D');
+        }
+        _ => {}
+    }
+
+    // translate the remaining characters in place, so later rules see the
+    // translated previous character
+    let mut key = vec![word[0]];
+    for i in 1..word.len() {
+        let prev = word[i - 1];
+        let next = word.get(i + 1).copied();
+
+        match word[i] {
+            'E' if next == Some('V') => word[i..i + 2].copy_from_slice(&['A', 'F']),
+            'A' | 'E' | 'I' | 'O' | 'U' => word[i] = 'A',
+            'Q' => word[i] = 'G',
+            'Z' => word[i] = 'S',
+            'M' => word[i] = 'N',
+            'K' if next == Some('N') => word[i] = 'N',
+            'K' => word[i] = 'C',
+            'S' if next == Some('C') && word.get(i + 2) == Some(&'H') => {
+                word[i..i + 3].copy_from_slice(&['S', 'S', 'S'])
+            }
+            'P' if next == Some('H') => word[i..i + 2].copy_from_slice(&['F', 'F']),
+            'H' if !is_vowel(prev) || !next.is_some_and(is_vowel) => word[i] = prev,
+            'W' if is_vowel(prev) => word[i] = prev,
+            _ => {}
+        }
+
+        if key.last() != Some(&word[i]) {
+            key.push(word[i]);
+        }
+    }
+
+    // clean up the end of the key
+    if key.len() > 1 && key.last() == Some(&'S') {
+        key.pop();
+    }
+    if key.ends_with(&['A', 'Y']) {
+        key.remove(key.len() - 2);
+    }
+    if key.len() > 1 && key.last() == Some(&'A') {
+        key.pop();
+    }
+
+    key.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nysiis() {
+        assert_eq!(nysiis("Brown"), "BRAN");
+        assert_eq!(nysiis("Browne"), "BRAN");
+        assert_eq!(nysiis("Knight"), "NAGT");
+        assert_eq!(nysiis("Mitchell"), "MATCAL");
+        assert_eq!(nysiis("Macintosh"), "MCANT");
+        assert_eq!(nysiis("Schmidt"), "SNAD");
+        assert_eq!(nysiis("Phillipson"), "FALAPSAN");
+        assert_eq!(nysiis("Worthy"), "WARTY");
+        assert_eq!(nysiis("Evans"), "EVAN");
+    }
+
+    #[test]
+    fn test_nysiis_ignores_non_letters() {
+        assert_eq!(nysiis("O'Daniel"), "ODANAL");
+        assert_eq!(nysiis(""), "");
+        assert_eq!(nysiis("1984"), "");
+    }
+}
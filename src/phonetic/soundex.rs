@@ -0,0 +1,73 @@
+/// Soundex digit of a letter. Vowels (and `Y`) separate equal digits, while
+/// `H` and `W` are skipped without separating them.
+fn code(c: char) -> Option<char> {
+    match c {
+        'B' | 'F' | 'P' | 'V' => Some('1'),
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => Some('2'),
+        'D' | 'T' => Some('3'),
+        'L' => Some('4'),
+        'M' | 'N' => Some('5'),
+        'R' => Some('6'),
+        'H' | 'W' => None,
+        _ => Some('0'),
+    }
+}
+
+/// Encodes `s` with American Soundex: the first letter followed by three
+/// digits. Everything but ASCII letters is ignored, so a string without any
+/// letters has an empty code.
+pub fn soundex(s: &str) -> String {
+    let mut letters = s
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase());
+
+    let Some(first) = letters.next() else {
+        return String::new();
+    };
+
+    let mut result = String::with_capacity(4);
+    result.push(first);
+    let mut last = code(first);
+    for c in letters {
+        if result.len() == 4 {
+            break;
+        }
+        match code(c) {
+            None => continue,
+            Some(digit) if digit != '0' && Some(digit) != last => result.push(digit),
+            _ => {}
+        }
+        last = code(c);
+    }
+
+    while result.len() < 4 {
+        result.push('0');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_soundex() {
+        assert_eq!(soundex("Robert"), "R163");
+        assert_eq!(soundex("Rupert"), "R163");
+        assert_eq!(soundex("Rubin"), "R150");
+        assert_eq!(soundex("Ashcraft"), "A261");
+        assert_eq!(soundex("Ashcroft"), "A261");
+        assert_eq!(soundex("Tymczak"), "T522");
+        assert_eq!(soundex("Pfister"), "P236");
+        assert_eq!(soundex("Honeyman"), "H555");
+        assert_eq!(soundex("Lee"), "L000");
+    }
+
+    #[test]
+    fn test_soundex_ignores_non_letters() {
+        assert_eq!(soundex("o'hara"), soundex("OHARA"));
+        assert_eq!(soundex(""), "");
+        assert_eq!(soundex("1234"), "");
+    }
+}
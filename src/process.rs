@@ -1,4 +1,5 @@
-use crate::distance::get_scorer_flags;
+use crate::common::{conv_sequence_view, exact_comparison, with_view, SeqElem, SeqView};
+use crate::distance::{batch, get_scorer_flags};
 use pyo3::prelude::*;
 use pyo3::types::{IntoPyDict, PyIterator, PyList, PyMapping, PySequence, PyTuple};
 use std::collections::HashMap;
@@ -14,6 +15,226 @@ fn get_scorer_bounds(
 
 type ExtractResult<'py> = (Bound<'py, PyAny>, f64, Bound<'py, PyAny>);
 
+#[derive(Clone, Copy)]
+enum BatchMetric {
+    #[cfg(feature = "levenshtein")]
+    Levenshtein,
+    Indel,
+    LcsSeq,
+}
+
+#[derive(Clone, Copy)]
+enum BatchKind {
+    Distance,
+    Similarity,
+    NormalizedDistance,
+    NormalizedSimilarity,
+}
+
+/// Scorer, which can be computed for many short choices at once using the
+/// kernels in `distance::batch`.
+#[derive(Clone, Copy)]
+pub struct BatchScorer {
+    metric: BatchMetric,
+    kind: BatchKind,
+    score_cutoff: Option<usize>,
+}
+
+impl BatchScorer {
+    /// Returns `None` when the scorer or its arguments are not supported.
+    /// `score_cutoff` is the cutoff that is passed on to the scorer.
+    #[cfg_attr(not(feature = "levenshtein"), allow(unused_variables))]
+    pub fn new(
+        scorer: &Bound<'_, PyAny>,
+        scorer_kwargs: &HashMap<String, PyObject>,
+        score_cutoff: Option<usize>,
+    ) -> Option<Self> {
+        if exact_comparison(scorer.py()).ok()? {
+            return None;
+        }
+
+        let module: String = scorer.getattr("__module__").ok()?.extract().ok()?;
+        let name: String = scorer.getattr("__name__").ok()?.extract().ok()?;
+
+        let metric = match module.as_str() {
+            #[cfg(feature = "levenshtein")]
+            "levenshtein" => BatchMetric::Levenshtein,
+            "indel" => BatchMetric::Indel,
+            "lcs_seq" => BatchMetric::LcsSeq,
+            _ => return None,
+        };
+        let kind = match name.as_str() {
+            "distance" => BatchKind::Distance,
+            "similarity" => BatchKind::Similarity,
+            "normalized_distance" => BatchKind::NormalizedDistance,
+            "normalized_similarity" => BatchKind::NormalizedSimilarity,
+            _ => return None,
+        };
+
+        for (key, value) in scorer_kwargs {
+            let supported = match (key.as_str(), metric) {
+                #[cfg(feature = "levenshtein")]
+                ("weights", BatchMetric::Levenshtein) => matches!(
+                    value.extract::<Option<(usize, usize, usize)>>(scorer.py()),
+                    Ok(None | Some((1, 1, 1)))
+                ),
+                #[cfg(feature = "levenshtein")]
+                ("costs", BatchMetric::Levenshtein) => value.is_none(scorer.py()),
+                _ => false,
+            };
+            if !supported {
+                return None;
+            }
+        }
+
+        Some(Self {
+            metric,
+            kind,
+            score_cutoff,
+        })
+    }
+
+    /// Computes the distance, or the similarity for LCS, between the query and
+    /// every choice.
+    fn raw_scores<Q: SeqElem, C: SeqElem>(&self, query: &[Q], choices: &[&[C]]) -> Vec<usize> {
+        match self.metric {
+            #[cfg(feature = "levenshtein")]
+            BatchMetric::Levenshtein => batch::levenshtein_distance(query, choices),
+            BatchMetric::Indel => batch::indel_distance(query, choices),
+            BatchMetric::LcsSeq => batch::lcs_seq_similarity(query, choices),
+        }
+    }
+
+    /// Turns a raw score into the result of the scorer, including its handling
+    /// of `score_cutoff`. Both sequences are non-empty.
+    fn score(&self, len1: usize, len2: usize, raw: usize) -> f64 {
+        let (maximum, dist) = match self.metric {
+            #[cfg(feature = "levenshtein")]
+            BatchMetric::Levenshtein => (len1.max(len2), raw),
+            BatchMetric::Indel => (len1 + len2, raw),
+            BatchMetric::LcsSeq => (len1.max(len2), len1.max(len2) - raw),
+        };
+        let norm_dist = dist as f64 / maximum as f64;
+
+        match (self.kind, self.score_cutoff) {
+            (BatchKind::Distance, Some(cutoff)) if dist > cutoff => (cutoff + 1) as f64,
+            (BatchKind::Distance, _) => dist as f64,
+            (BatchKind::Similarity, Some(cutoff)) if maximum - dist < cutoff => 0.0,
+            (BatchKind::Similarity, _) => (maximum - dist) as f64,
+            (BatchKind::NormalizedDistance, Some(cutoff)) if norm_dist > cutoff as f64 => 1.0,
+            (BatchKind::NormalizedDistance, _) => norm_dist,
+            (BatchKind::NormalizedSimilarity, Some(cutoff)) if 1.0 - norm_dist < cutoff as f64 => {
+                0.0
+            }
+            (BatchKind::NormalizedSimilarity, _) => 1.0 - norm_dist,
+        }
+    }
+
+    /// Scores the processed query against all choices. Choices that are too
+    /// long for a batch, empty or can't be converted are passed to `scorer`
+    /// instead. `None` choices are skipped and get no score.
+    fn score_choices<'py>(
+        &self,
+        processed_query: &Bound<'py, PyAny>,
+        items: &[(Bound<'py, PyAny>, Bound<'py, PyAny>)],
+        scorer: &impl Fn(&Bound<'py, PyAny>, &Bound<'py, PyAny>) -> PyResult<f64>,
+        processor: &Option<impl Fn(&Bound<'py, PyAny>) -> Bound<'py, PyAny>>,
+    ) -> PyResult<Vec<Option<f64>>> {
+        let query = match conv_sequence_view(processed_query) {
+            Ok(Some(query)) if !query.is_empty() => Some(query),
+            _ => None,
+        };
+
+        let mut processed = Vec::with_capacity(items.len());
+        for (i, (_, choice)) in items.iter().enumerate() {
+            if choice.is_none() {
+                continue;
+            }
+            let choice = match processor {
+                Some(proc) => proc(choice),
+                None => choice.to_owned(),
+            };
+            processed.push((i, choice));
+        }
+
+        // the kernels are instantiated per element width, so the choices are
+        // grouped by the width of their storage
+        let mut scores = vec![None; items.len()];
+        let (mut ucs1, mut ucs2, mut ucs4) = (Vec::new(), Vec::new(), Vec::new());
+        for (i, choice) in &processed {
+            match (&query, conv_sequence_view(choice)) {
+                (Some(_), Ok(Some(seq))) if !seq.is_empty() && seq.len() <= batch::MAX_LEN => {
+                    match seq {
+                        SeqView::Ucs1(seq) => ucs1.push((*i, seq)),
+                        SeqView::Ucs2(seq) => ucs2.push((*i, seq)),
+                        SeqView::Ucs4(seq) => ucs4.push((*i, seq)),
+                    }
+                }
+                _ => scores[*i] = Some(scorer(processed_query, choice)?),
+            }
+        }
+
+        if let Some(query) = query {
+            with_view!(&query, |query| {
+                self.score_group(query, &ucs1, &mut scores);
+                self.score_group(query, &ucs2, &mut scores);
+                self.score_group(query, &ucs4, &mut scores);
+            });
+        }
+
+        Ok(scores)
+    }
+
+    /// Scores the query against the `(index, choice)` pairs of `group`.
+    fn score_group<Q: SeqElem, C: SeqElem>(
+        &self,
+        query: &[Q],
+        group: &[(usize, impl AsRef<[C]>)],
+        scores: &mut [Option<f64>],
+    ) {
+        if group.is_empty() {
+            return;
+        }
+        let choices = group
+            .iter()
+            .map(|(_, seq)| seq.as_ref())
+            .collect::<Vec<_>>();
+        let raw_scores = self.raw_scores(query, &choices);
+        for ((i, seq), raw) in group.iter().zip(raw_scores) {
+            scores[*i] = Some(self.score(query.len(), seq.as_ref().len(), raw));
+        }
+    }
+}
+
+/// Collects the choices as `(key, choice)` pairs. The key is the index of the
+/// choice, unless `choices` is a mapping.
+fn collect_items<'py>(
+    py: Python<'py>,
+    choices: &Bound<'py, PyAny>,
+) -> PyResult<Vec<(Bound<'py, PyAny>, Bound<'py, PyAny>)>> {
+    let choices_iter = match choices.downcast::<PyMapping>() {
+        Ok(mapping) => mapping.items()?.iter()?,
+        Err(_) => {
+            let items = choices
+                .downcast::<PySequence>()?
+                .iter()?
+                .enumerate()
+                .map(|(i, v)| PyTuple::new_bound(py, &[i.into_py(py), v.unwrap().into_py(py)]))
+                .collect::<Vec<_>>();
+            // Convert to sequence and get iterator
+            PyIterator::from_bound_object(&PyList::new_bound(py, items))?
+        }
+    };
+
+    choices_iter
+        .map(|item| {
+            let item = item?;
+            let key_choice = item.downcast::<PyTuple>()?;
+            Ok((key_choice.get_item(0)?, key_choice.get_item(1)?))
+        })
+        .collect()
+}
+
 #[pyclass]
 pub struct ExtractIter {
     inner: Vec<(PyObject, f64, PyObject)>,
@@ -150,6 +371,7 @@ pub fn py_extract_iter<'py>(
 
     let mut scorer_kwargs = scorer_kwargs.unwrap_or_default();
     let (worst_score, optimal_score) = get_scorer_bounds(&scorer, &scorer_kwargs);
+    let batch_scorer = BatchScorer::new(&scorer, &scorer_kwargs, score_cutoff.map(|c| c as usize));
     scorer_kwargs.insert(
         "score_cutoff".to_string(),
         // TODO: make cutoff type dependent on scorer
@@ -174,6 +396,7 @@ pub fn py_extract_iter<'py>(
         processor_fn,
         score_cutoff,
         (worst_score, optimal_score),
+        batch_scorer,
     )?;
 
     let converted_results: Vec<(PyObject, f64, PyObject)> = results
@@ -191,6 +414,7 @@ pub fn extract_iter<'py>(
     processor: Option<impl Fn(&Bound<'py, PyAny>) -> Bound<'py, PyAny>>,
     score_cutoff: Option<f64>,
     bounds: (usize, usize),
+    batch_scorer: Option<BatchScorer>,
 ) -> PyResult<Vec<ExtractResult<'py>>> {
     let (worst_score, optimal_score) = bounds;
     let lowest_score_worst = worst_score < optimal_score;
@@ -207,69 +431,30 @@ pub fn extract_iter<'py>(
         None => query.to_owned(),
     };
 
-    let choices_iter = match choices.downcast::<PyMapping>() {
-        Ok(mapping) => mapping.items()?.iter()?,
-        Err(_) => {
-            let items = choices
-                .downcast::<PySequence>()?
-                .iter()?
-                .enumerate()
-                .map(|(i, v)| {
-                    PyTuple::new_bound(
-                        query.py(),
-                        &[i.into_py(query.py()), v.unwrap().into_py(query.py())],
-                    )
-                })
-                .collect::<Vec<_>>();
-            // Convert to sequence and get iterator
-            PyIterator::from_bound_object(&PyList::new_bound(query.py(), items))?
-        }
-    };
+    let items = collect_items(query.py(), choices)?;
+    let batch_scores = batch_scorer
+        .map(|batch_scorer| {
+            batch_scorer.score_choices(&processed_query, &items, &scorer, &processor)
+        })
+        .transpose()?;
 
-    // choices_iter
-    //     .into_iter()
-    //     .filter_map(|item| {
-    //         let key_choice = item
-    //             .downcast::<PyTuple>()
-    //             .expect("error converting to tuple");
-    //         let key = key_choice.get_item(0).expect("error getting key");
-    //         let choice = key_choice.get_item(1).expect("error getting choice");
-    //
-    //         if choice.is_none() {
-    //             return None;
-    //         }
-    //
-    //         let score = match &processor {
-    //             Some(proc) => scorer(&processed_query, &proc(&choice)),
-    //             None => scorer(&processed_query, &choice),
-    //         };
-    //
-    //         if (lowest_score_worst && score >= score_cutoff)
-    //             || (!lowest_score_worst && score <= score_cutoff)
-    //         {
-    //             Some((choice, score, key))
-    //         } else {
-    //             None
-    //         }
-    //     })
-    //     .collect()
-
-    // TODO: process items during iteration instead of collecting to vector first
     let mut results = Vec::new();
 
-    for item in choices_iter {
-        let item = item?;
-        let key_choice = item.downcast::<PyTuple>()?;
-        let key = key_choice.get_item(0)?;
-        let choice = key_choice.get_item(1)?;
-
-        if choice.is_none() {
-            continue;
-        }
-
-        let score = match &processor {
-            Some(proc) => scorer(&processed_query, &proc(&choice))?,
-            None => scorer(&processed_query, &choice)?,
+    for (i, (key, choice)) in items.into_iter().enumerate() {
+        let score = match &batch_scores {
+            Some(scores) => match scores[i] {
+                Some(score) => score,
+                None => continue,
+            },
+            None => {
+                if choice.is_none() {
+                    continue;
+                }
+                match &processor {
+                    Some(proc) => scorer(&processed_query, &proc(&choice))?,
+                    None => scorer(&processed_query, &choice)?,
+                }
+            }
         };
 
         if (lowest_score_worst && score >= score_cutoff)
@@ -423,6 +608,7 @@ pub fn py_extract_one<'py>(
 
     let mut scorer_kwargs = scorer_kwargs.unwrap_or_default();
     let (worst_score, optimal_score) = get_scorer_bounds(&scorer, &scorer_kwargs);
+    let batch_scorer = BatchScorer::new(&scorer, &scorer_kwargs, score_cutoff.map(|c| c as usize));
     scorer_kwargs.insert(
         "score_cutoff".to_string(),
         // TODO: make cutoff type dependent on scorer
@@ -447,6 +633,7 @@ pub fn py_extract_one<'py>(
         processor_fn,
         score_cutoff,
         (worst_score, optimal_score),
+        batch_scorer,
     )
 }
 
@@ -457,6 +644,7 @@ pub fn extract_one<'py>(
     processor: Option<impl Fn(&Bound<'py, PyAny>) -> Bound<'py, PyAny>>,
     score_cutoff: Option<f64>,
     bounds: (usize, usize),
+    batch_scorer: Option<BatchScorer>,
 ) -> PyResult<Option<ExtractResult<'py>>> {
     let (worst_score, optimal_score) = bounds;
     let lowest_score_worst = worst_score < optimal_score;
@@ -475,53 +663,29 @@ pub fn extract_one<'py>(
 
     let mut result: Option<(Bound<'py, PyAny>, f64, Bound<'py, PyAny>)> = None;
 
-    // let choices_iter = match choices.downcast::<PyMapping>() {
-    //     Ok(mapping) => mapping
-    //         .items()
-    //         .expect("error getting items")
-    //         .iter()
-    //         .expect("error getting iter"),
-    //     Err(_) => choices
-    //         .downcast::<PySequence>()
-    //         .expect("error converting to sequence")
-    //         .iter()
-    //         .expect("error getting iter")
-    //         .enumerate(),
-    // };
-
-    let choices_iter = match choices.downcast::<PyMapping>() {
-        Ok(mapping) => mapping.items()?.iter()?,
-        Err(_) => {
-            let items = choices
-                .downcast::<PySequence>()?
-                .iter()?
-                .enumerate()
-                .map(|(i, v)| {
-                    PyTuple::new_bound(
-                        query.py(),
-                        &[i.into_py(query.py()), v.unwrap().into_py(query.py())],
-                    )
-                })
-                .collect::<Vec<_>>();
-            // Convert to sequence and get iterator
-            PyIterator::from_bound_object(&PyList::new_bound(query.py(), items))?
-        }
-    };
+    let items = collect_items(query.py(), choices)?;
+    let batch_scores = batch_scorer
+        .map(|batch_scorer| {
+            batch_scorer.score_choices(&processed_query, &items, &scorer, &processor)
+        })
+        .transpose()?;
 
     let mut current_score_cutoff = score_cutoff;
-    for item in choices_iter {
-        let item = item?;
-        let key_choice = item.downcast::<PyTuple>()?;
-        let key = key_choice.get_item(0)?;
-        let choice = key_choice.get_item(1)?;
-
-        if choice.is_none() {
-            continue;
-        }
-
-        let score = match &processor {
-            Some(proc) => scorer(&processed_query, &proc(&choice))?,
-            None => scorer(&processed_query, &choice)?,
+    for (i, (key, choice)) in items.into_iter().enumerate() {
+        let score = match &batch_scores {
+            Some(scores) => match scores[i] {
+                Some(score) => score,
+                None => continue,
+            },
+            None => {
+                if choice.is_none() {
+                    continue;
+                }
+                match &processor {
+                    Some(proc) => scorer(&processed_query, &proc(&choice))?,
+                    None => scorer(&processed_query, &choice)?,
+                }
+            }
         };
 
         let should_update = if lowest_score_worst {
@@ -635,6 +799,7 @@ pub fn py_extract<'py>(
 
     let mut scorer_kwargs = scorer_kwargs.unwrap_or_default();
     let (worst_score, optimal_score) = get_scorer_bounds(&scorer, &scorer_kwargs);
+    let batch_scorer = BatchScorer::new(&scorer, &scorer_kwargs, score_cutoff.map(|c| c as usize));
     scorer_kwargs.insert(
         "score_cutoff".to_string(),
         // TODO: make cutoff type dependent on scorer
@@ -660,9 +825,11 @@ pub fn py_extract<'py>(
         limit,
         score_cutoff,
         (worst_score, optimal_score),
+        batch_scorer,
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn extract<'py>(
     query: Option<&Bound<'py, PyAny>>,
     choices: &Bound<'py, PyAny>,
@@ -671,6 +838,7 @@ pub fn extract<'py>(
     limit: Option<usize>,
     score_cutoff: Option<f64>,
     bounds: (usize, usize),
+    batch_scorer: Option<BatchScorer>,
 ) -> PyResult<Vec<ExtractResult<'py>>> {
     let (worst_score, optimal_score) = bounds;
     let lowest_score_worst = worst_score < optimal_score;
@@ -683,6 +851,7 @@ pub fn extract<'py>(
             processor,
             score_cutoff,
             (worst_score, optimal_score),
+            batch_scorer,
         )?
         .map_or_else(Vec::new, |res| vec![res]));
     }
@@ -694,6 +863,7 @@ pub fn extract<'py>(
         processor,
         score_cutoff,
         (worst_score, optimal_score),
+        batch_scorer,
     )?;
 
     results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
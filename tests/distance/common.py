@@ -30,10 +30,25 @@ def create_generic_scorer(func_name, get_scorer_flags):
     return GenericScorer(scorers, get_scorer_flags)
 
 
-def get_scorer_flags_damerau_levenshtein(s1, s2, **kwargs):
+def get_scorer_flags_damerau_levenshtein(s1, s2, weights=(1, 1, 1, 1), **kwargs):
+    insert_cost, delete_cost, replace_cost, _ = weights
+    symmetric = insert_cost == delete_cost
+
     if is_none(s1) or is_none(s2):
-        return {"maximum": None, "symmetric": True}
-    return {"maximum": max(len(s1), len(s2)), "symmetric": True}
+        return {"maximum": None, "symmetric": symmetric}
+
+    max_dist = len(s1) * delete_cost + len(s2) * insert_cost
+
+    if len(s1) >= len(s2):
+        max_dist = min(
+            max_dist, len(s2) * replace_cost + (len(s1) - len(s2)) * delete_cost
+        )
+    else:
+        max_dist = min(
+            max_dist, len(s1) * replace_cost + (len(s2) - len(s1)) * insert_cost
+        )
+
+    return {"maximum": max_dist, "symmetric": symmetric}
 
 
 DamerauLevenshtein = create_generic_scorer(
@@ -42,6 +57,36 @@ DamerauLevenshtein = create_generic_scorer(
 )
 
 
+def get_scorer_flags_editex(s1, s2, **kwargs):
+    if is_none(s1) or is_none(s2):
+        return {"maximum": None, "symmetric": True}
+    return {"maximum": 2 * max(len(s1), len(s2)), "symmetric": True}
+
+
+Editex = create_generic_scorer("editex", get_scorer_flags_editex)
+
+
+def get_scorer_flags_gotoh(s1, s2, weights=(1, 1, 1), **kwargs):
+    gap_open, gap_extend, replace_cost = weights
+    gap_extend = min(gap_open, gap_extend)
+
+    if is_none(s1) or is_none(s2):
+        return {"maximum": None, "symmetric": True}
+
+    def gap_cost(length):
+        return gap_open + (length - 1) * gap_extend if length else 0
+
+    max_dist = gap_cost(len(s1)) + gap_cost(len(s2))
+    max_dist = min(
+        max_dist,
+        min(len(s1), len(s2)) * replace_cost + gap_cost(abs(len(s1) - len(s2))),
+    )
+    return {"maximum": max_dist, "symmetric": True}
+
+
+Gotoh = create_generic_scorer("gotoh", get_scorer_flags_gotoh)
+
+
 def get_scorer_flags_hamming(s1, s2, **kwargs):
     if is_none(s1) or is_none(s2):
         return {"maximum": None, "symmetric": True}
@@ -87,6 +132,15 @@ def get_scorer_flags_lcs_seq(s1, s2, **kwargs):
 LCSseq = create_generic_scorer("lcs_seq", get_scorer_flags_lcs_seq)
 
 
+def get_scorer_flags_lcs_str(s1, s2, **kwargs):
+    if is_none(s1) or is_none(s2):
+        return {"maximum": None, "symmetric": True}
+    return {"maximum": max(len(s1), len(s2)), "symmetric": True}
+
+
+LCSstr = create_generic_scorer("lcs_str", get_scorer_flags_lcs_str)
+
+
 def get_scorer_flags_levenshtein(s1, s2, weights=(1, 1, 1), **kwargs):
     insert_cost, delete_cost, replace_cost = weights
 
@@ -110,10 +164,34 @@ def get_scorer_flags_levenshtein(s1, s2, weights=(1, 1, 1), **kwargs):
 Levenshtein = create_generic_scorer("levenshtein", get_scorer_flags_levenshtein)
 
 
-def get_scorer_flags_osa(s1, s2, **kwargs):
+def get_scorer_flags_mra(s1, s2, **kwargs):
     if is_none(s1) or is_none(s2):
         return {"maximum": None, "symmetric": True}
-    return {"maximum": max(len(s1), len(s2)), "symmetric": True}
+    return {"maximum": 6, "symmetric": True}
+
+
+MRA = create_generic_scorer("mra", get_scorer_flags_mra)
+
+
+def get_scorer_flags_osa(s1, s2, weights=(1, 1, 1, 1), **kwargs):
+    insert_cost, delete_cost, replace_cost, _ = weights
+    symmetric = insert_cost == delete_cost
+
+    if is_none(s1) or is_none(s2):
+        return {"maximum": None, "symmetric": symmetric}
+
+    max_dist = len(s1) * delete_cost + len(s2) * insert_cost
+
+    if len(s1) >= len(s2):
+        max_dist = min(
+            max_dist, len(s2) * replace_cost + (len(s1) - len(s2)) * delete_cost
+        )
+    else:
+        max_dist = min(
+            max_dist, len(s1) * replace_cost + (len(s2) - len(s1)) * insert_cost
+        )
+
+    return {"maximum": max_dist, "symmetric": symmetric}
 
 
 OSA = create_generic_scorer("osa", get_scorer_flags_osa)
@@ -136,15 +214,60 @@ def get_scorer_flags_prefix(s1, s2, **kwargs):
 
 Prefix = create_generic_scorer("prefix", get_scorer_flags_prefix)
 
+
+def get_scorer_flags_ratcliff_obershelp(s1, s2, **kwargs):
+    if is_none(s1) or is_none(s2):
+        return {"maximum": None, "symmetric": False}
+    return {"maximum": len(s1) + len(s2), "symmetric": False}
+
+
+RatcliffObershelp = create_generic_scorer(
+    "ratcliff_obershelp", get_scorer_flags_ratcliff_obershelp
+)
+
+
+def get_scorer_flags_smith_waterman(s1, s2, weights=(1, 1, 1, 1), **kwargs):
+    if is_none(s1) or is_none(s2):
+        return {"maximum": None, "symmetric": True}
+    return {"maximum": min(len(s1), len(s2)) * weights[0], "symmetric": True}
+
+
+SmithWaterman = create_generic_scorer(
+    "smith_waterman", get_scorer_flags_smith_waterman
+)
+
+
+def get_scorer_flags_qgram(s1, s2, **kwargs):
+    if is_none(s1) or is_none(s2):
+        return {"maximum": None, "symmetric": True}
+    return {"maximum": 1.0, "symmetric": True}
+
+
+Jaccard = create_generic_scorer("jaccard", get_scorer_flags_qgram)
+SorensenDice = create_generic_scorer("sorensen_dice", get_scorer_flags_qgram)
+Cosine = create_generic_scorer("cosine", get_scorer_flags_qgram)
+Overlap = create_generic_scorer("overlap", get_scorer_flags_qgram)
+
 all_scorer_modules = [
+    Cosine,
     DamerauLevenshtein,
+    Editex,
+    Gotoh,
     Hamming,
     Indel,
+    Jaccard,
     Jaro,
     JaroWinkler,
     LCSseq,
+    LCSstr,
     Levenshtein,
+    MRA,
     OSA,
+    Overlap,
     Postfix,
     Prefix,
+    RatcliffObershelp,
+    SmithWaterman,
+    SorensenDice,
 ]
+
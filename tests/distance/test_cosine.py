@@ -0,0 +1,25 @@
+"""Tests for the cosine distance metrics."""
+
+from __future__ import annotations
+
+import pytest
+
+from tests.distance.common import Cosine
+
+
+def test_empty_string():
+    assert Cosine.similarity("", "") == 1.0
+    assert Cosine.similarity("", "ab") == 0.0
+
+
+def test_similar_strings():
+    assert pytest.approx(Cosine.similarity("abcd", "bcde")) == 2 / 3
+    assert Cosine.similarity("night", "nacht") == 0.25
+
+
+def test_multiset():
+    assert Cosine.similarity("aaaa", "aa") == 1.0
+    # aa:3 | aa:1 ab:1
+    assert pytest.approx(
+        Cosine.similarity("aaaa", "aab", multiset=True)
+    ) == 3 / 18**0.5
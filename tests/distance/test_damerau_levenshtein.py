@@ -41,3 +41,19 @@ def testCaseInsensitive():
         )
         == 0
     )
+
+
+def test_weights():
+    """
+    transpositions can be cheaper than substitutions
+    """
+    assert DamerauLevenshtein.distance("ab", "ba", weights=(1, 1, 2, 1)) == 1
+    assert DamerauLevenshtein.distance("ab", "ba", weights=(1, 1, 2, 3)) == 2
+    assert DamerauLevenshtein.distance("ca", "abc", weights=(2, 2, 2, 1)) == 3
+    assert DamerauLevenshtein.distance("abcd", "badc", weights=(3, 3, 3, 1)) == 2
+    assert DamerauLevenshtein.distance("abc", "", weights=(1, 3, 1, 1)) == 9
+    assert DamerauLevenshtein.distance("", "abc", weights=(1, 3, 1, 1)) == 3
+    assert DamerauLevenshtein.similarity("abcd", "badc", weights=(3, 3, 3, 1)) == 10
+    assert DamerauLevenshtein.normalized_distance(
+        "abcd", "badc", weights=(3, 3, 3, 1)
+    ) == pytest.approx(2 / 12)
@@ -0,0 +1,50 @@
+"""Tests for the Editex distance metric."""
+
+from __future__ import annotations
+
+import pytest
+
+from crustyfuzz import utils
+from tests.distance.common import Editex
+
+
+def test_basic():
+    assert Editex.distance("", "") == 0
+    assert Editex.distance("test", "test") == 0
+    assert Editex.distance("cat", "hat") == 2
+    assert Editex.distance("Niall", "Neil") == 2
+    assert Editex.distance("aluminum", "Catalan") == 12
+    assert Editex.distance("ATCG", "TAGC") == 6
+
+
+def test_letter_groups():
+    # "d" and "t" sound alike, "d" and "k" do not
+    assert Editex.distance("bed", "bet") == 1
+    assert Editex.distance("bed", "bek") == 2
+    assert Editex.similarity("Smith", "Smyth") == 9
+    assert pytest.approx(Editex.normalized_similarity("Smith", "Smyth")) == 0.9
+
+
+def test_case_insensitive():
+    assert Editex.distance("SMITH", "smith") == 0
+
+
+def test_score_cutoff():
+    """
+    test whether score_cutoff works correctly
+    """
+    assert Editex.distance("aluminum", "Catalan", score_cutoff=12) == 12
+    assert Editex.distance("aluminum", "Catalan", score_cutoff=11) == 12
+    assert Editex.similarity("aluminum", "Catalan", score_cutoff=4) == 4
+    assert Editex.similarity("aluminum", "Catalan", score_cutoff=5) == 0
+
+
+def test_processor():
+    assert (
+        Editex.distance(
+            "new york mets",
+            "new YORK mets",
+            processor=utils.default_process,
+        )
+        == 0
+    )
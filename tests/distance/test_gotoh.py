@@ -0,0 +1,63 @@
+"""Tests for the Gotoh global alignment distance."""
+
+from __future__ import annotations
+
+from crustyfuzz import utils
+from tests.distance.common import Gotoh, Levenshtein
+
+
+def test_basic():
+    assert Gotoh.distance("", "") == 0
+    assert Gotoh.distance("test", "test") == 0
+    assert Gotoh.distance("aaaa", "bbbb") == 4
+
+
+def test_uniform_weights_match_levenshtein():
+    pairs = [
+        ("lewenstein", "levenshtein"),
+        ("total amount due", "total due"),
+        ("kitten", "sitting"),
+        ("", "abc"),
+    ]
+    for s1, s2 in pairs:
+        assert Gotoh.distance(s1, s2) == Levenshtein.distance(s1, s2)
+
+
+def test_affine_gaps():
+    s1 = "total amount due"
+    assert Gotoh.distance(s1, "total due", weights=(3, 1, 2)) == 9
+    # two gaps in "total" and one in "amount"
+    assert Gotoh.distance(s1, "ttl amnt due", weights=(3, 1, 2)) == 10
+    # gap_extend is limited to gap_open
+    assert Gotoh.distance(s1, "total due", weights=(1, 5, 1)) == 7
+
+
+def test_score_cutoff():
+    s1 = "total amount due"
+    assert Gotoh.distance(s1, "total due", weights=(3, 1, 2), score_cutoff=9) == 9
+    assert Gotoh.distance(s1, "total due", weights=(3, 1, 2), score_cutoff=8) == 9
+    assert Gotoh.distance(s1, "total due", weights=(3, 1, 2), score_cutoff=4) == 5
+
+
+def test_editops():
+    s1 = "ab xy cd"
+    s2 = "ab cd"
+    ops = Gotoh.editops(s1, s2, weights=(3, 1, 3))
+    assert [(op.tag, op.src_pos, op.dest_pos) for op in ops] == [
+        ("delete", 2, 2),
+        ("delete", 3, 2),
+        ("delete", 4, 2),
+    ]
+    assert ops.apply(s1, s2) == s2
+    assert Gotoh.opcodes(s1, s2, weights=(3, 1, 3)).apply(s1, s2) == s2
+
+
+def testCaseInsensitive():
+    assert (
+        Gotoh.distance(
+            "new york mets",
+            "new YORK mets",
+            processor=utils.default_process,
+        )
+        == 0
+    )
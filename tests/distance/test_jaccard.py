@@ -0,0 +1,51 @@
+"""Tests for the jaccard distance metrics."""
+
+from __future__ import annotations
+
+import pytest
+
+from crustyfuzz import utils
+from tests.distance.common import Jaccard
+
+
+def test_empty_string():
+    assert Jaccard.similarity("", "") == 1.0
+    assert Jaccard.similarity("a", "a") == 1.0
+    assert Jaccard.similarity("a", "b") == 0.0
+    assert Jaccard.similarity("", "ab") == 0.0
+
+
+def test_similar_strings():
+    assert Jaccard.similarity("abcd", "bcde") == 0.5
+    assert pytest.approx(Jaccard.similarity("night", "nacht")) == 1 / 7
+    assert Jaccard.similarity("abcd", "bcde", q=3) == pytest.approx(1 / 3)
+    assert Jaccard.similarity("abcd", "abcd", q=5) == 1.0
+
+
+def test_padding():
+    # ##a #ab ab# b## | ##a #a# a##
+    assert Jaccard.similarity("ab", "a", q=3, pad="#") == pytest.approx(1 / 6)
+
+
+def test_multiset():
+    assert Jaccard.similarity("aaaa", "aa") == 1.0
+    assert Jaccard.similarity("aaaa", "aa", multiset=True) == pytest.approx(1 / 3)
+
+
+def test_score_cutoff():
+    assert Jaccard.similarity("abcd", "bcde", score_cutoff=0.6) == 0
+    assert Jaccard.distance("abcd", "bcde", score_cutoff=0.4) == 1
+
+
+def test_invalid_q():
+    with pytest.raises(ValueError):
+        Jaccard.similarity("abcd", "bcde", q=0)
+
+
+def testCaseInsensitive():
+    assert (
+        Jaccard.similarity(
+            "new york mets", "new YORK mets", processor=utils.default_process
+        )
+        == 1.0
+    )
@@ -0,0 +1,58 @@
+"""Tests for the longest common substring metrics."""
+
+from __future__ import annotations
+
+from crustyfuzz import utils
+from crustyfuzz.distance import ScoreAlignment, lcs_str
+from tests.distance.common import LCSstr
+
+
+def test_basic():
+    assert LCSstr.distance("", "") == 0
+    assert LCSstr.distance("test", "test") == 0
+    assert LCSstr.distance("aaaa", "bbbb") == 4
+    assert LCSstr.similarity("abcxyzdef", "xyabcdef") == 3
+    assert LCSstr.similarity("part XJ-2291 rev b", "XJ-2291-B") == 7
+
+
+def test_score_cutoff():
+    assert LCSstr.similarity("abcxyzdef", "xyabcdef", score_cutoff=3) == 3
+    assert LCSstr.similarity("abcxyzdef", "xyabcdef", score_cutoff=4) == 0
+    assert LCSstr.distance("abcxyzdef", "xyabcdef", score_cutoff=6) == 6
+    assert LCSstr.distance("abcxyzdef", "xyabcdef", score_cutoff=5) == 6
+
+
+def test_alignment():
+    s1 = "part XJ-2291 rev b"
+    s2 = "XJ-2291-B"
+    res = lcs_str.alignment(s1, s2)
+    assert res == ScoreAlignment(7, 5, 12, 0, 7)
+    assert s1[res.src_start : res.src_end] == s2[res.dest_start : res.dest_end]
+
+    assert lcs_str.alignment(s1, s2, score_cutoff=8) is None
+    assert lcs_str.alignment(s1, None) is None
+    assert lcs_str.alignment("", "") == ScoreAlignment(0, 0, 0, 0, 0)
+
+
+def test_alignment_ties():
+    # "abc" and "def" have the same length, "abc" ends first in s2
+    res = lcs_str.alignment("defabc", "abcdef")
+    assert res == ScoreAlignment(3, 3, 6, 0, 3)
+
+
+def test_long_strings():
+    s1 = "x" * 1000 + "needle" + "y" * 1000
+    s2 = "z" * 500 + "needle" + "w" * 500
+    res = lcs_str.alignment(s1, s2)
+    assert res == ScoreAlignment(6, 1000, 1006, 500, 506)
+
+
+def testCaseInsensitive():
+    assert (
+        LCSstr.distance(
+            "new york mets",
+            "new YORK mets",
+            processor=utils.default_process,
+        )
+        == 0
+    )
@@ -2,8 +2,11 @@
 
 from __future__ import annotations
 
-from crustyfuzz import utils
-from crustyfuzz.distance import Editop, Opcode
+import pytest
+
+from crustyfuzz import process, utils
+from crustyfuzz.distance import Editop, Opcode, levenshtein
+from crustyfuzz.distance.levenshtein import CostTable
 from tests.distance.common import Levenshtein
 
 
@@ -40,6 +43,15 @@ def test_score_cutoff_overflow():
     assert Levenshtein.distance("", "", score_cutoff=2**63) == 0
 
 
+def test_zero_indel_weights():
+    """
+    a zero weight does not limit how far the alignment can leave the diagonal
+    """
+    assert Levenshtein.distance("a", "babbbab", weights=(0, 1, 1)) == 0
+    assert Levenshtein.distance("babbbab", "a", weights=(1, 0, 1)) == 0
+    assert Levenshtein.distance("a", "babbbab", weights=(1, 0, 1)) == 6
+
+
 def test_cross_type_matching():
     """
     strings should always be interpreted in the same way
@@ -60,6 +72,24 @@ def test_cross_type_matching():
     )
 
 
+def test_string_storage_kinds():
+    """
+    strings stored with one, two or four bytes per character are compared
+    by their characters
+    """
+    def ordinals(s):
+        return list(s) if isinstance(s, bytes) else [ord(c) for c in s]
+
+    strings = ["abcd", "abcé", "ab€d", "a😀cd", "😀€éd", b"abcd"]
+    for s1 in strings:
+        for s2 in strings:
+            for weights in [(1, 1, 1), (1, 1, 2), (3, 1, 2)]:
+                expected = Levenshtein.distance(
+                    ordinals(s1), ordinals(s2), weights=weights
+                )
+                assert Levenshtein.distance(s1, s2, weights=weights) == expected
+
+
 def test_word_error_rate():
     """
     it should be possible to use levenshtein to implement a word error rate
@@ -148,3 +178,87 @@ def testCaseInsensitive():
         )
         == 0
     )
+
+
+def test_cost_table():
+    """
+    costs missing from the table fall back to the weights
+    """
+    costs = CostTable({("a", "e"): 1, ("rn", "m"): 1}, insertions={"x": 5})
+    assert levenshtein.distance("rat", "ret", weights=(3, 3, 3), costs=costs) == 1
+    assert levenshtein.distance("ret", "rat", weights=(3, 3, 3), costs=costs) == 1
+    assert levenshtein.distance("corn", "com", weights=(3, 3, 3), costs=costs) == 1
+    assert levenshtein.distance("a", "ax", weights=(3, 3, 3), costs=costs) == 5
+    assert levenshtein.distance("ax", "a", weights=(3, 3, 3), costs=costs) == 3
+    assert levenshtein.distance("rot", "rut", weights=(3, 3, 3), costs=costs) == 3
+
+    directed = CostTable({("a", "e"): 1}, symmetric=False)
+    assert levenshtein.distance("a", "e", weights=(3, 3, 3), costs=directed) == 1
+    assert levenshtein.distance("e", "a", weights=(3, 3, 3), costs=directed) == 3
+
+    with pytest.raises(ValueError):
+        CostTable({("", "a"): 1})
+    with pytest.raises(ValueError):
+        CostTable(insertions={"ab": 1})
+
+
+def test_cost_table_from_matrix():
+    costs = CostTable.from_matrix("abc", [[0, 1, 4], [1, 0, 2], [4, 2, 0]])
+    assert levenshtein.distance("a", "b", weights=(5, 5, 5), costs=costs) == 1
+    assert levenshtein.distance("a", "c", weights=(5, 5, 5), costs=costs) == 4
+    assert levenshtein.distance("a", "d", weights=(5, 5, 5), costs=costs) == 5
+
+    with pytest.raises(ValueError):
+        CostTable.from_matrix("abc", [[0, 1], [1, 0]])
+
+
+def test_cost_table_presets():
+    ocr = CostTable.ocr()
+    assert levenshtein.distance("m0dern", "modem", weights=(2, 2, 2), costs=ocr) == 2
+    assert levenshtein.distance("m0dern", "modem", weights=(2, 2, 2)) == 6
+
+    qwerty = CostTable.qwerty()
+    assert levenshtein.distance("hrllo", "hello", weights=(2, 2, 2), costs=qwerty) == 1
+    assert levenshtein.distance("hpllo", "hello", weights=(2, 2, 2), costs=qwerty) == 2
+
+    both = ocr.merge(qwerty)
+    assert levenshtein.distance("hrll0", "hello", weights=(2, 2, 2), costs=both) == 2
+
+
+def test_cost_table_normalized():
+    costs = CostTable.ocr()
+    args = {"weights": (2, 2, 2), "costs": costs}
+    dist = levenshtein.distance("clear", "dear", **args)
+    sim = levenshtein.similarity("clear", "dear", **args)
+    assert dist + sim == 10
+    assert levenshtein.normalized_distance("clear", "dear", **args) == 0.1
+    assert levenshtein.normalized_similarity("clear", "dear", **args) == 0.9
+    assert (
+        levenshtein.normalized_similarity("clear", "dear", **args, score_cutoff=0.95)
+        == 0
+    )
+    assert levenshtein.normalized_similarity("", "", **args) == 1.0
+
+
+def test_cost_table_editops():
+    costs = CostTable.ocr()
+    ops = levenshtein.editops("modern", "modem", weights=(2, 2, 2), costs=costs)
+    assert ops.as_list() == [Editop("replace", 4, 4), Editop("delete", 5, 5)]
+    assert ops.apply("modern", "modem") == "modem"
+    opcodes = levenshtein.opcodes("modern", "modem", costs=costs)
+    assert opcodes.apply("modern", "modem") == "modem"
+
+    # weighted editops prefer cheap operations
+    ops = levenshtein.editops("ab", "ba", weights=(1, 1, 5))
+    assert sorted(op.tag for op in ops) == ["delete", "insert"]
+
+
+def test_cost_table_process():
+    costs = CostTable.ocr()
+    result = process.extract_one(
+        "m0dern",
+        ["modem", "madman"],
+        scorer=levenshtein.distance,
+        scorer_kwargs={"weights": (2, 2, 2), "costs": costs},
+    )
+    assert result == ("modem", 2, 0)
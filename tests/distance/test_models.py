@@ -0,0 +1,162 @@
+"""Tests for the serialization of the alignment models."""
+
+from __future__ import annotations
+
+import json
+
+import pytest
+
+from crustyfuzz.distance import (
+    Editop,
+    Editops,
+    MatchingBlock,
+    Opcode,
+    Opcodes,
+    ScoreAlignment,
+)
+from crustyfuzz.distance import levenshtein as Levenshtein
+
+
+def test_score_alignment_round_trip():
+    alignment = ScoreAlignment(83.5, 0, 5, 2, 7)
+    assert alignment.to_dict() == {
+        "score": 83.5,
+        "src_start": 0,
+        "src_end": 5,
+        "dest_start": 2,
+        "dest_end": 7,
+    }
+    assert ScoreAlignment.from_dict(alignment.to_dict()) == alignment
+    assert ScoreAlignment.from_json(alignment.to_json()) == alignment
+
+
+def test_matching_block_round_trip():
+    block = MatchingBlock(1, 2, 3)
+    assert block.to_dict() == {"a": 1, "b": 2, "size": 3}
+    assert MatchingBlock.from_dict(block.to_dict()) == block
+    assert MatchingBlock.from_json(block.to_json()) == block
+
+
+def test_editop_round_trip():
+    op = Editop("replace", 1, 2)
+    assert op.to_dict() == {"tag": "replace", "src_pos": 1, "dest_pos": 2}
+    assert Editop.from_dict(op.to_dict()) == op
+    assert Editop.from_json(op.to_json()) == op
+
+
+def test_opcode_round_trip():
+    op = Opcode("insert", 1, 1, 1, 3)
+    assert op.to_dict() == {
+        "tag": "insert",
+        "src_start": 1,
+        "src_end": 1,
+        "dest_start": 1,
+        "dest_end": 3,
+    }
+    assert Opcode.from_dict(op.to_dict()) == op
+    assert Opcode.from_json(op.to_json()) == op
+
+
+def test_editops_round_trip():
+    ops = Levenshtein.editops("qabxcd", "abycdf")
+    data = ops.to_dict()
+    assert data["src_len"] == 6
+    assert data["dest_len"] == 6
+    assert data["editops"] == [op.to_dict() for op in ops]
+    assert Editops.from_dict(data) == ops
+    assert Editops.from_json(json.dumps(data)) == ops
+
+
+def test_opcodes_round_trip():
+    ops = Levenshtein.opcodes("qabxcd", "abycdf")
+    data = ops.to_dict()
+    assert data["src_len"] == 6
+    assert data["dest_len"] == 6
+    assert data["opcodes"] == [op.to_dict() for op in ops.as_list()]
+    assert Opcodes.from_dict(data) == ops
+    assert Opcodes.from_json(ops.to_json()) == ops
+
+
+def test_editops_replay():
+    """
+    stored edit scripts can be replayed with apply
+    """
+    s1 = "Lorem ipsum."
+    s2 = "XYZLorem ABC iPsum"
+    stored = Levenshtein.editops(s1, s2).to_json()
+    assert Editops.from_json(stored).apply(s1, s2) == s2
+    stored = Levenshtein.opcodes(s1, s2).to_json()
+    assert Opcodes.from_json(stored).apply(s1, s2) == s2
+
+
+def test_from_dict_missing_key():
+    with pytest.raises(KeyError):
+        MatchingBlock.from_dict({"a": 1, "b": 2})
+    with pytest.raises(KeyError):
+        Editops.from_dict({"src_len": 1, "dest_len": 1})
+
+
+def test_from_dict_invalid_editops():
+    data = Levenshtein.editops("kitten", "sitting").to_dict()
+
+    out_of_bounds = json.loads(json.dumps(data))
+    out_of_bounds["editops"][0]["src_pos"] = 100
+    with pytest.raises(ValueError):
+        Editops.from_dict(out_of_bounds)
+
+    bogus = json.loads(json.dumps(data))
+    bogus["editops"][0]["tag"] = "bogus"
+    with pytest.raises(ValueError):
+        Editops.from_json(json.dumps(bogus))
+    with pytest.raises(ValueError):
+        Editop.from_dict({"tag": "bogus", "src_pos": 0, "dest_pos": 0})
+
+    unordered = json.loads(json.dumps(data))
+    unordered["editops"].reverse()
+    with pytest.raises(ValueError):
+        Editops.from_dict(unordered)
+
+    wrong_len = dict(data, dest_len=8)
+    with pytest.raises(ValueError):
+        Editops.from_dict(wrong_len)
+
+
+def test_from_dict_invalid_opcodes():
+    data = Levenshtein.opcodes("kitten", "sitting").to_dict()
+
+    gap = json.loads(json.dumps(data))
+    del gap["opcodes"][1]
+    with pytest.raises(ValueError):
+        Opcodes.from_dict(gap)
+
+    bogus = json.loads(json.dumps(data))
+    bogus["opcodes"][0]["tag"] = "bogus"
+    with pytest.raises(ValueError):
+        Opcodes.from_json(json.dumps(bogus))
+
+    with pytest.raises(ValueError):
+        Opcode.from_dict(
+            {
+                "tag": "equal",
+                "src_start": 0,
+                "src_end": 2,
+                "dest_start": 0,
+                "dest_end": 1,
+            }
+        )
+
+    wrong_len = dict(data, src_len=100)
+    with pytest.raises(ValueError):
+        Opcodes.from_dict(wrong_len)
+
+
+def test_unpacking():
+    a, b, size = MatchingBlock(1, 2, 3)
+    assert (a, b, size) == (1, 2, 3)
+    assert tuple(Opcode("insert", 1, 1, 1, 3)) == ("insert", 1, 1, 1, 3)
+
+    ops = Levenshtein.opcodes("qabxcd", "abycdf")
+    assert list(ops) == ops.as_list()
+    for tag, i1, i2, j1, j2 in ops:
+        assert tag in ("equal", "replace", "insert", "delete")
+        assert i1 <= i2 and j1 <= j2
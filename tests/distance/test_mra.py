@@ -0,0 +1,41 @@
+"""Tests for the Match Rating Approach distance metric."""
+
+from __future__ import annotations
+
+import pytest
+
+from tests.distance.common import MRA
+
+
+def test_basic():
+    assert MRA.similarity("", "") == 6
+    assert MRA.similarity("Smith", "Smith") == 6
+    assert MRA.similarity("Byrne", "Boern") == 5
+    assert MRA.similarity("Catherine", "Kathryn") == 4
+    assert MRA.similarity("Smith", "Schmidt") == 2
+    assert MRA.distance("Smith", "Schmidt") == 4
+
+
+def test_codex():
+    # vowels after the first letter, doubled letters and case are ignored
+    assert MRA.similarity("Abbott", "ABT") == 6
+    assert MRA.similarity("Smith", "smyth") == 5
+    # long names are shortened to their first and last three letters
+    assert MRA.similarity("Bartholomew", "Bart Lmw") == 6
+
+
+def test_not_comparable():
+    assert MRA.similarity("", "Smith") == 0
+    assert MRA.similarity("Al", "Bartholomew") == 0
+    assert MRA.normalized_similarity("Al", "Bartholomew") == 0
+    assert MRA.normalized_distance("Al", "Bartholomew") == 1
+
+
+def test_score_cutoff():
+    """
+    test whether score_cutoff works correctly
+    """
+    assert MRA.similarity("Byrne", "Boern", score_cutoff=5) == 5
+    assert MRA.similarity("Byrne", "Boern", score_cutoff=6) == 0
+    assert MRA.distance("Smith", "Schmidt", score_cutoff=3) == 4
+    assert pytest.approx(MRA.normalized_similarity("Byrne", "Boern")) == 5 / 6
@@ -88,3 +88,19 @@ def testCaseInsensitive():
         )
         == 0
     )
+
+
+def test_weights():
+    """
+    transpositions can be cheaper than substitutions
+    """
+    assert OSA.distance("ab", "ba", weights=(1, 1, 2, 1)) == 1
+    assert OSA.distance("ab", "ba", weights=(1, 1, 2, 3)) == 2
+    assert OSA.distance("ca", "abc", weights=(1, 1, 1, 1)) == 3
+    assert OSA.distance("abcd", "badc", weights=(3, 3, 3, 1)) == 2
+    assert OSA.distance("abc", "", weights=(1, 3, 1, 1)) == 9
+    assert OSA.distance("", "abc", weights=(1, 3, 1, 1)) == 3
+    assert OSA.similarity("abcd", "badc", weights=(3, 3, 3, 1)) == 10
+    assert isclose(
+        OSA.normalized_distance("abcd", "badc", weights=(3, 3, 3, 1)), 2 / 12
+    )
@@ -0,0 +1,22 @@
+"""Tests for the overlap distance metrics."""
+
+from __future__ import annotations
+
+import pytest
+
+from tests.distance.common import Overlap
+
+
+def test_empty_string():
+    assert Overlap.similarity("", "") == 1.0
+    assert Overlap.similarity("", "ab") == 0.0
+
+
+def test_similar_strings():
+    assert Overlap.similarity("abcd", "bc") == 1.0
+    assert pytest.approx(Overlap.similarity("abcd", "bcde")) == 2 / 3
+    assert Overlap.similarity("night", "nacht") == 0.25
+
+
+def test_padding():
+    assert Overlap.similarity("abcd", "bc", q=2, pad="#") == pytest.approx(1 / 3)
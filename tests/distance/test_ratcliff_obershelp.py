@@ -0,0 +1,129 @@
+"""Tests for the Ratcliff/Obershelp metrics against difflib."""
+
+from __future__ import annotations
+
+import random
+from difflib import SequenceMatcher
+
+from crustyfuzz import utils
+from crustyfuzz.distance import MatchingBlock, ratcliff_obershelp
+from tests.distance.common import RatcliffObershelp
+
+
+def difflib_blocks(s1, s2, isjunk=None, autojunk=True):
+    matcher = SequenceMatcher(isjunk, s1, s2, autojunk)
+    return [tuple(block) for block in matcher.get_matching_blocks()]
+
+
+def difflib_opcodes(s1, s2, isjunk=None, autojunk=True):
+    return SequenceMatcher(isjunk, s1, s2, autojunk).get_opcodes()
+
+
+def blocks(s1, s2, **kwargs):
+    matching_blocks = ratcliff_obershelp.matching_blocks(s1, s2, **kwargs)
+    return [tuple(block) for block in matching_blocks]
+
+
+def opcodes(s1, s2, **kwargs):
+    return [tuple(op) for op in ratcliff_obershelp.opcodes(s1, s2, **kwargs)]
+
+
+def test_basic():
+    assert RatcliffObershelp.distance("", "") == 0
+    assert RatcliffObershelp.similarity("test", "test") == 8
+    assert RatcliffObershelp.distance("aaaa", "bbbb") == 8
+    assert RatcliffObershelp.normalized_similarity("abcd", "bcde") == 0.75
+    assert RatcliffObershelp.normalized_similarity("", "") == 1.0
+
+
+def test_score_cutoff():
+    assert RatcliffObershelp.similarity("abcd", "bcde", score_cutoff=6) == 6
+    assert RatcliffObershelp.similarity("abcd", "bcde", score_cutoff=7) == 0
+    assert RatcliffObershelp.distance("abcd", "bcde", score_cutoff=2) == 2
+    assert RatcliffObershelp.distance("abcd", "bcde", score_cutoff=1) == 2
+    assert RatcliffObershelp.normalized_similarity(
+        "abcd", "bcde", score_cutoff=0.8
+    ) == 0.0
+
+
+def test_matching_blocks():
+    assert ratcliff_obershelp.matching_blocks("abxcd", "abcd") == [
+        MatchingBlock(0, 0, 2),
+        MatchingBlock(3, 2, 2),
+        MatchingBlock(5, 4, 0),
+    ]
+    assert blocks("", "") == difflib_blocks("", "")
+
+
+def test_opcodes():
+    s1 = "qabxcd"
+    s2 = "abycdf"
+    assert opcodes(s1, s2) == difflib_opcodes(s1, s2)
+    ops = ratcliff_obershelp.opcodes(s1, s2)
+    assert ops.apply(s1, s2) == s2
+    assert ops.as_matching_blocks() == ratcliff_obershelp.matching_blocks(s1, s2)
+
+
+def test_isjunk():
+    def isjunk(x):
+        return x == " "
+
+    s1 = " abcd"
+    s2 = "abcd abcd"
+    assert blocks(s1, s2) == difflib_blocks(s1, s2)
+    assert blocks(s1, s2, isjunk=isjunk) == difflib_blocks(s1, s2, isjunk)
+    assert ratcliff_obershelp.normalized_similarity(
+        s1, s2, isjunk=isjunk
+    ) == SequenceMatcher(isjunk, s1, s2).ratio()
+
+
+def test_autojunk():
+    s1 = "ccc"
+    s2 = "ab" + "c" * 200
+    assert blocks(s1, s2) == difflib_blocks(s1, s2)
+    assert blocks(s1, s2, autojunk=False) == difflib_blocks(s1, s2, autojunk=False)
+    assert blocks(s1, s2) != blocks(s1, s2, autojunk=False)
+
+
+def test_asymmetric():
+    s1 = "abcd abcd"
+    s2 = " abcd"
+    isjunk = str.isspace
+    assert blocks(s1, s2, isjunk=isjunk) == difflib_blocks(s1, s2, isjunk)
+    assert blocks(s2, s1, isjunk=isjunk) == difflib_blocks(s2, s1, isjunk)
+
+
+def test_lists():
+    s1 = ["the", "quick", "brown", "fox"]
+    s2 = ["the", "slow", "brown", "fox", "jumps"]
+    assert blocks(s1, s2) == difflib_blocks(s1, s2)
+    assert opcodes(s1, s2) == difflib_opcodes(s1, s2)
+
+
+def test_random_against_difflib():
+    rng = random.Random(42)
+    for _ in range(300):
+        alphabet = "abcde "[: rng.randint(2, 6)]
+        s1 = "".join(rng.choices(alphabet, k=rng.randint(0, 40)))
+        s2 = "".join(rng.choices(alphabet, k=rng.randint(0, 300)))
+        isjunk = rng.choice([None, str.isspace, lambda x: x == "a"])
+        autojunk = rng.choice([True, False])
+
+        kwargs = {"isjunk": isjunk, "autojunk": autojunk}
+        assert blocks(s1, s2, **kwargs) == difflib_blocks(s1, s2, isjunk, autojunk)
+        assert opcodes(s1, s2, **kwargs) == difflib_opcodes(s1, s2, isjunk, autojunk)
+        assert (
+            ratcliff_obershelp.normalized_similarity(s1, s2, **kwargs)
+            == SequenceMatcher(isjunk, s1, s2, autojunk).ratio()
+        )
+
+
+def testCaseInsensitive():
+    assert (
+        RatcliffObershelp.distance(
+            "new york mets",
+            "new YORK mets",
+            processor=utils.default_process,
+        )
+        == 0
+    )
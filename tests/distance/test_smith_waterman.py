@@ -0,0 +1,69 @@
+"""Tests for the Smith-Waterman local alignment."""
+
+from __future__ import annotations
+
+from crustyfuzz import utils
+from crustyfuzz.distance import ScoreAlignment, smith_waterman
+from tests.distance.common import SmithWaterman
+
+
+def test_basic():
+    assert SmithWaterman.similarity("", "") == 0
+    assert SmithWaterman.similarity("test", "test") == 4
+    assert SmithWaterman.similarity("aaaa", "bbbb") == 0
+    assert SmithWaterman.similarity("invoice no A1B2-77 total", "A1B2-77") == 7
+    assert SmithWaterman.distance("invoice no A1B2-77 total", "A1B2-77") == 0
+
+
+def test_weights():
+    s1 = "ref A1B2-77"
+    s2 = "ref A1 B2-77"
+    assert SmithWaterman.similarity(s1, s2) == 10
+    assert SmithWaterman.similarity(s1, s2, weights=(2, 1, 2, 1)) == 20
+    # the gap costs more than the 5 matches behind it
+    assert SmithWaterman.similarity(s1, s2, weights=(1, 1, 6, 6)) == 6
+    # a mismatch is cheaper than a gap
+    assert SmithWaterman.similarity("abXcd", "abYcd", weights=(1, 1, 3, 1)) == 3
+
+
+def test_score_cutoff():
+    s1 = "invoice no A1B2-77 total"
+    assert SmithWaterman.similarity(s1, "A1B2-77", score_cutoff=7) == 7
+    assert SmithWaterman.similarity(s1, "A1B2-77", score_cutoff=8) == 0
+    assert SmithWaterman.distance(s1, "A1C2-77") == 2
+    assert SmithWaterman.distance(s1, "A1C2-77", score_cutoff=2) == 2
+    assert SmithWaterman.distance(s1, "A1C2-77", score_cutoff=1) == 2
+
+
+def test_alignment():
+    s1 = "invoice no A1B2-77 total"
+    res = smith_waterman.alignment(s1, "A1B2-77")
+    assert res == ScoreAlignment(7, 11, 18, 0, 7)
+    assert smith_waterman.alignment(s1, "A1B2-77", score_cutoff=8) is None
+    assert smith_waterman.alignment(s1, None) is None
+
+
+def test_editops():
+    s1 = "ref A1B2-77"
+    s2 = "A1 B2-77"
+    weights = (2, 1, 2, 1)
+    res = smith_waterman.alignment(s1, s2, weights=weights)
+    assert res == ScoreAlignment(12, 4, 11, 0, 8)
+
+    ops = SmithWaterman.editops(s1, s2, weights=weights)
+    assert [(op.tag, op.src_pos, op.dest_pos) for op in ops] == [("insert", 2, 2)]
+    src = s1[res.src_start : res.src_end]
+    dest = s2[res.dest_start : res.dest_end]
+    assert ops.apply(src, dest) == dest
+    assert SmithWaterman.opcodes(s1, s2, weights=weights).apply(src, dest) == dest
+
+
+def testCaseInsensitive():
+    assert (
+        SmithWaterman.distance(
+            "new york mets",
+            "new YORK mets",
+            processor=utils.default_process,
+        )
+        == 0
+    )
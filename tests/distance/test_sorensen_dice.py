@@ -0,0 +1,27 @@
+"""Tests for the sorensen_dice distance metrics."""
+
+from __future__ import annotations
+
+import pytest
+
+from tests.distance.common import SorensenDice
+
+
+def test_empty_string():
+    assert SorensenDice.similarity("", "") == 1.0
+    assert SorensenDice.similarity("", "ab") == 0.0
+
+
+def test_similar_strings():
+    assert SorensenDice.similarity("abcd", "bcd") == 0.8
+    assert SorensenDice.similarity("night", "nacht") == 0.25
+    assert SorensenDice.similarity("abcd", "bcde", q=1) == 0.75
+
+
+def test_padding():
+    # ##a #ab ab# b## | ##a #a# a##
+    assert SorensenDice.similarity("ab", "a", q=3, pad="#") == pytest.approx(2 / 7)
+
+
+def test_multiset():
+    assert SorensenDice.similarity("aaaa", "aa", multiset=True) == 0.5
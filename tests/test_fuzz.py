@@ -330,6 +330,29 @@ def test_invalid_input(scorer):
         scorer(1, 1, catch_exceptions=True)
 
 
+@pytest.mark.parametrize("scorer", scorers)
+def test_large_int_elements(scorer):
+    """
+    ints that do not fit into a character are hashed and the token based
+    scorers have to handle them as non whitespace
+    """
+    assert scorer([1, 2**40], [1, 2**40]) == 100
+    assert 0 <= scorer([1, 2**40], [1, 3]) < 100
+
+
+@pytest.mark.parametrize("scorer", scorers)
+def test_string_storage_kinds(scorer):
+    """
+    strings stored with one, two or four bytes per character are compared
+    by their characters
+    """
+    strings = ["new york mets", "new yörk mets", "new y€rk mets", "new 😀 mets"]
+    for s1 in strings:
+        for s2 in strings:
+            expected = scorer([ord(c) for c in s1], [ord(c) for c in s2])
+            assert pytest.approx(scorer(s1, s2)) == expected
+
+
 @pytest.mark.parametrize("scorer", scorers)
 def test_array(scorer):
     """
@@ -450,3 +473,70 @@ def testIssue257():
     assert pytest.approx(score) == 98.46153846153847
     score = fuzz.partial_ratio(s2, s1)
     assert pytest.approx(score) == 98.46153846153847
+
+
+def test_monge_elkan():
+    from crustyfuzz.distance import jaro_winkler, levenshtein
+
+    assert fuzz_cf.monge_elkan("new york mets", "mets new york") == 100
+    assert fuzz_cf.monge_elkan("", "new york") == 0
+    assert fuzz_cf.monge_elkan(None, "new york") == 0
+
+    score = fuzz_cf.monge_elkan(
+        "jon smiht", "john smith", inner=jaro_winkler.similarity
+    )
+    expected = (
+        jaro_winkler.similarity("jon", "john")
+        + jaro_winkler.similarity("smiht", "smith")
+    ) * 50
+    assert pytest.approx(score) == expected
+    assert fuzz_cf.monge_elkan(
+        "jon smiht", "john smith", inner=jaro_winkler.similarity, score_cutoff=99
+    ) == 0
+
+    # distances are turned into similarities
+    assert pytest.approx(
+        fuzz_cf.monge_elkan(
+            "jon smiht", "john smith", inner=levenshtein.normalized_distance
+        )
+    ) == fuzz_cf.monge_elkan(
+        "jon smiht", "john smith", inner=levenshtein.normalized_similarity
+    )
+
+    with pytest.raises(ValueError):
+        fuzz_cf.monge_elkan("jon", "john", inner=levenshtein.distance)
+
+
+def test_monge_elkan_symmetric():
+    s1, s2 = "new york", "york new jersey"
+    assert fuzz_cf.monge_elkan(s1, s2, symmetric=False) == 100
+    assert fuzz_cf.monge_elkan(s2, s1, symmetric=False) < 100
+    assert fuzz_cf.monge_elkan(s1, s2) == fuzz_cf.monge_elkan(s2, s1)
+
+
+def test_monge_elkan_callable():
+    def prefix(s1, s2):
+        return 1.0 if s1[:1] == s2[:1] else 0.0
+
+    assert fuzz_cf.monge_elkan("nw yrk", "new york", inner=prefix) == 100
+    assert fuzz_cf.monge_elkan("ab cd", "ax", inner=prefix) == 75
+
+
+def test_monge_elkan_sequences():
+    assert fuzz_cf.monge_elkan([1, 2**40], [1, 2**40]) == 100
+    assert fuzz_cf.monge_elkan([1, 2**40], [1, 3]) < 100
+
+    # tokens are passed to callables as strings
+    tokens = []
+
+    def record(s1, s2):
+        tokens.append((s1, s2))
+        return 1.0
+
+    assert fuzz_cf.monge_elkan([97, 32, 98], [98], inner=record) == 100
+    assert ("a", "b") in tokens
+
+    with pytest.raises(ValueError):
+        fuzz_cf.monge_elkan([1, 2**40], [1, 3], inner=lambda a, b: 1.0)
+    with pytest.raises(ValueError):
+        fuzz_cf.monge_elkan([0xD800], ["a"], inner=lambda a, b: 1.0)
@@ -0,0 +1,119 @@
+"""Tests for the phonetic encoders and the phonetic scorer."""
+
+from __future__ import annotations
+
+import pytest
+
+from crustyfuzz import phonetic, process
+from crustyfuzz.distance import jaro_winkler, levenshtein
+from crustyfuzz.utils import default_process
+
+
+def test_soundex():
+    assert phonetic.soundex("Robert") == "R163"
+    assert phonetic.soundex("Rupert") == "R163"
+    assert phonetic.soundex("Tymczak") == "T522"
+    assert phonetic.soundex("") == ""
+
+
+def test_metaphone():
+    assert phonetic.metaphone("Smith") == "SM0"
+    assert phonetic.metaphone("Smyth") == "SM0"
+    assert phonetic.metaphone("Knight") == "NT"
+
+
+def test_double_metaphone():
+    assert phonetic.double_metaphone("Smith") == ("SM0", "XMT")
+    assert phonetic.double_metaphone("Schmidt") == ("XMT", "SMT")
+    assert phonetic.double_metaphone("Maclaverty") == ("MKLF", "")
+
+
+def test_nysiis():
+    assert phonetic.nysiis("Mitchell") == "MATCAL"
+    assert phonetic.nysiis("O'Daniel") == "ODANAL"
+
+
+def test_score_without_metric():
+    assert phonetic.score("Smith", "Smyth") == 1.0
+    assert phonetic.score("Robert", "Rupert", encoder="soundex") == 1.0
+    assert phonetic.score("Robert", "Rubin", encoder="soundex") == 0.0
+    assert phonetic.score("Smith", None) == 0.0
+    assert phonetic.score("Smith", "Smyth", score_cutoff=1.0) == 1.0
+
+
+def test_score_uses_alternate_codes():
+    assert phonetic.score("Smith", "Schmidt") == 0.0
+    assert phonetic.score("Smith", "Schmidt", encoder="double_metaphone") == 1.0
+
+
+def test_score_with_metric():
+    # NYSIIS codes: MATCAL and MACAL
+    assert phonetic.score(
+        "Mitchell", "Michel", encoder="nysiis", metric=levenshtein.distance
+    ) == levenshtein.distance("MATCAL", "MACAL")
+    assert phonetic.score(
+        "Mitchell", "Michel", encoder="nysiis", metric=jaro_winkler.similarity
+    ) == pytest.approx(jaro_winkler.similarity("MATCAL", "MACAL"))
+    assert phonetic.score(
+        "Mitchell",
+        "Michel",
+        encoder="nysiis",
+        metric=levenshtein.distance,
+        weights=(1, 1, 2),
+    ) == levenshtein.distance("MATCAL", "MACAL", weights=(1, 1, 2))
+
+
+def test_score_picks_best_code_combination():
+    # the primary codes SM0 and XMT differ in two characters, while the
+    # alternate code of Smith matches the primary code of Schmidt
+    assert (
+        phonetic.score(
+            "Smith", "Schmidt", encoder="double_metaphone", metric=levenshtein.distance
+        )
+        == 0
+    )
+    assert (
+        phonetic.score(
+            "Smith",
+            "Schmidt",
+            encoder="double_metaphone",
+            metric=levenshtein.normalized_similarity,
+        )
+        == 1.0
+    )
+
+
+def test_score_processor():
+    score = phonetic.score(
+        "  smith", "SMYTH!", processor=default_process, encoder="soundex"
+    )
+    assert score == 1.0
+
+
+def test_score_invalid_encoder():
+    with pytest.raises(ValueError, match="unknown encoder"):
+        phonetic.score("Smith", "Smyth", encoder="caverphone")
+
+
+def test_score_in_process():
+    choices = ["Smyth", "Schmidt", "Jones"]
+    assert process.extract_one("Smith", choices, scorer=phonetic.score) == (
+        "Smyth",
+        1.0,
+        0,
+    )
+    # Metaphone codes: SM0, SM0, SKMTT and JNS
+    assert process.extract(
+        "Smith",
+        choices,
+        scorer=phonetic.score,
+        scorer_kwargs={"metric": levenshtein.normalized_similarity},
+        limit=2,
+    ) == [("Smyth", 1.0, 0), ("Schmidt", pytest.approx(0.4), 1)]
+    assert process.extract_one(
+        "Smith",
+        choices,
+        scorer=phonetic.score,
+        scorer_kwargs={"metric": levenshtein.similarity},
+        score_cutoff=4,
+    ) is None
@@ -5,7 +5,7 @@ from rapidfuzz import process as process_cpp
 
 from crustyfuzz import fuzz
 from crustyfuzz import process as process_rs
-from crustyfuzz.distance import levenshtein
+from crustyfuzz.distance import indel, lcs_seq, levenshtein
 from crustyfuzz.utils import default_process
 
 
@@ -601,6 +601,59 @@ def test_extract_one_use_first_match(scorer):
     )
 
 
+@pytest.mark.parametrize(
+    "scorer",
+    [
+        getattr(module, name)
+        for module in (levenshtein, indel, lcs_seq)
+        for name in (
+            "distance",
+            "similarity",
+            "normalized_distance",
+            "normalized_similarity",
+        )
+    ],
+)
+def test_extract_batched_scorers(scorer):
+    """
+    Short choices are scored in batches, which has to give the same results
+    as calling the scorer for every choice.
+    """
+    choices = [
+        "new york mets",
+        "",
+        None,
+        "new YORK mets vs atlanta braves at the city field stadium in queens",
+        "atlanta braves",
+        "x" * 64,
+        "y" * 65,
+        ["n", "e", "w"],
+    ]
+    expected = [
+        (choice, scorer("new york", choice), i)
+        for i, choice in enumerate(choices)
+        if choice is not None
+    ]
+    assert list(process.extract_iter("new york", choices, scorer=scorer)) == expected
+    results = process.extract("new york", choices, scorer=scorer, limit=None)
+    assert sorted(results, key=lambda res: res[2]) == expected
+
+
+def test_extract_batched_storage_kinds():
+    """
+    Batched choices are grouped by the width of their storage, which must not
+    change the results.
+    """
+    choices = ["new york", "new yörk", "new y€rk", "new 😀rk", b"new york", "yörk"]
+    for query in ["new york", "new y€rk", "😀"]:
+        expected = [
+            (choice, levenshtein.distance(query, choice), i)
+            for i, choice in enumerate(choices)
+        ]
+        results = process.extract_iter(query, choices, scorer=levenshtein.distance)
+        assert list(results) == expected
+
+
 @pytest.mark.skip(reason="cdist not implemented yet")
 @pytest.mark.parametrize("scorer", [fuzz.ratio, fuzz.WRatio, custom_scorer])
 def test_cdist_empty_seq(scorer):
@@ -1,5 +1,7 @@
 """Tests for the utils module."""
 
+import threading
+
 from crustyfuzz import utils
 
 
@@ -26,3 +28,54 @@ def test_full_process() -> None:
 
     for string, proc_string in zip(mixed_strings, mixed_strings_proc):
         assert utils.default_process(string) == proc_string
+
+
+class CollidingHashable:
+    def __init__(self, value):
+        self._value = value
+
+    def __eq__(self, other):
+        return isinstance(other, CollidingHashable) and self._value == other._value
+
+    def __hash__(self):
+        return 42
+
+
+def test_exact_comparison() -> None:
+    """Elements are compared using Python equality in exact comparison mode."""
+    from crustyfuzz.distance import levenshtein
+
+    s1 = [CollidingHashable("a"), CollidingHashable("b")]
+    s2 = [CollidingHashable("a"), CollidingHashable("c")]
+    large = [2**32 + 5, 2**40]
+    small = [5, 0]
+
+    assert levenshtein.distance(s1, s2) == 0
+    with utils.exact_comparison():
+        assert utils.get_exact_comparison()
+        assert levenshtein.distance(s1, s2) == 1
+        assert levenshtein.distance(large, small) == 2
+        assert levenshtein.distance(large, list(large)) == 0
+        assert levenshtein.distance(("a", "b"), "ab") == 0
+        assert levenshtein.distance("abc", "abd") == 1
+    assert not utils.get_exact_comparison()
+
+
+def test_exact_comparison_is_context_local() -> None:
+    """The exact comparison mode does not leak into other threads."""
+    seen = []
+    with utils.exact_comparison():
+        thread = threading.Thread(
+            target=lambda: seen.append(utils.get_exact_comparison())
+        )
+        thread.start()
+        thread.join()
+        assert utils.get_exact_comparison()
+    assert seen == [False]
+
+
+def test_large_int_not_truncated() -> None:
+    """Large ints do not wrap around to small ordinals."""
+    from crustyfuzz.distance import levenshtein
+
+    assert levenshtein.distance([2**32 + 5], [5]) == 1